@@ -2,13 +2,14 @@ use anchor_client::{Client, Cluster};
 use anyhow::Result;
 use gfx_solana_utils::{load_keypair, AnchorClientErrorExt, Duplicate};
 use gfx_swap::{
-    ConstantProductCurve, DerivedAccountIdentifier, ErrorCode, Fees, LPMint, Pool, SwapCurve,
+    ConstantProductCurve, DerivedAccountIdentifier, ErrorCode, Fees, FlashFees, GlobalConfig,
+    LPMint, Pool, PoolRegistry, PoolRegistryPage, SwapCurve, REGISTRY_PAGE_CAPACITY,
 };
 use solana_sdk::{
-    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signer, system_program, sysvar,
-    transaction::Transaction,
+    commitment_config::CommitmentConfig, instruction::AccountMeta, pubkey::Pubkey,
+    signature::Signer, system_program, sysvar, transaction::Transaction,
 };
-use spl_associated_token_account::{create_associated_token_account, get_associated_token_address};
+use spl_associated_token_account::get_associated_token_address;
 use spl_token::instruction::transfer;
 use structopt::StructOpt;
 
@@ -29,6 +30,10 @@ struct Opt {
 
     #[structopt(long, env)]
     token_b: Pubkey,
+
+    // seconds a fee or curve change has to wait before it can be executed
+    #[structopt(long, env, default_value = "86400")]
+    config_delay: i64,
 }
 
 fn main() -> Result<()> {
@@ -52,20 +57,65 @@ fn main() -> Result<()> {
     let (pool, pool_bump) = Pool::get_address_with_bump(&program_id, &seed.to_bytes());
     let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&program_id, &seed.to_bytes());
 
+    println!("Looking for a registry page ...");
+    let (registry, registry_bump) = PoolRegistry::get_address_with_bump(&program_id, &[]);
+    let page_count = program
+        .account::<PoolRegistry>(registry)
+        .map(|registry| registry.page_count)
+        .unwrap_or(0);
+    let last_page = page_count
+        .checked_sub(1)
+        .map(|page| PoolRegistryPage::get_page_address(&program_id, page));
+    let registry_page = match last_page {
+        Some(page)
+            if program.account::<PoolRegistryPage>(page)?.entries.len()
+                < REGISTRY_PAGE_CAPACITY =>
+        {
+            page
+        }
+        _ => {
+            println!("Adding registry page {} ...", page_count);
+            let (page, page_bump) =
+                PoolRegistryPage::get_address_with_bump(&program_id, &page_count.to_le_bytes());
+            program
+                .request()
+                .accounts(gfx_swap::accounts::AddRegistryPage {
+                    registry,
+                    registry_page: page,
+                    payer: admin.pubkey(),
+                    rent: sysvar::rent::id(),
+                    system_program: system_program::id(),
+                })
+                .args(gfx_swap::instruction::AddRegistryPage {
+                    registry_bump,
+                    page_bump,
+                })
+                .signer(&admin)
+                .send()
+                .map_err(|e| e.canonicalize::<ErrorCode>())?;
+            page
+        }
+    };
+
     println!("Creating the LP pool ...");
     let tx = program
         .request()
         .accounts(gfx_swap::accounts::CreatePool {
             pool: pool,
+            global_config: GlobalConfig::get_address(&program_id, &[]),
 
             lp_token_mint: lp_mint,
             fee_vault: get_associated_token_address(&pool, &lp_mint),
 
             token_a_mint: opt.token_a,
             token_a_vault: get_associated_token_address(&pool, &opt.token_a),
+            token_a_program: spl_token::id(),
 
             token_b_mint: opt.token_b,
             token_b_vault: get_associated_token_address(&pool, &opt.token_b),
+            token_b_program: spl_token::id(),
+
+            registry_page,
 
             admin: admin.pubkey(),
 
@@ -89,6 +139,13 @@ fn main() -> Result<()> {
                 host_fee_denominator: 0,
             },
             swap_curve: SwapCurve::ConstantProductCurve(ConstantProductCurve::new()),
+            config_delay: opt.config_delay,
+            flash_fees: FlashFees {
+                flash_fee_numerator: 9,
+                flash_fee_denominator: 10000, // 0.09% flash loan fee
+                owner_flash_fee_numerator: 1,
+                owner_flash_fee_denominator: 10000, // 0.01% flash loan fee to us
+            },
         })
         .signer(&admin)
         .send()
@@ -107,8 +164,10 @@ fn main() -> Result<()> {
     program
         .request()
         .accounts(gfx_swap::accounts::MintLPTo {
-            admin: user_wallet.pubkey(),
+            admin: admin.pubkey(),
+            payer: admin.pubkey(),
             pool,
+            recipient: user_wallet.pubkey(),
             recipient_ata: get_associated_token_address(&user_wallet.pubkey(), &lp_mint),
             lp_token_mint: lp_mint,
             token_program: spl_token::id(),
@@ -116,12 +175,9 @@ fn main() -> Result<()> {
             rent: sysvar::rent::id(),
             associated_token_program: spl_associated_token_account::id(),
         })
+        .accounts(AccountMeta::new_readonly(admin.pubkey(), true))
         .args(gfx_swap::instruction::MintLpTo { n: 1 })
-        .instruction(
-            // create the ata account of the lp token for the user
-            create_associated_token_account(&admin.pubkey(), &user_wallet.pubkey(), &lp_mint),
-        )
-        .signer(&user_wallet)
+        .signer(&admin)
         .send()
         .map_err(|e| e.canonicalize::<ErrorCode>())?;
 
@@ -2,7 +2,10 @@ use anchor_client::{Client, Cluster};
 use anchor_spl::token::Mint;
 use anyhow::Result;
 use gfx_solana_utils::{load_keypair, AnchorClientErrorExt, ApplyDecimal, Duplicate};
-use gfx_swap::{DerivedAccountIdentifier, ErrorCode, LPMint, Pool, LP_TOKEN_DECIMALS};
+use gfx_swap::{
+    AllowlistEntry, DerivedAccountIdentifier, ErrorCode, GlobalConfig, LPMint, Pool,
+    LP_TOKEN_DECIMALS,
+};
 use solana_sdk::{
     commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signer, system_program, sysvar,
 };
@@ -54,15 +57,19 @@ fn main() -> Result<()> {
         .request()
         .accounts(gfx_swap::accounts::Deposit1 {
             pool: pool,
+            global_config: GlobalConfig::get_address(&program_id, &[]),
             token_a_vault: get_associated_token_address(&pool, &opt.token_a),
             token_b_vault: get_associated_token_address(&pool, &opt.token_b),
             lp_token_mint: lp_mint,
 
             user_wallet: user.pubkey(),
+            allowlist_entry: AllowlistEntry::address(&program_id, &pool, &user.pubkey()),
             in_token_ata_user: get_associated_token_address(&user.pubkey(), &opt.token),
+            in_token_mint: opt.token,
             user_lp_ata: get_associated_token_address(&user.pubkey(), &lp_mint),
 
             token_program: spl_token::id(),
+            in_token_program: spl_token::id(),
             system_program: system_program::id(),
             rent: sysvar::rent::id(),
             associated_token_program: spl_associated_token_account::id(),
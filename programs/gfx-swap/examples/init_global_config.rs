@@ -0,0 +1,62 @@
+use anchor_client::{Client, Cluster};
+use anyhow::Result;
+use gfx_solana_utils::{load_keypair, AnchorClientErrorExt, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, ErrorCode, GlobalConfig};
+use solana_sdk::{
+    bpf_loader_upgradeable, commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signer,
+    system_program, sysvar,
+};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "transact", about = "Making transactions to the GFX Swap")]
+struct Opt {
+    #[structopt(long, env, short = "p")]
+    program_id: Option<Pubkey>,
+
+    #[structopt(long, env)]
+    admin_wallet: String,
+}
+
+fn main() -> Result<()> {
+    let opt = Opt::from_args();
+    let program_id = gfx_swap::ID;
+    println!("program_id: {}", program_id);
+
+    let admin = load_keypair(&opt.admin_wallet)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::confirmed(),
+    );
+    let program = client.program(program_id);
+
+    let (global_config, bump) = GlobalConfig::get_address_with_bump(&program_id, &[]);
+    println!("Global config: {}", global_config);
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::InitGlobalConfig {
+            global_config,
+            owner: admin.pubkey(),
+            program_data: Pubkey::find_program_address(
+                &[program_id.as_ref()],
+                &bpf_loader_upgradeable::id(),
+            )
+            .0,
+            rent: sysvar::rent::id(),
+            system_program: system_program::id(),
+        })
+        .args(gfx_swap::instruction::InitGlobalConfig { bump })
+        .signer(&admin)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "Transaction: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use anchor_client::{Client, Cluster};
+use anyhow::Result;
+use gfx_solana_utils::{load_keypair, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, PoolRegistry, PoolRegistryPage};
+use prettytable::{cell, row, Table};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "transact",
+    about = "Listing all the pools in the GFX Swap registry"
+)]
+struct Opt {
+    #[structopt(long, env, short = "p")]
+    program_id: Option<Pubkey>,
+
+    #[structopt(long, env)]
+    admin_wallet: String,
+}
+
+fn main() -> Result<()> {
+    let opt = Opt::from_args();
+    let program_id = gfx_swap::ID;
+    println!("program_id: {}", program_id);
+
+    let admin = load_keypair(&opt.admin_wallet)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::confirmed(),
+    );
+    let program = client.program(program_id);
+
+    let registry: PoolRegistry = program.account(PoolRegistry::get_address(&program_id, &[]))?;
+
+    let mut table = Table::new();
+    table.add_row(row![
+        "Page",
+        "Pool",
+        "Token 1",
+        "Token 2",
+        "Curve",
+        "Trade Fee"
+    ]);
+
+    for address in PoolRegistryPage::page_addresses(&program_id, registry.page_count) {
+        let page: PoolRegistryPage = program.account(address)?;
+        for entry in &page.entries {
+            table.add_row(row![
+                page.page,
+                entry.pool,
+                entry.token_mint_1,
+                entry.token_mint_2,
+                format!("{:?}", entry.curve_type),
+                format!(
+                    "{}/{}",
+                    entry.trade_fee_numerator, entry.trade_fee_denominator
+                ),
+            ]);
+        }
+    }
+
+    table.printstd();
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use anchor_client::{Client, Cluster};
+use anchor_lang::AnchorDeserialize;
+use anyhow::Result;
+use gfx_solana_utils::{load_keypair, AnchorClientErrorExt, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, ErrorCode, LegacyPool, Pool};
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signer, system_program, sysvar,
+};
+use spl_associated_token_account::get_associated_token_address;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "transact", about = "Making transactions to the GFX Swap")]
+struct Opt {
+    #[structopt(long, env, short = "p")]
+    program_id: Option<Pubkey>,
+
+    #[structopt(long, env)]
+    seed: Pubkey,
+
+    // a page of the registry with room for the pool
+    #[structopt(long, env)]
+    registry_page: Pubkey,
+
+    #[structopt(long, env)]
+    admin_wallet: String,
+}
+
+fn main() -> Result<()> {
+    let opt = Opt::from_args();
+    let program_id = gfx_swap::ID;
+    println!("program_id: {}", program_id);
+
+    let admin = load_keypair(&opt.admin_wallet)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::confirmed(),
+    );
+    let program = client.program(program_id);
+
+    let legacy_pool = LegacyPool::get_address(&program_id, &opt.seed.to_bytes());
+    println!("Legacy pool: {}", legacy_pool);
+    let data = program.rpc().get_account_data(&legacy_pool)?;
+    let legacy = LegacyPool::deserialize(&mut &data[8..])?;
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&program_id, &opt.seed.to_bytes());
+    println!("Pool: {}", pool);
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::MigratePool {
+            legacy_pool,
+            legacy_token_vault_1: legacy.token_vault_1,
+            legacy_token_vault_2: legacy.token_vault_2,
+            legacy_fee_vault: legacy.fee_vault,
+
+            pool,
+            lp_token_mint: legacy.mint,
+            token_mint_1: legacy.token_mint_1,
+            token_mint_2: legacy.token_mint_2,
+            token_vault_1: get_associated_token_address(&pool, &legacy.token_mint_1),
+            token_vault_2: get_associated_token_address(&pool, &legacy.token_mint_2),
+            fee_vault: get_associated_token_address(&pool, &legacy.mint),
+
+            registry_page: opt.registry_page,
+            payer: admin.pubkey(),
+
+            associated_token_program: spl_associated_token_account::id(),
+            token_program: spl_token::id(),
+            rent: sysvar::rent::id(),
+            system_program: system_program::id(),
+        })
+        .args(gfx_swap::instruction::MigratePool {
+            seed: opt.seed.to_bytes(),
+            pool_bump,
+        })
+        .signer(&admin)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "Transaction: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+
+    Ok(())
+}
@@ -3,7 +3,8 @@ use anyhow::Result;
 use gfx_solana_utils::{load_keypair, AnchorClientErrorExt, Duplicate};
 use gfx_swap::{DerivedAccountIdentifier, ErrorCode, LPMint, Pool};
 use solana_sdk::{
-    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signer, system_program, sysvar,
+    commitment_config::CommitmentConfig, instruction::AccountMeta, pubkey::Pubkey,
+    signature::Signer, system_program, sysvar,
 };
 use spl_associated_token_account::get_associated_token_address;
 use structopt::StructOpt;
@@ -42,7 +43,9 @@ fn main() -> Result<()> {
         .request()
         .accounts(gfx_swap::accounts::MintLPTo {
             admin: mint_to.pubkey(),
+            payer: mint_to.pubkey(),
             pool,
+            recipient: mint_to.pubkey(),
             recipient_ata: get_associated_token_address(&mint_to.pubkey(), &lp_mint),
             lp_token_mint: lp_mint,
             token_program: spl_token::id(),
@@ -50,6 +53,7 @@ fn main() -> Result<()> {
             rent: sysvar::rent::id(),
             associated_token_program: spl_associated_token_account::id(),
         })
+        .accounts(AccountMeta::new_readonly(mint_to.pubkey(), true))
         .args(gfx_swap::instruction::MintLpTo { n: 1 })
         .signer(&mint_to)
         .options(CommitmentConfig::confirmed())
@@ -2,8 +2,10 @@ use anchor_client::{Client, Cluster};
 use anchor_spl::token::Mint;
 use anyhow::Result;
 use gfx_solana_utils::{load_keypair, AnchorClientErrorExt, ApplyDecimal, Duplicate};
-use gfx_swap::{DerivedAccountIdentifier, ErrorCode, LPMint, Pool};
-use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signer};
+use gfx_swap::{AllowlistEntry, DerivedAccountIdentifier, ErrorCode, GlobalConfig, LPMint, Pool};
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signer, system_program,
+};
 use spl_associated_token_account::get_associated_token_address;
 use structopt::StructOpt;
 
@@ -57,17 +59,24 @@ fn main() -> Result<()> {
         .request()
         .accounts(gfx_swap::accounts::Swap {
             pool: pool,
+            global_config: GlobalConfig::get_address(&program_id, &[]),
 
             in_token_vault: get_associated_token_address(&pool, &swap_in),
             out_token_vault: get_associated_token_address(&pool, &swap_out),
+            in_token_mint: swap_in,
+            out_token_mint: swap_out,
             lp_token_mint: lp_mint,
             fee_vault: get_associated_token_address(&pool, &lp_mint),
 
             user_wallet: user.pubkey(),
+            allowlist_entry: AllowlistEntry::address(&program_id, &pool, &user.pubkey()),
             in_token_ata_user: get_associated_token_address(&user.pubkey(), &swap_in),
             out_token_ata_user: get_associated_token_address(&user.pubkey(), &swap_out),
 
             token_program: spl_token::id(),
+            in_token_program: spl_token::id(),
+            out_token_program: spl_token::id(),
+            system_program: system_program::id(),
         })
         .args(gfx_swap::instruction::Swap {
             amount_in: swap_in_mint.decimals.apply(opt.amount),
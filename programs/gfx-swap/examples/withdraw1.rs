@@ -2,7 +2,9 @@ use anchor_client::{Client, Cluster};
 use anchor_spl::token::Mint;
 use anyhow::Result;
 use gfx_solana_utils::{load_keypair, AnchorClientErrorExt, ApplyDecimal, Duplicate};
-use gfx_swap::{DerivedAccountIdentifier, ErrorCode, LPMint, Pool, LP_TOKEN_DECIMALS};
+use gfx_swap::{
+    DerivedAccountIdentifier, ErrorCode, GlobalConfig, LPMint, Pool, LP_TOKEN_DECIMALS,
+};
 use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signer};
 use spl_associated_token_account::get_associated_token_address;
 use structopt::StructOpt;
@@ -52,6 +54,7 @@ fn main() -> Result<()> {
         .request()
         .accounts(gfx_swap::accounts::Withdraw1 {
             pool: pool,
+            global_config: GlobalConfig::get_address(&program_id, &[]),
             token_a_vault: get_associated_token_address(&pool, &opt.token_a),
             token_b_vault: get_associated_token_address(&pool, &opt.token_b),
             lp_token_mint: lp_mint,
@@ -59,9 +62,11 @@ fn main() -> Result<()> {
 
             user_wallet: user.pubkey(),
             out_token_ata_user: get_associated_token_address(&user.pubkey(), &opt.token),
+            out_token_mint: opt.token,
             user_lp_ata: get_associated_token_address(&user.pubkey(), &lp_mint),
 
             token_program: spl_token::id(),
+            out_token_program: spl_token::id(),
         })
         .args(gfx_swap::instruction::Withdraw1 {
             out_token_amount: token_mint.decimals.apply(opt.amount),
@@ -1,8 +1,130 @@
-use crate::states::Pool;
+use crate::states::{
+    AccessMode, AdminMultisig, AllowlistEntry, Deadline, GlobalConfig, Pool, PoolConfig, PoolRole,
+};
+use crate::token_interface::{CpiAccount, InterfaceTokenAccount};
 use anchor_lang::prelude::*;
 use fehler::throws;
 
 #[throws(ProgramError)]
-pub fn suspended(pool: &Account<'_, Pool>) {
-    require!(!pool.suspended, Suspended);
+pub fn not_expired(deadline: &Deadline) {
+    deadline.check()?;
+}
+
+#[throws(ProgramError)]
+pub fn globally_open(global_config: &Account<'_, GlobalConfig>) {
+    require!(!global_config.suspended, GloballySuspended);
+}
+
+#[throws(ProgramError)]
+pub fn global_withdrawals_open(global_config: &Account<'_, GlobalConfig>) {
+    require!(
+        !global_config.withdrawals_suspended,
+        GlobalWithdrawalsSuspended
+    );
+}
+
+#[throws(ProgramError)]
+pub fn swaps_open(pool: &Account<'_, Pool>) {
+    require!(!pool.circuit_breaker.is_tripped(), CircuitBreakerTripped);
+    require!(!pool.swaps_suspended, SwapsSuspended);
+}
+
+#[throws(ProgramError)]
+pub fn unlocked(pool: &Account<'_, Pool>) {
+    require!(!pool.locked, PoolLocked);
+}
+
+#[throws(ProgramError)]
+pub fn deposits_open(pool: &Account<'_, Pool>) {
+    require!(!pool.deposits_suspended, DepositsSuspended);
+}
+
+#[throws(ProgramError)]
+pub fn single_sided_open(pool: &Account<'_, Pool>) {
+    require!(!pool.single_sided_suspended, SingleSidedSuspended);
+}
+
+#[throws(ProgramError)]
+pub fn withdrawals_open(pool: &Account<'_, Pool>) {
+    require!(!pool.withdrawals_suspended, WithdrawalsSuspended);
+}
+
+// The output token accounts of `swap`, `withdraw1` and `withdraw2` belong to the user wallet,
+// the `_to` variants send the output to the token accounts of an explicit recipient instead.
+#[throws(ProgramError)]
+pub fn receives(token_account: &CpiAccount<'_, InterfaceTokenAccount>, recipient: &Pubkey) {
+    require!(&token_account.owner == recipient, WrongATAOwner);
+}
+
+// Every wallet may use an open pool, a permissioned pool requires the `AllowlistEntry` of the wallet.
+// The address of the entry is already checked by the seeds of the account.
+#[throws(ProgramError)]
+pub fn allowlisted(pool: &Account<'_, Pool>, allowlist_entry: &AccountInfo<'_>) {
+    if pool.access_mode == AccessMode::Open {
+        return;
+    }
+
+    require!(allowlist_entry.owner == &crate::ID, NotAllowlisted);
+    AllowlistEntry::try_deserialize(&mut &allowlist_entry.data.borrow()[..])?;
+}
+
+// An authority approves the instruction either by signing it, or by being an `AdminMultisig`
+// whose signers sign it and are passed in as the remaining accounts.
+#[throws(ProgramError)]
+pub fn approved(authority: &AccountInfo<'_>, remaining_accounts: &[AccountInfo<'_>]) {
+    if authority.is_signer {
+        return;
+    }
+
+    require!(authority.owner == &crate::ID, AuthorityNotSigner);
+    let multisig = AdminMultisig::try_deserialize(&mut &authority.data.borrow()[..])?;
+    multisig.verify(remaining_accounts)?;
+}
+
+#[throws(ProgramError)]
+pub fn multisig_approved(
+    multisig: &Account<'_, AdminMultisig>,
+    remaining_accounts: &[AccountInfo<'_>],
+) {
+    multisig.verify(remaining_accounts)?;
+}
+
+#[throws(ProgramError)]
+pub fn authorized(
+    pool: &Account<'_, Pool>,
+    role: PoolRole,
+    authority: &AccountInfo<'_>,
+    remaining_accounts: &[AccountInfo<'_>],
+) {
+    require!(pool.authorizes(role, authority.key), PrivilegeRequired);
+    approved(authority, remaining_accounts)?;
+}
+
+#[throws(ProgramError)]
+pub fn authorized_config(
+    pool: &Account<'_, Pool>,
+    authority: &AccountInfo<'_>,
+    remaining_accounts: &[AccountInfo<'_>],
+    config: &PoolConfig,
+) {
+    // the delay and the access mode belong to the admin alone, who approves like for any admin instruction:
+    // a permissioned pool locks out every wallet without an allowlist entry
+    if config.config_delay.is_some() || config.access_mode.is_some() {
+        require!(authority.key == &pool.admin, PrivilegeRequired);
+        approved(authority, remaining_accounts)?;
+    }
+
+    for role in config.required_roles() {
+        authorized(pool, role, authority, remaining_accounts)?;
+    }
+}
+
+#[throws(ProgramError)]
+pub fn global_owner_approved(
+    global_config: &Account<'_, GlobalConfig>,
+    owner: &AccountInfo<'_>,
+    remaining_accounts: &[AccountInfo<'_>],
+) {
+    require!(owner.key == &global_config.owner, WrongGlobalOwner);
+    approved(owner, remaining_accounts)?;
 }
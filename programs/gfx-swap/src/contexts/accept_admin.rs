@@ -0,0 +1,26 @@
+use crate::states::{DerivedAccountIdentifier, Pool};
+use crate::ErrorCode::*;
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [Pool::IDENT, &pool.seed],
+        bump = pool.bump,
+        constraint = pool.has_pending_admin() @ NoPendingAdmin,
+        constraint = pool.pending_admin == new_admin.key() @ WrongPendingAdmin,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    pub new_admin: UncheckedAccount<'info>, // the new admin approves to prove the key is usable
+}
+
+impl<'info> AcceptAdmin<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self) {
+        let AcceptAdmin { pool, .. } = self;
+        pool.accept_admin();
+    }
+}
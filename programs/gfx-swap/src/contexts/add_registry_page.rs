@@ -0,0 +1,45 @@
+use crate::states::{DerivedAccountIdentifier, PoolRegistry, PoolRegistryPage};
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+#[derive(Accounts)]
+#[instruction(registry_bump: u8, page_bump: u8)]
+pub struct AddRegistryPage<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [PoolRegistry::IDENT],
+        bump = registry_bump,
+        payer = payer,
+    )]
+    pub registry: Account<'info, PoolRegistry>,
+
+    #[account(
+        init,
+        seeds = [PoolRegistryPage::IDENT, &registry.page_count.to_le_bytes()],
+        bump = page_bump,
+        payer = payer,
+        space = PoolRegistryPage::LEN,
+    )]
+    pub registry_page: Account<'info, PoolRegistryPage>,
+
+    pub payer: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AddRegistryPage<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self, registry_bump: u8, page_bump: u8) {
+        let AddRegistryPage {
+            registry,
+            registry_page,
+            ..
+        } = self;
+
+        registry.bump = registry_bump;
+        registry_page.page = registry.page_count;
+        registry_page.bump = page_bump;
+        registry.page_count += 1;
+    }
+}
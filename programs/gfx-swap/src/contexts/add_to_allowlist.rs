@@ -0,0 +1,50 @@
+use crate::states::{AllowlistEntry, DerivedAccountIdentifier, Pool};
+use crate::ErrorCode::*;
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct AddToAllowlist<'info> {
+    #[account(
+        seeds = [Pool::IDENT, &pool.seed],
+        bump = pool.bump,
+        has_one = admin @ WrongAdmin
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init,
+        seeds = [AllowlistEntry::IDENT, pool.key().as_ref(), wallet.key().as_ref()],
+        bump = bump,
+        payer = payer,
+    )]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+
+    pub wallet: UncheckedAccount<'info>, // the wallet to allow, it does not need to sign
+
+    pub admin: UncheckedAccount<'info>, // a signer or an AdminMultisig
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AddToAllowlist<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self, bump: u8) {
+        let AddToAllowlist {
+            pool,
+            allowlist_entry,
+            wallet,
+            payer,
+            ..
+        } = self;
+
+        allowlist_entry.pool = pool.key();
+        allowlist_entry.wallet = wallet.key();
+        allowlist_entry.bump = bump;
+        allowlist_entry.payer = payer.key();
+    }
+}
@@ -0,0 +1,26 @@
+use crate::states::{DerivedAccountIdentifier, Pool};
+use crate::ErrorCode::*;
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+#[derive(Accounts)]
+pub struct CancelAdminProposal<'info> {
+    #[account(
+        mut,
+        seeds = [Pool::IDENT, &pool.seed],
+        bump = pool.bump,
+        has_one = admin @ WrongAdmin,
+        constraint = pool.has_pending_admin() @ NoPendingAdmin,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    pub admin: UncheckedAccount<'info>, // a signer or an AdminMultisig
+}
+
+impl<'info> CancelAdminProposal<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self) {
+        let CancelAdminProposal { pool, .. } = self;
+        pool.cancel_admin_proposal();
+    }
+}
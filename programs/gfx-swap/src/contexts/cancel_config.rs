@@ -0,0 +1,33 @@
+use crate::states::{DerivedAccountIdentifier, Pool, QueuedConfig};
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+#[derive(Accounts)]
+pub struct CancelConfig<'info> {
+    #[account(
+        seeds = [Pool::IDENT, &pool.seed],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [QueuedConfig::IDENT, pool.key().as_ref()],
+        bump = queued_config.bump,
+        has_one = payer,
+        close = payer,
+    )]
+    pub queued_config: Account<'info, QueuedConfig>,
+
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    pub authority: UncheckedAccount<'info>, // the admin or the role required by the queued config
+}
+
+impl<'info> CancelConfig<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self) {
+        // closing the queued config is all we need
+    }
+}
@@ -0,0 +1,66 @@
+use crate::errors::ErrorCode::*;
+use crate::states::{DerivedAccountIdentifier, EscrowExt, LimitOrder};
+use crate::token_interface::{
+    associated_token_address, CpiAccount, InterfaceMint, InterfaceTokenAccount,
+};
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+// Cancelling is always possible, the pool may be paused or even delisted
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    #[account(
+        mut,
+        seeds = [LimitOrder::IDENT, order.pool.as_ref(), maker.key().as_ref(), &order.id.to_le_bytes()],
+        bump = order.bump,
+        has_one = maker @ WrongATAOwner,
+        close = maker,
+    )]
+    pub order: Box<Account<'info, LimitOrder>>,
+
+    #[account(
+        mut,
+        constraint = escrow.key() == associated_token_address(&order.key(), &order.in_mint, token_program.key) @ WrongATAOwner,
+    )]
+    pub escrow: CpiAccount<'info, InterfaceTokenAccount>,
+    // written when the escrow withholds transfer fees, see `token_interface::close_account`
+    #[account(mut, constraint = in_mint.key() == order.in_mint @ TokenNotSupportedByPool)]
+    pub in_mint: CpiAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        constraint = maker_in_token_ata.mint == order.in_mint @ TokenNotSupportedByPool,
+        constraint = maker_in_token_ata.owner == maker.key() @ WrongATAOwner,
+    )]
+    pub maker_in_token_ata: CpiAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    pub token_program: UncheckedAccount<'info>, // of the input mint
+}
+
+impl<'info> CancelOrder<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self) {
+        let CancelOrder {
+            order,
+            escrow,
+            in_mint,
+            maker_in_token_ata,
+            maker,
+            token_program,
+        } = self;
+
+        if escrow.amount > 0 {
+            order.transfer_from_escrow(
+                escrow,
+                maker_in_token_ata,
+                in_mint,
+                token_program,
+                escrow.amount,
+            )?;
+        }
+        order.close_escrow(escrow, in_mint, maker, token_program)?;
+    }
+}
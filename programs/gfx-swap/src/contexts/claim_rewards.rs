@@ -0,0 +1,54 @@
+use crate::errors::ErrorCode::*;
+use crate::states::{DerivedAccountIdentifier, Farm, FarmExt, FarmStake};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use fehler::throws;
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    #[account(mut, seeds = [Farm::IDENT, farm.pool.as_ref()], bump = farm.bump)]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(
+        mut,
+        seeds = [FarmStake::IDENT, farm.key().as_ref(), owner.key().as_ref()],
+        bump = stake.bump,
+        has_one = owner @ WrongATAOwner,
+    )]
+    pub stake: Box<Account<'info, FarmStake>>,
+
+    #[account(mut, constraint = farm.reward_vault == reward_vault.key() @ WrongRewardVault)]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = owner_reward_ata.mint == farm.reward_mint @ WrongRewardVault,
+        constraint = owner_reward_ata.owner == owner.key() @ WrongATAOwner,
+    )]
+    pub owner_reward_ata: Box<Account<'info, TokenAccount>>,
+
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> ClaimRewards<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self) {
+        let ClaimRewards {
+            farm,
+            stake,
+            reward_vault,
+            owner_reward_ata,
+            token_program,
+            ..
+        } = self;
+
+        farm.update(Clock::get()?.unix_timestamp)?;
+        stake.settle(farm)?;
+
+        let rewards = std::mem::take(&mut stake.unclaimed_rewards);
+        if rewards > 0 {
+            farm.transfer_from_vault(reward_vault, owner_reward_ata, token_program, rewards)?;
+        }
+    }
+}
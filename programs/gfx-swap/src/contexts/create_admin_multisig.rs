@@ -0,0 +1,32 @@
+use crate::states::{AdminMultisig, DerivedAccountIdentifier};
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+#[derive(Accounts)]
+#[instruction(seed: [u8; 32], bump: u8)]
+pub struct CreateAdminMultisig<'info> {
+    #[account(
+        init,
+        seeds = [AdminMultisig::IDENT, &seed],
+        bump = bump,
+        payer = payer,
+        space = AdminMultisig::LEN,
+    )]
+    pub multisig: Account<'info, AdminMultisig>,
+
+    pub payer: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateAdminMultisig<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self, seed: [u8; 32], bump: u8, signers: Vec<Pubkey>, threshold: u8) {
+        let CreateAdminMultisig { multisig, .. } = self;
+
+        multisig.seed = seed;
+        multisig.bump = bump;
+        multisig.set_signers(signers, threshold)?;
+    }
+}
@@ -0,0 +1,108 @@
+use crate::errors::ErrorCode::*;
+use crate::states::{
+    AllowlistEntry, DcaOrder, DcaSchedule, DerivedAccountIdentifier, GlobalConfig, Pool,
+};
+use crate::token_interface::{self, CpiAccount, InterfaceMint, InterfaceTokenAccount};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use fehler::throws;
+
+#[derive(Accounts)]
+#[instruction(id: u64, bump: u8)]
+pub struct CreateDcaOrder<'info> {
+    #[account(seeds = [Pool::IDENT, &pool.seed], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+
+    #[account(seeds = [GlobalConfig::IDENT], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        init,
+        seeds = [DcaOrder::IDENT, pool.key().as_ref(), owner.key().as_ref(), &id.to_le_bytes()],
+        bump = bump,
+        payer = owner,
+    )]
+    pub order: Box<Account<'info, DcaOrder>>,
+
+    #[account(constraint = pool.swaps(&in_mint) @ TokenNotSupportedByPool)]
+    pub in_mint: CpiAccount<'info, InterfaceMint>,
+    // the associated token account of the order, created in `process` with the program of the mint
+    #[account(mut)]
+    pub escrow: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = owner_in_token_ata.mint == in_mint.key() @ TokenNotSupportedByPool,
+        constraint = owner_in_token_ata.owner == owner.key() @ WrongATAOwner,
+    )]
+    pub owner_in_token_ata: CpiAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    // only read when the pool is permissioned, see `allowlisted`
+    #[account(seeds = [AllowlistEntry::IDENT, pool.key().as_ref(), owner.key().as_ref()], bump)]
+    pub allowlist_entry: UncheckedAccount<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: UncheckedAccount<'info>, // of the input mint
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateDcaOrder<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self, id: u64, bump: u8, schedule: DcaSchedule) {
+        let CreateDcaOrder {
+            pool,
+            order,
+            in_mint,
+            escrow,
+            owner_in_token_ata,
+            owner,
+            system_program,
+            token_program,
+            associated_token_program,
+            ..
+        } = self;
+
+        schedule.validate()?;
+
+        order.pool = pool.key();
+        order.owner = owner.key();
+        order.id = id;
+        order.bump = bump;
+        order.in_mint = in_mint.key();
+        order.out_mint = if in_mint.key() == pool.token_mint_1 {
+            pool.token_mint_2
+        } else {
+            pool.token_mint_1
+        };
+        order.total_amount = schedule.total_amount;
+        order.slice_amount = schedule.slice_amount;
+        order.interval = schedule.interval;
+        order.minimum_slice_amount_out = schedule.minimum_slice_amount_out;
+        order.keeper_fee_bps = schedule.keeper_fee_bps;
+
+        token_interface::create_associated_token_account(
+            owner,
+            escrow,
+            &order.to_account_info(),
+            in_mint,
+            system_program,
+            token_program,
+            associated_token_program,
+        )?;
+
+        // the escrow receives the total without the fee of a Token-2022 mint, the last slice sells the rest
+        token_interface::transfer_checked(
+            owner_in_token_ata,
+            &CpiAccount::try_from(escrow)?,
+            in_mint,
+            owner,
+            token_program,
+            &[],
+            schedule.total_amount,
+        )?;
+    }
+}
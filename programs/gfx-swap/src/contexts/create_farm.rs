@@ -0,0 +1,77 @@
+use crate::errors::ErrorCode::*;
+use crate::states::{DerivedAccountIdentifier, Farm, Pool};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+use fehler::throws;
+
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct CreateFarm<'info> {
+    #[account(
+        seeds = [Pool::IDENT, &pool.seed],
+        bump = pool.bump,
+        has_one = admin @ WrongAdmin
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init,
+        seeds = [Farm::IDENT, pool.key().as_ref()],
+        bump = bump,
+        payer = payer,
+    )]
+    pub farm: Box<Account<'info, Farm>>,
+
+    pub reward_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = reward_mint,
+        associated_token::authority = farm,
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(constraint = pool.mint == lp_token_mint.key() @ WrongLPMint)]
+    pub lp_token_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = lp_token_mint,
+        associated_token::authority = farm,
+    )]
+    pub lp_vault: Box<Account<'info, TokenAccount>>,
+
+    pub admin: UncheckedAccount<'info>, // a signer or an AdminMultisig
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateFarm<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self, bump: u8, emission_rate: u64) {
+        let CreateFarm {
+            pool,
+            farm,
+            reward_mint,
+            reward_vault,
+            lp_vault,
+            ..
+        } = self;
+
+        farm.pool = pool.key();
+        farm.bump = bump;
+        farm.reward_mint = reward_mint.key();
+        farm.reward_vault = reward_vault.key();
+        farm.lp_vault = lp_vault.key();
+        farm.emission_rate = emission_rate;
+        farm.last_update_at = Clock::get()?.unix_timestamp;
+    }
+}
@@ -0,0 +1,37 @@
+use crate::states::{DerivedAccountIdentifier, Farm, FarmStake};
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct CreateFarmStake<'info> {
+    #[account(seeds = [Farm::IDENT, farm.pool.as_ref()], bump = farm.bump)]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(
+        init,
+        seeds = [FarmStake::IDENT, farm.key().as_ref(), owner.key().as_ref()],
+        bump = bump,
+        payer = owner,
+    )]
+    pub stake: Box<Account<'info, FarmStake>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateFarmStake<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self, bump: u8) {
+        let CreateFarmStake {
+            farm, stake, owner, ..
+        } = self;
+
+        stake.farm = farm.key();
+        stake.owner = owner.key();
+        stake.bump = bump;
+    }
+}
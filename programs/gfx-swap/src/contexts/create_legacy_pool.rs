@@ -0,0 +1,137 @@
+use crate::curve::{Fees, SwapCurve};
+use crate::states::{DerivedAccountIdentifier, LPMint, LegacyPool, Pool};
+use crate::utils::PubkeyPairExt;
+use crate::LP_TOKEN_DECIMALS;
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+use fehler::{throw, throws};
+use solana_program::{program::invoke_signed, system_instruction};
+
+// Creates a pool the way the first version of the program did, so the tests can migrate it. Only
+// the builds with the `ci` feature accept it.
+#[derive(Accounts)]
+#[instruction(seed: [u8; 32], pool_bump: u8, lp_bump: u8)]
+pub struct CreateLegacyPool<'info> {
+    // created in `process`, anchor cannot init an account which does not deserialize as its type
+    #[account(mut)]
+    pub legacy_pool: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        mint::decimals = LP_TOKEN_DECIMALS,
+        mint::authority = legacy_pool,
+        seeds = [LPMint::IDENT, &seed],
+        bump = lp_bump,
+        payer = admin,
+        space = Mint::LEN,
+    )]
+    pub lp_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = admin,
+        associated_token::mint = lp_token_mint,
+        associated_token::authority = legacy_pool,
+    )]
+    pub fee_vault: Box<Account<'info, TokenAccount>>,
+
+    pub token_a_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = admin,
+        associated_token::mint = token_a_mint,
+        associated_token::authority = legacy_pool,
+    )]
+    pub token_a_vault: Box<Account<'info, TokenAccount>>,
+
+    pub token_b_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = admin,
+        associated_token::mint = token_b_mint,
+        associated_token::authority = legacy_pool,
+    )]
+    pub token_b_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateLegacyPool<'info> {
+    #[throws(ProgramError)]
+    pub fn process(
+        &mut self,
+        seed: [u8; 32],
+        pool_bump: u8,
+        lp_bump: u8,
+        fees: Fees,
+        curve: SwapCurve,
+    ) {
+        if !cfg!(feature = "ci") {
+            throw!(ProgramError::InvalidInstructionData);
+        }
+
+        let CreateLegacyPool {
+            legacy_pool,
+            lp_token_mint,
+            fee_vault,
+            token_a_mint,
+            token_a_vault,
+            token_b_mint,
+            token_b_vault,
+            admin,
+            rent,
+            system_program,
+            ..
+        } = self;
+
+        let (token_vault_1, token_vault_2) = (token_a_mint.key(), token_b_mint.key())
+            .sort(token_a_vault.key(), token_b_vault.key())?;
+        let (token_mint_1, token_mint_2) = (token_a_mint.key(), token_b_mint.key()).sort_self()?;
+
+        let legacy = LegacyPool {
+            seed,
+            bump: pool_bump,
+            lp_bump,
+            admin: admin.key(),
+            token_mint_1,
+            token_mint_2,
+            token_vault_1,
+            token_vault_2,
+            mint: lp_token_mint.key(),
+            fee_vault: fee_vault.key(),
+            fees,
+            curve,
+            suspended: false,
+        };
+        // the first version stored the pool under the discriminator of `Pool`
+        let mut data = Pool::discriminator().to_vec();
+        legacy.serialize(&mut data)?;
+
+        invoke_signed(
+            &system_instruction::create_account(
+                &admin.key(),
+                &legacy_pool.key(),
+                rent.minimum_balance(data.len()),
+                data.len() as u64,
+                &crate::ID,
+            ),
+            &[
+                admin.to_account_info(),
+                legacy_pool.to_account_info(),
+                system_program.to_account_info(),
+            ],
+            &[&[LegacyPool::IDENT, &seed, &[pool_bump]]],
+        )?;
+        legacy_pool.try_borrow_mut_data()?.copy_from_slice(&data);
+    }
+}
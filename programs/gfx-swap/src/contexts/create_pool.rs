@@ -1,5 +1,8 @@
-use crate::curve::{CurveCalculator, Fees, SwapCurve};
-use crate::states::{DerivedAccountIdentifier, LPMint, Pool};
+use crate::curve::{CurveCalculator, Fees, FlashFees, SwapCurve};
+use crate::states::{
+    DerivedAccountIdentifier, GlobalConfig, LPMint, Pool, PoolRegistryPage, RegistryEntry,
+};
+use crate::token_interface::{self, CpiAccount, InterfaceMint};
 use crate::utils::PubkeyPairExt;
 use crate::LP_TOKEN_DECIMALS;
 use anchor_lang::prelude::*;
@@ -17,6 +20,7 @@ pub struct CreatePool<'info> {
         seeds = [Pool::IDENT, &seed],
         bump = pool_bump,
         payer = admin,
+        space = Pool::LEN,
     )]
     pub pool: Account<'info, Pool>,
 
@@ -39,24 +43,26 @@ pub struct CreatePool<'info> {
     )]
     pub fee_vault: Box<Account<'info, TokenAccount>>,
 
-    pub token_a_mint: Box<Account<'info, Mint>>,
-    #[account(
-        init,
-        payer = admin,
-        associated_token::mint = token_a_mint,
-        associated_token::authority = pool,
-    )]
-    pub token_a_vault: Box<Account<'info, TokenAccount>>,
+    // The mints may be of either token program, the vaults are created in `process` with the program
+    // of their mint, see `token_interface`.
+    pub token_a_mint: CpiAccount<'info, InterfaceMint>,
+    #[account(mut)]
+    pub token_a_vault: UncheckedAccount<'info>,
+    pub token_a_program: UncheckedAccount<'info>,
 
-    pub token_b_mint: Box<Account<'info, Mint>>,
-    #[account(
-        init,
-        payer = admin,
-        associated_token::mint = token_b_mint,
-        associated_token::authority = pool,
-    )]
-    pub token_b_vault: Box<Account<'info, TokenAccount>>,
+    pub token_b_mint: CpiAccount<'info, InterfaceMint>,
+    #[account(mut)]
+    pub token_b_vault: UncheckedAccount<'info>,
+    pub token_b_program: UncheckedAccount<'info>,
 
+    #[account(seeds = [GlobalConfig::IDENT], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    // any page of the registry which still has room for the new pool
+    #[account(mut)]
+    pub registry_page: Box<Account<'info, PoolRegistryPage>>,
+
+    #[account(mut)]
     pub admin: Signer<'info>, // admin account can do privileged operations
 
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -67,6 +73,7 @@ pub struct CreatePool<'info> {
 
 impl<'info> CreatePool<'info> {
     #[throws(ProgramError)]
+    #[allow(clippy::too_many_arguments)]
     pub fn process(
         &mut self,
         seed: [u8; 32],
@@ -74,39 +81,80 @@ impl<'info> CreatePool<'info> {
         lp_bump: u8,
         fees: Fees,
         curve: SwapCurve,
+        config_delay: i64,
+        flash_fees: FlashFees,
     ) {
         let CreatePool {
             admin,
             pool,
             lp_token_mint,
             fee_vault: lp_token_ata_fee,
-            token_a_vault,
-            token_b_vault,
             token_a_mint,
+            token_a_vault,
+            token_a_program,
             token_b_mint,
+            token_b_vault,
+            token_b_program,
+            registry_page,
+            global_config,
+            system_program,
+            associated_token_program,
             ..
         } = self;
 
         fees.validate()?;
+        flash_fees.validate()?;
         curve.validate()?;
+        global_config.constraints.validate_fees(&fees)?;
+        global_config
+            .constraints
+            .validate_config_delay(config_delay)?;
 
-        let (token_a_mint, token_b_mint) = (&mut **token_a_mint, &mut **token_b_mint);
         // sort the tokens into the increasing order based on address
-        let (token_vault_1, token_vault_2) =
-            (token_a_vault.mint, token_b_vault.mint).sort(token_a_vault, token_b_vault)?;
-        let (token_mint_1, token_mint_2) = (token_a_mint, token_b_mint).sort_self()?;
+        let (token_vault_1, token_vault_2) = (token_a_mint.key(), token_b_mint.key())
+            .sort(token_a_vault.key(), token_b_vault.key())?;
+        let (token_mint_1, token_mint_2) = (token_a_mint.key(), token_b_mint.key()).sort_self()?;
+
+        // the associated token program derives the vaults from the mints and their token programs
+        for (mint, vault, token_program) in [
+            (&*token_a_mint, &*token_a_vault, &*token_a_program),
+            (&*token_b_mint, &*token_b_vault, &*token_b_program),
+        ] {
+            mint.check_extensions()?;
+            token_interface::create_associated_token_account(
+                admin,
+                vault,
+                &pool.to_account_info(),
+                mint,
+                system_program,
+                token_program,
+                associated_token_program,
+            )?;
+        }
 
         pool.admin = admin.key();
         pool.seed = seed;
         pool.bump = pool_bump;
         pool.lp_bump = lp_bump;
-        pool.token_mint_1 = token_mint_1.key();
-        pool.token_mint_2 = token_mint_2.key();
-        pool.token_vault_1 = token_vault_1.key();
-        pool.token_vault_2 = token_vault_2.key();
+        pool.token_mint_1 = token_mint_1;
+        pool.token_mint_2 = token_mint_2;
+        pool.token_vault_1 = token_vault_1;
+        pool.token_vault_2 = token_vault_2;
         pool.mint = lp_token_mint.key();
         pool.fee_vault = lp_token_ata_fee.key();
         pool.fees = fees;
+        pool.flash_fees = flash_fees;
         pool.curve = curve;
+        pool.set_config_delay(config_delay)?;
+        pool.fee_manager = global_config.fee_recipient;
+
+        registry_page.register(RegistryEntry {
+            pool: pool.key(),
+            token_mint_1: pool.token_mint_1,
+            token_mint_2: pool.token_mint_2,
+            curve_type: pool.curve.curve_type(),
+            trade_fee_numerator: pool.fees.trade_fee_numerator,
+            trade_fee_denominator: pool.fees.trade_fee_denominator,
+        })?;
     }
 }
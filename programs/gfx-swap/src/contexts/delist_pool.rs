@@ -0,0 +1,32 @@
+use crate::errors::ErrorCode::*;
+use crate::states::{DerivedAccountIdentifier, Pool, PoolRegistryPage};
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+#[derive(Accounts)]
+pub struct DelistPool<'info> {
+    #[account(
+        seeds = [Pool::IDENT, &pool.seed],
+        bump = pool.bump,
+        has_one = admin @ WrongAdmin
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(mut)]
+    pub registry_page: Account<'info, PoolRegistryPage>,
+
+    pub admin: UncheckedAccount<'info>, // a signer or an AdminMultisig
+}
+
+impl<'info> DelistPool<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self) {
+        let DelistPool {
+            pool,
+            registry_page,
+            ..
+        } = self;
+
+        registry_page.delist(&pool.key())?;
+    }
+}
@@ -1,6 +1,10 @@
+use super::{__client_accounts_temp_wsol, __cpi_client_accounts_temp_wsol, TempWsol};
 use crate::curve::{CurveCalculator, TradeDirection};
 use crate::errors::ErrorCode::*;
-use crate::states::{DerivedAccountIdentifier, LPMint, Pool, PoolExt};
+use crate::states::{
+    AllowlistEntry, DerivedAccountIdentifier, GlobalConfig, LPMint, Pool, PoolExt,
+};
+use crate::token_interface::{CpiAccount, InterfaceMint, InterfaceTokenAccount};
 use crate::utils::{self, PubkeyPairExt, TupleExt};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
@@ -9,22 +13,25 @@ use fehler::{throw, throws};
 
 #[derive(Accounts)]
 pub struct Deposit1<'info> {
-    #[account(seeds = [Pool::IDENT, &pool.seed], bump = pool.bump)]
+    #[account(mut, seeds = [Pool::IDENT, &pool.seed], bump = pool.bump)]
     pub pool: Account<'info, Pool>,
 
+    #[account(seeds = [GlobalConfig::IDENT], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
     #[account(
         mut,
         constraint = token_a_vault.owner == pool.key() @ WrongATAOwner,
         constraint = (pool.token_vault_1, pool.token_vault_2).contains(&token_a_vault.key()) @ TokenNotSupportedByPool,
     )]
-    pub token_a_vault: Box<Account<'info, TokenAccount>>,
+    pub token_a_vault: CpiAccount<'info, InterfaceTokenAccount>,
     #[account(
         mut,
         constraint = token_b_vault.owner == pool.key() @ WrongATAOwner,
         constraint = (pool.token_vault_1, pool.token_vault_2).contains(&token_b_vault.key()) @ TokenNotSupportedByPool,
         constraint = token_a_vault.mint != token_b_vault.mint @ SameToken,
     )]
-    pub token_b_vault: Box<Account<'info, TokenAccount>>,
+    pub token_b_vault: CpiAccount<'info, InterfaceTokenAccount>,
 
     #[account(
         mut,
@@ -34,12 +41,14 @@ pub struct Deposit1<'info> {
     )]
     pub lp_token_mint: Box<Account<'info, Mint>>,
 
+    // owned by the user wallet or delegated to it, see `utils::check_user_authority`
     #[account(
         mut,
-        constraint = in_token_ata_user.owner == user_wallet.key() @ WrongATAOwner,
         constraint = (pool.token_mint_1, pool.token_mint_2).contains(&in_token_ata_user.mint) @ TokenNotSupportedByPool,
     )]
-    pub in_token_ata_user: Box<Account<'info, TokenAccount>>,
+    pub in_token_ata_user: CpiAccount<'info, InterfaceTokenAccount>,
+    #[account(constraint = in_token_mint.key() == in_token_ata_user.mint @ TokenNotSupportedByPool)]
+    pub in_token_mint: CpiAccount<'info, InterfaceMint>,
 
     #[account(
         init_if_needed,
@@ -49,11 +58,17 @@ pub struct Deposit1<'info> {
     )]
     pub user_lp_ata: Box<Account<'info, TokenAccount>>,
 
+    #[account(mut)] // pays and receives the lamports of the native SOL variants
     pub user_wallet: Signer<'info>,
 
+    // only read when the pool is permissioned, see `allowlisted`
+    #[account(seeds = [AllowlistEntry::IDENT, pool.key().as_ref(), user_wallet.key().as_ref()], bump)]
+    pub allowlist_entry: UncheckedAccount<'info>,
+
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Program<'info, Token>, // of the LP token
+    pub in_token_program: UncheckedAccount<'info>, // of the input mint
     pub rent: Sysvar<'info, Rent>,
 }
 
@@ -67,8 +82,10 @@ impl<'info> Deposit1<'info> {
             lp_token_mint,
             user_wallet,
             in_token_ata_user,
+            in_token_mint,
             user_lp_ata,
             token_program,
+            in_token_program,
             ..
         } = self;
         let (token1_ata_pool, token2_ata_pool) =
@@ -82,11 +99,14 @@ impl<'info> Deposit1<'info> {
             throw!(IncorrectSwapAccount);
         };
 
+        // the fee of a Token-2022 mint never reaches the pool
+        let received_amount = in_token_mint.amount_received(in_token_amount)?;
+
         let lp_token_supply = utils::to_u128(lp_token_mint.supply)?;
         let lp_token_amount = if lp_token_supply > 0 {
             pool.curve
                 .deposit_single_token_type(
-                    utils::to_u128(in_token_amount)?,
+                    utils::to_u128(received_amount)?,
                     utils::to_u128(token1_ata_pool.amount)?,
                     utils::to_u128(token2_ata_pool.amount)?,
                     lp_token_supply,
@@ -106,13 +126,31 @@ impl<'info> Deposit1<'info> {
             throw!(ZeroTradingTokens);
         }
 
+        let reserves_before = (token1_ata_pool.amount, token2_ata_pool.amount);
+        let mut reserves_after = reserves_before;
+        let reserve = match trade_direction {
+            TradeDirection::AtoB => &mut reserves_after.0,
+            TradeDirection::BtoA => &mut reserves_after.1,
+        };
+        *reserve = reserve
+            .checked_add(received_amount)
+            .ok_or(CalculationFailure)?;
+        pool.record_reserve_change(reserves_before, reserves_after, Clock::get()?.slot)?;
+        pool.limits.check_lp_supply(
+            lp_token_mint
+                .supply
+                .checked_add(lp_token_amount)
+                .ok_or(CalculationFailure)?,
+        )?;
+
         match trade_direction {
             TradeDirection::AtoB => {
                 pool.transfer_to_pool(
                     user_wallet,
                     in_token_ata_user,
                     token1_ata_pool,
-                    token_program,
+                    in_token_mint,
+                    in_token_program,
                     in_token_amount,
                 )?;
             }
@@ -121,7 +159,8 @@ impl<'info> Deposit1<'info> {
                     user_wallet,
                     in_token_ata_user,
                     token2_ata_pool,
-                    token_program,
+                    in_token_mint,
+                    in_token_program,
                     in_token_amount,
                 )?;
             }
@@ -130,3 +169,23 @@ impl<'info> Deposit1<'info> {
         pool.mint_lp_to(lp_token_mint, user_lp_ata, token_program, lp_token_amount)?;
     }
 }
+
+// `deposit1_native` deposits native SOL through a temporary WSOL account
+#[derive(Accounts)]
+pub struct Deposit1Native<'info> {
+    pub temp_wsol: TempWsol<'info>,
+    pub deposit: Deposit1<'info>,
+}
+
+impl<'info> Deposit1Native<'info> {
+    // Wraps the deposited lamports, the input token has to be SOL
+    #[throws(ProgramError)]
+    pub fn wrap_sol(&self, in_token_amount: u64) {
+        require!(
+            self.temp_wsol.is(&self.deposit.in_token_ata_user),
+            NoNativeToken
+        );
+
+        self.temp_wsol.wrap(in_token_amount)?;
+    }
+}
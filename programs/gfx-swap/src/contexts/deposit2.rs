@@ -1,6 +1,10 @@
+use super::{__client_accounts_temp_wsol, __cpi_client_accounts_temp_wsol, TempWsol};
 use crate::curve::{CurveCalculator, RoundDirection};
 use crate::errors::ErrorCode::*;
-use crate::states::{DerivedAccountIdentifier, LPMint, Pool, PoolExt};
+use crate::states::{
+    AllowlistEntry, DerivedAccountIdentifier, GlobalConfig, LPMint, Pool, PoolExt,
+};
+use crate::token_interface::{CpiAccount, InterfaceMint, InterfaceTokenAccount};
 use crate::utils::{self, PubkeyPairExt, TupleExt};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
@@ -9,22 +13,25 @@ use fehler::throws;
 
 #[derive(Accounts)]
 pub struct Deposit2<'info> {
-    #[account(seeds = [Pool::IDENT, &pool.seed], bump = pool.bump)]
+    #[account(mut, seeds = [Pool::IDENT, &pool.seed], bump = pool.bump)]
     pub pool: Account<'info, Pool>,
 
+    #[account(seeds = [GlobalConfig::IDENT], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
     #[account(
         mut,
         constraint = token_a_vault.owner == pool.key() @ WrongATAOwner,
         constraint = (pool.token_vault_1, pool.token_vault_2).contains(&token_a_vault.key()) @ TokenNotSupportedByPool
     )]
-    pub token_a_vault: Box<Account<'info, TokenAccount>>,
+    pub token_a_vault: CpiAccount<'info, InterfaceTokenAccount>,
     #[account(
         mut,
         constraint = token_b_vault.owner == pool.key() @ WrongATAOwner,
         constraint = (pool.token_vault_1, pool.token_vault_2).contains(&token_b_vault.key()) @ TokenNotSupportedByPool,
         constraint = token_a_vault.mint != token_b_vault.mint @ SameToken,
     )]
-    pub token_b_vault: Box<Account<'info, TokenAccount>>,
+    pub token_b_vault: CpiAccount<'info, InterfaceTokenAccount>,
 
     #[account(
         mut,
@@ -34,19 +41,23 @@ pub struct Deposit2<'info> {
     )]
     pub lp_token_mint: Box<Account<'info, Mint>>,
 
+    // owned by the user wallet or delegated to it, see `utils::check_user_authority`
     #[account(
         mut,
-        constraint = user_token_a_ata.owner == user_wallet.key() @ WrongATAOwner,
         constraint = (pool.token_mint_1, pool.token_mint_2).contains(&user_token_a_ata.mint) @ TokenNotSupportedByPool,
     )]
-    pub user_token_a_ata: Box<Account<'info, TokenAccount>>, // let the spl program check the ownership
+    pub user_token_a_ata: CpiAccount<'info, InterfaceTokenAccount>, // let the spl program check the ownership
     #[account(
         mut,
-        constraint = user_token_b_ata.owner == user_wallet.key() @ WrongATAOwner,
         constraint = (pool.token_mint_1, pool.token_mint_2).contains(&user_token_b_ata.mint) @ TokenNotSupportedByPool,
         constraint = user_token_a_ata.mint != user_token_b_ata.mint @ SameToken,
     )]
-    pub user_token_b_ata: Box<Account<'info, TokenAccount>>, // let the spl program check the ownership
+    pub user_token_b_ata: CpiAccount<'info, InterfaceTokenAccount>, // let the spl program check the ownership
+
+    #[account(constraint = token_a_mint.key() == user_token_a_ata.mint @ TokenNotSupportedByPool)]
+    pub token_a_mint: CpiAccount<'info, InterfaceMint>,
+    #[account(constraint = token_b_mint.key() == user_token_b_ata.mint @ TokenNotSupportedByPool)]
+    pub token_b_mint: CpiAccount<'info, InterfaceMint>,
 
     #[account(
         init_if_needed,
@@ -56,10 +67,18 @@ pub struct Deposit2<'info> {
     )]
     pub user_lp_ata: Box<Account<'info, TokenAccount>>,
 
+    #[account(mut)] // pays and receives the lamports of the native SOL variants
     pub user_wallet: Signer<'info>,
 
+    // only read when the pool is permissioned, see `allowlisted`
+    #[account(seeds = [AllowlistEntry::IDENT, pool.key().as_ref(), user_wallet.key().as_ref()], bump)]
+    pub allowlist_entry: UncheckedAccount<'info>,
+
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Program<'info, Token>, // of the LP token
+    // of the mints, checked by `token_interface::transfer_checked`
+    pub token_a_program: UncheckedAccount<'info>,
+    pub token_b_program: UncheckedAccount<'info>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -80,8 +99,12 @@ impl<'info> Deposit2<'info> {
             user_wallet,
             user_token_a_ata,
             user_token_b_ata,
+            token_a_mint,
+            token_b_mint,
             user_lp_ata,
             token_program,
+            token_a_program,
+            token_b_program,
             ..
         } = self;
 
@@ -93,6 +116,9 @@ impl<'info> Deposit2<'info> {
                 .sort(maximum_token_a_amount, maximum_token_b_amount)?;
         let (user_token1_ata, user_token2_ata) = (user_token_a_ata.mint, user_token_b_ata.mint)
             .sort(user_token_a_ata, user_token_b_ata)?;
+        let mints = (token_a_mint.key(), token_b_mint.key());
+        let (token1_mint, token2_mint) = mints.sort(token_a_mint, token_b_mint)?;
+        let (token1_program, token2_program) = mints.sort(token_a_program, token_b_program)?;
 
         let calculator = &pool.curve;
         require!(calculator.allows_deposits(), UnsupportedCurveOperation);
@@ -114,22 +140,32 @@ impl<'info> Deposit2<'info> {
             )
             .ok_or(ZeroTradingTokens)?;
 
+        // the vaults have to receive the amounts, the user also pays the fee of a Token-2022 mint
         let token1_amount = utils::to_u64(results.token1_amount)?;
-        require!(token1_amount < maximum_token1_amount, ExceededSlippage);
         require!(token1_amount != 0, ZeroTradingTokens);
+        let token1_amount = token1_mint.amount_to_send(token1_amount)?;
+        require!(token1_amount < maximum_token1_amount, ExceededSlippage);
 
         let token2_amount = utils::to_u64(results.token2_amount)?;
-        require!(token2_amount < maximum_token2_amount, ExceededSlippage);
         require!(token2_amount != 0, ZeroTradingTokens);
+        let token2_amount = token2_mint.amount_to_send(token2_amount)?;
+        require!(token2_amount < maximum_token2_amount, ExceededSlippage);
 
         let lp_token_amount = utils::to_u64(lp_token_amount)?;
+        pool.limits.check_lp_supply(
+            lp_token_mint
+                .supply
+                .checked_add(lp_token_amount)
+                .ok_or(CalculationFailure)?,
+        )?;
 
         // transfer token_a to the pool
         pool.transfer_to_pool(
             user_wallet,
             user_token1_ata,
             token1_ata_pool,
-            token_program,
+            token1_mint,
+            token1_program,
             token1_amount,
         )?;
 
@@ -138,11 +174,253 @@ impl<'info> Deposit2<'info> {
             user_wallet,
             user_token2_ata,
             token2_ata_pool,
-            token_program,
+            token2_mint,
+            token2_program,
             token2_amount,
         )?;
 
         // mint some lp_token to the user
         pool.mint_lp_to(lp_token_mint, user_lp_ata, token_program, lp_token_amount)?;
     }
+
+    // Deposits at most `amount_a` and `amount_b` for the largest LP amount they cover at the pool ratio.
+    // The LP amount is rounded down and the token amounts up, the rest stays with the user.
+    #[throws(ProgramError)]
+    pub fn process_exact_tokens(
+        &mut self,
+        amount_a: u64,
+        amount_b: u64,
+        minimum_lp_token_amount: u64,
+    ) {
+        let Deposit2 {
+            pool,
+            token_a_vault,
+            token_b_vault,
+            lp_token_mint,
+            user_wallet,
+            user_token_a_ata,
+            user_token_b_ata,
+            token_a_mint,
+            token_b_mint,
+            user_lp_ata,
+            token_program,
+            token_a_program,
+            token_b_program,
+            ..
+        } = self;
+
+        // sort the tokens into the increasing order based on address
+        let (token1_ata_pool, token2_ata_pool) =
+            (token_a_vault.mint, token_b_vault.mint).sort(token_a_vault, token_b_vault)?;
+        let (amount1, amount2) =
+            (user_token_a_ata.mint, user_token_b_ata.mint).sort(amount_a, amount_b)?;
+        let (user_token1_ata, user_token2_ata) = (user_token_a_ata.mint, user_token_b_ata.mint)
+            .sort(user_token_a_ata, user_token_b_ata)?;
+        let mints = (token_a_mint.key(), token_b_mint.key());
+        let (token1_mint, token2_mint) = mints.sort(token_a_mint, token_b_mint)?;
+        let (token1_program, token2_program) = mints.sort(token_a_program, token_b_program)?;
+        // what reaches the vaults after the fee of a Token-2022 mint
+        let (amount1, amount2) = (
+            token1_mint.amount_received(amount1)?,
+            token2_mint.amount_received(amount2)?,
+        );
+
+        let calculator = &pool.curve;
+        require!(calculator.allows_deposits(), UnsupportedCurveOperation);
+
+        // the first deposit sets the ratio of the pool, use `deposit2` for it
+        let lp_supply = utils::to_u128(lp_token_mint.supply)?;
+        require!(lp_supply > 0, ZeroTradingTokens);
+
+        let reserve1 = utils::to_u128(token1_ata_pool.amount)?;
+        let reserve2 = utils::to_u128(token2_ata_pool.amount)?;
+        let lp_for = |amount: u64, reserve: u128| {
+            utils::to_u128(amount)
+                .ok()?
+                .checked_mul(lp_supply)?
+                .checked_div(reserve)
+        };
+        let lp_token_amount = std::cmp::min(
+            lp_for(amount1, reserve1).ok_or(CalculationFailure)?,
+            lp_for(amount2, reserve2).ok_or(CalculationFailure)?,
+        );
+
+        let results = calculator
+            .pool_tokens_to_trading_tokens(
+                lp_token_amount,
+                lp_supply,
+                reserve1,
+                reserve2,
+                RoundDirection::Ceiling,
+            )
+            .ok_or(ZeroTradingTokens)?;
+
+        let token1_amount = utils::to_u64(results.token1_amount)?;
+        let token2_amount = utils::to_u64(results.token2_amount)?;
+        require!(token1_amount != 0 && token2_amount != 0, ZeroTradingTokens);
+        // rounding up a proportional amount never goes above the amount it is proportional to
+        require!(
+            token1_amount <= amount1 && token2_amount <= amount2,
+            CalculationFailure
+        );
+
+        let lp_token_amount = utils::to_u64(lp_token_amount)?;
+        require!(lp_token_amount >= minimum_lp_token_amount, ExceededSlippage);
+        pool.limits.check_lp_supply(
+            lp_token_mint
+                .supply
+                .checked_add(lp_token_amount)
+                .ok_or(CalculationFailure)?,
+        )?;
+
+        pool.transfer_to_pool(
+            user_wallet,
+            user_token1_ata,
+            token1_ata_pool,
+            token1_mint,
+            token1_program,
+            token1_mint.amount_to_send(token1_amount)?,
+        )?;
+        pool.transfer_to_pool(
+            user_wallet,
+            user_token2_ata,
+            token2_ata_pool,
+            token2_mint,
+            token2_program,
+            token2_mint.amount_to_send(token2_amount)?,
+        )?;
+
+        pool.mint_lp_to(lp_token_mint, user_lp_ata, token_program, lp_token_amount)?;
+    }
+
+    // Deposits exactly `amount_a` and `amount_b` in any ratio, see `SwapCurve::deposit_imbalanced`.
+    // The part that deviates from the pool ratio pays the imbalance fee.
+    #[throws(ProgramError)]
+    pub fn process_imbalanced(
+        &mut self,
+        amount_a: u64,
+        amount_b: u64,
+        minimum_lp_token_amount: u64,
+    ) {
+        let Deposit2 {
+            pool,
+            token_a_vault,
+            token_b_vault,
+            lp_token_mint,
+            user_wallet,
+            user_token_a_ata,
+            user_token_b_ata,
+            token_a_mint,
+            token_b_mint,
+            user_lp_ata,
+            token_program,
+            token_a_program,
+            token_b_program,
+            ..
+        } = self;
+
+        // sort the tokens into the increasing order based on address
+        let (token1_ata_pool, token2_ata_pool) =
+            (token_a_vault.mint, token_b_vault.mint).sort(token_a_vault, token_b_vault)?;
+        let (amount1, amount2) =
+            (user_token_a_ata.mint, user_token_b_ata.mint).sort(amount_a, amount_b)?;
+        let (user_token1_ata, user_token2_ata) = (user_token_a_ata.mint, user_token_b_ata.mint)
+            .sort(user_token_a_ata, user_token_b_ata)?;
+        let mints = (token_a_mint.key(), token_b_mint.key());
+        let (token1_mint, token2_mint) = mints.sort(token_a_mint, token_b_mint)?;
+        let (token1_program, token2_program) = mints.sort(token_a_program, token_b_program)?;
+        // what reaches the vaults after the fee of a Token-2022 mint
+        let (amount1, amount2) = (
+            token1_mint.amount_received(amount1)?,
+            token2_mint.amount_received(amount2)?,
+        );
+
+        let calculator = &pool.curve;
+        require!(calculator.allows_deposits(), UnsupportedCurveOperation);
+
+        // the first deposit sets the ratio of the pool, use `deposit2` for it
+        let lp_supply = utils::to_u128(lp_token_mint.supply)?;
+        require!(lp_supply > 0, ZeroTradingTokens);
+
+        let lp_token_amount = calculator
+            .deposit_imbalanced(
+                utils::to_u128(amount1)?,
+                utils::to_u128(amount2)?,
+                utils::to_u128(token1_ata_pool.amount)?,
+                utils::to_u128(token2_ata_pool.amount)?,
+                lp_supply,
+                &pool.fees,
+            )
+            .ok_or(CalculationFailure)?;
+        let lp_token_amount = utils::to_u64(lp_token_amount)?;
+        require!(lp_token_amount != 0, ZeroTradingTokens);
+        require!(lp_token_amount >= minimum_lp_token_amount, ExceededSlippage);
+        let reserves_before = (token1_ata_pool.amount, token2_ata_pool.amount);
+        let reserves_after = (
+            reserves_before
+                .0
+                .checked_add(amount1)
+                .ok_or(CalculationFailure)?,
+            reserves_before
+                .1
+                .checked_add(amount2)
+                .ok_or(CalculationFailure)?,
+        );
+        pool.record_reserve_change(reserves_before, reserves_after, Clock::get()?.slot)?;
+        pool.limits.check_lp_supply(
+            lp_token_mint
+                .supply
+                .checked_add(lp_token_amount)
+                .ok_or(CalculationFailure)?,
+        )?;
+
+        if amount1 > 0 {
+            pool.transfer_to_pool(
+                user_wallet,
+                user_token1_ata,
+                token1_ata_pool,
+                token1_mint,
+                token1_program,
+                token1_mint.amount_to_send(amount1)?,
+            )?;
+        }
+        if amount2 > 0 {
+            pool.transfer_to_pool(
+                user_wallet,
+                user_token2_ata,
+                token2_ata_pool,
+                token2_mint,
+                token2_program,
+                token2_mint.amount_to_send(amount2)?,
+            )?;
+        }
+
+        pool.mint_lp_to(lp_token_mint, user_lp_ata, token_program, lp_token_amount)?;
+    }
+}
+
+// `deposit2_native` deposits native SOL on one side through a temporary WSOL account
+#[derive(Accounts)]
+pub struct Deposit2Native<'info> {
+    pub temp_wsol: TempWsol<'info>,
+    pub deposit: Deposit2<'info>,
+}
+
+impl<'info> Deposit2Native<'info> {
+    // Wraps the maximum amount of SOL, the unused part is unwrapped with the account
+    #[throws(ProgramError)]
+    pub fn wrap_sol(&self, maximum_token_a_amount: u64, maximum_token_b_amount: u64) {
+        let Deposit2 {
+            user_token_a_ata,
+            user_token_b_ata,
+            ..
+        } = &self.deposit;
+
+        if self.temp_wsol.is(user_token_a_ata) {
+            self.temp_wsol.wrap(maximum_token_a_amount)?;
+        } else {
+            require!(self.temp_wsol.is(user_token_b_ata), NoNativeToken);
+            self.temp_wsol.wrap(maximum_token_b_amount)?;
+        }
+    }
 }
@@ -0,0 +1,43 @@
+use crate::states::{DerivedAccountIdentifier, Pool, QueuedConfig};
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+// Anyone can execute a queued config once its eta has passed.
+#[derive(Accounts)]
+pub struct ExecuteConfig<'info> {
+    #[account(
+        mut,
+        seeds = [Pool::IDENT, &pool.seed],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [QueuedConfig::IDENT, pool.key().as_ref()],
+        bump = queued_config.bump,
+        has_one = payer,
+        close = payer,
+    )]
+    pub queued_config: Account<'info, QueuedConfig>,
+
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+impl<'info> ExecuteConfig<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self) {
+        let ExecuteConfig {
+            pool,
+            queued_config,
+            clock,
+            ..
+        } = self;
+
+        require!(queued_config.is_ready(clock.unix_timestamp), ConfigNotReady);
+        pool.config(&queued_config.config)?;
+    }
+}
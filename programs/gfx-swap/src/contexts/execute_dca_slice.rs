@@ -0,0 +1,174 @@
+use crate::curve::TradeDirection;
+use crate::errors::ErrorCode::*;
+use crate::states::{
+    split_proceeds, DcaOrder, DerivedAccountIdentifier, EscrowExt, GlobalConfig, LPMint, Pool,
+    PoolExt,
+};
+use crate::token_interface::{
+    associated_token_address, CpiAccount, InterfaceMint, InterfaceTokenAccount,
+};
+use crate::utils::TupleExt;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use fehler::throws;
+
+// The pool accounts are checked the same way as the ones of `swap`
+#[derive(Accounts)]
+pub struct ExecuteDcaSlice<'info> {
+    #[account(mut, seeds = [Pool::IDENT, &pool.seed], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+
+    #[account(seeds = [GlobalConfig::IDENT], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        constraint = in_token_vault.owner == pool.key() @ WrongATAOwner,
+        constraint = (pool.token_vault_1, pool.token_vault_2).contains(&in_token_vault.key()) @ TokenNotSupportedByPool,
+        constraint = in_token_vault.mint == order.in_mint @ TokenNotSupportedByPool,
+    )]
+    pub in_token_vault: CpiAccount<'info, InterfaceTokenAccount>,
+    #[account(
+        mut,
+        constraint = out_token_vault.owner == pool.key() @ WrongATAOwner,
+        constraint = (pool.token_vault_1, pool.token_vault_2).contains(&out_token_vault.key()) @ TokenNotSupportedByPool,
+        constraint = in_token_vault.mint != out_token_vault.mint @ SameToken,
+    )]
+    pub out_token_vault: CpiAccount<'info, InterfaceTokenAccount>,
+
+    #[account(constraint = in_token_mint.key() == order.in_mint @ TokenNotSupportedByPool)]
+    pub in_token_mint: CpiAccount<'info, InterfaceMint>,
+    #[account(constraint = out_token_mint.key() == order.out_mint @ TokenNotSupportedByPool)]
+    pub out_token_mint: CpiAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        seeds = [LPMint::IDENT, &pool.seed],
+        bump = pool.lp_bump,
+        constraint = pool.mint == lp_token_mint.key() @ WrongLPMint,
+    )]
+    pub lp_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = fee_vault.owner == pool.key() @ WrongFeeVault,
+        constraint = pool.fee_vault == fee_vault.key() @ WrongFeeVault,
+    )]
+    pub fee_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [DcaOrder::IDENT, pool.key().as_ref(), order.owner.as_ref(), &order.id.to_le_bytes()],
+        bump = order.bump,
+    )]
+    pub order: Box<Account<'info, DcaOrder>>,
+    #[account(
+        mut,
+        constraint = escrow.key() == associated_token_address(&order.key(), &order.in_mint, in_token_program.key) @ WrongATAOwner,
+    )]
+    pub escrow: CpiAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_out_token_ata.owner == order.owner @ WrongATAOwner,
+        constraint = owner_out_token_ata.mint == order.out_mint @ TokenNotSupportedByPool,
+    )]
+    pub owner_out_token_ata: CpiAccount<'info, InterfaceTokenAccount>,
+    // receives the keeper fee, it does not have to be owned by the keeper
+    #[account(
+        mut,
+        constraint = keeper_out_token_ata.mint == order.out_mint @ TokenNotSupportedByPool,
+    )]
+    pub keeper_out_token_ata: CpiAccount<'info, InterfaceTokenAccount>,
+
+    pub keeper: Signer<'info>,
+    pub token_program: Program<'info, Token>, // of the LP token
+    // of the mints, checked by `token_interface::transfer_checked`
+    pub in_token_program: UncheckedAccount<'info>,
+    pub out_token_program: UncheckedAccount<'info>,
+}
+
+impl<'info> ExecuteDcaSlice<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self) {
+        let ExecuteDcaSlice {
+            pool,
+            in_token_vault,
+            out_token_vault,
+            lp_token_mint,
+            fee_vault,
+            order,
+            escrow,
+            owner_out_token_ata,
+            keeper_out_token_ata,
+            in_token_mint,
+            out_token_mint,
+            token_program,
+            in_token_program,
+            out_token_program,
+            ..
+        } = self;
+
+        let clock = Clock::get()?;
+        require!(order.is_due(clock.unix_timestamp), SliceNotDue);
+
+        let amount_in = std::cmp::min(order.slice_amount, escrow.amount);
+        require!(amount_in > 0, InvalidOrder);
+
+        let trade_direction = if order.in_mint == pool.token_mint_1 {
+            TradeDirection::AtoB
+        } else {
+            TradeDirection::BtoA
+        };
+
+        // the curve trades what the pool receives and the minimum is on what the owner receives,
+        // both without the fees of a Token-2022 mint
+        let quote = pool.quote_swap(
+            trade_direction,
+            in_token_mint.amount_received(amount_in)?,
+            in_token_vault.amount,
+            out_token_vault.amount,
+            lp_token_mint.supply,
+            clock.slot,
+        )?;
+        let amount_in = in_token_mint.amount_to_send(quote.amount_in)?;
+        let (owner_amount, keeper_fee) = split_proceeds(quote.amount_out, order.keeper_fee_bps)?;
+        require!(
+            out_token_mint.amount_received(owner_amount)? >= order.minimum_amount_out(amount_in)?,
+            OrderNotFillable
+        );
+
+        order.transfer_from_escrow(
+            escrow,
+            in_token_vault,
+            in_token_mint,
+            in_token_program,
+            amount_in,
+        )?;
+        pool.transfer_to_user(
+            out_token_vault,
+            owner_out_token_ata,
+            out_token_mint,
+            out_token_program,
+            owner_amount,
+        )?;
+        if keeper_fee > 0 {
+            pool.transfer_to_user(
+                out_token_vault,
+                keeper_out_token_ata,
+                out_token_mint,
+                out_token_program,
+                keeper_fee,
+            )?;
+        }
+        if quote.owner_fee > 0 {
+            pool.mint_lp_to(lp_token_mint, fee_vault, token_program, quote.owner_fee)?;
+        }
+
+        order.last_slice_at = clock.unix_timestamp;
+        order.executed_amount = order
+            .executed_amount
+            .checked_add(amount_in)
+            .ok_or(CalculationFailure)?;
+    }
+}
@@ -0,0 +1,218 @@
+use crate::curve::TradeDirection;
+use crate::errors::ErrorCode::*;
+use crate::states::{
+    split_proceeds, DerivedAccountIdentifier, EscrowExt, GlobalConfig, LimitOrder, Pool, PoolExt,
+};
+use crate::token_interface::{
+    associated_token_address, CpiAccount, InterfaceMint, InterfaceTokenAccount,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::AccountsClose;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use fehler::{throw, throws};
+
+// Every order passes its accounts in the remaining accounts, in this order:
+// order (mut), escrow (mut), maker (mut), maker_out_token_ata (mut)
+pub const FILL_ORDER_ACCOUNTS: usize = 4;
+
+#[derive(Accounts)]
+pub struct FillOrders<'info> {
+    #[account(mut, seeds = [Pool::IDENT, &pool.seed], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+
+    #[account(seeds = [GlobalConfig::IDENT], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(mut, constraint = token_vault_1.key() == pool.token_vault_1 @ TokenNotSupportedByPool)]
+    pub token_vault_1: CpiAccount<'info, InterfaceTokenAccount>,
+    #[account(mut, constraint = token_vault_2.key() == pool.token_vault_2 @ TokenNotSupportedByPool)]
+    pub token_vault_2: CpiAccount<'info, InterfaceTokenAccount>,
+
+    // written when the escrows withhold transfer fees, see `token_interface::close_account`
+    #[account(mut, constraint = token_mint_1.key() == pool.token_mint_1 @ TokenNotSupportedByPool)]
+    pub token_mint_1: CpiAccount<'info, InterfaceMint>,
+    #[account(mut, constraint = token_mint_2.key() == pool.token_mint_2 @ TokenNotSupportedByPool)]
+    pub token_mint_2: CpiAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        constraint = pool.mint == lp_token_mint.key() @ WrongLPMint,
+    )]
+    pub lp_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = pool.fee_vault == fee_vault.key() @ WrongFeeVault,
+    )]
+    pub fee_vault: Box<Account<'info, TokenAccount>>,
+
+    // receive the bounties, they do not have to be owned by the keeper
+    #[account(mut, constraint = keeper_token_1_ata.mint == pool.token_mint_1 @ TokenNotSupportedByPool)]
+    pub keeper_token_1_ata: CpiAccount<'info, InterfaceTokenAccount>,
+    #[account(mut, constraint = keeper_token_2_ata.mint == pool.token_mint_2 @ TokenNotSupportedByPool)]
+    pub keeper_token_2_ata: CpiAccount<'info, InterfaceTokenAccount>,
+
+    pub keeper: Signer<'info>,
+    pub token_program: Program<'info, Token>, // of the LP token
+    // of the mints, checked by `token_interface::transfer_checked`
+    pub token_program_1: UncheckedAccount<'info>,
+    pub token_program_2: UncheckedAccount<'info>,
+}
+
+// The accounts of one order
+struct Fill<'info> {
+    order: Account<'info, LimitOrder>,
+    escrow: CpiAccount<'info, InterfaceTokenAccount>,
+    maker: AccountInfo<'info>,
+    maker_out_token_ata: CpiAccount<'info, InterfaceTokenAccount>,
+}
+
+impl<'info> Fill<'info> {
+    #[throws(ProgramError)]
+    fn load(accounts: &[AccountInfo<'info>], pool: &Pubkey) -> Self {
+        let order: Account<'info, LimitOrder> = Account::try_from(&accounts[0])?;
+        order.verify_address(&order.key())?;
+        require!(&order.pool == pool, ContractAddressNotCorrect);
+
+        // the token program owning the escrow is checked by `token_interface::transfer_checked`
+        let escrow: CpiAccount<'info, InterfaceTokenAccount> = CpiAccount::try_from(&accounts[1])?;
+        require!(
+            escrow.key()
+                == associated_token_address(&order.key(), &order.in_mint, accounts[1].owner),
+            WrongATAOwner
+        );
+
+        let maker = accounts[2].clone();
+        require!(maker.key == &order.maker, WrongATAOwner);
+
+        let maker_out_token_ata: CpiAccount<'info, InterfaceTokenAccount> =
+            CpiAccount::try_from(&accounts[3])?;
+        require!(maker_out_token_ata.owner == order.maker, WrongATAOwner);
+        require!(
+            maker_out_token_ata.mint == order.out_mint,
+            TokenNotSupportedByPool
+        );
+
+        Fill {
+            order,
+            escrow,
+            maker,
+            maker_out_token_ata,
+        }
+    }
+}
+
+impl<'info> FillOrders<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self, remaining_accounts: &[AccountInfo<'info>]) {
+        let FillOrders {
+            pool,
+            token_vault_1,
+            token_vault_2,
+            lp_token_mint,
+            fee_vault,
+            token_mint_1,
+            token_mint_2,
+            keeper_token_1_ata,
+            keeper_token_2_ata,
+            token_program,
+            token_program_1,
+            token_program_2,
+            ..
+        } = self;
+
+        let orders = remaining_accounts.chunks_exact(FILL_ORDER_ACCOUNTS);
+        require!(
+            orders.len() > 0 && orders.remainder().is_empty(),
+            InvalidOrder
+        );
+
+        let slot = Clock::get()?.slot;
+        for accounts in orders {
+            let fill = Fill::load(accounts, &pool.key())?;
+            let order = &fill.order;
+
+            let (trade_direction, in_vault, out_vault, keeper_ata) =
+                if order.in_mint == pool.token_mint_1 {
+                    (
+                        TradeDirection::AtoB,
+                        &mut *token_vault_1,
+                        &mut *token_vault_2,
+                        &*keeper_token_2_ata,
+                    )
+                } else if order.in_mint == pool.token_mint_2 {
+                    (
+                        TradeDirection::BtoA,
+                        &mut *token_vault_2,
+                        &mut *token_vault_1,
+                        &*keeper_token_1_ata,
+                    )
+                } else {
+                    throw!(TokenNotSupportedByPool);
+                };
+            let ((in_mint, in_program), (out_mint, out_program)) = match trade_direction {
+                TradeDirection::AtoB => (
+                    (&*token_mint_1, &*token_program_1),
+                    (&*token_mint_2, &*token_program_2),
+                ),
+                TradeDirection::BtoA => (
+                    (&*token_mint_2, &*token_program_2),
+                    (&*token_mint_1, &*token_program_1),
+                ),
+            };
+
+            // the whole escrow is sold, including anything sent to it besides the order. The pool
+            // receives it and the maker its share without the fees of a Token-2022 mint.
+            let amount_in = in_mint.amount_received(fill.escrow.amount)?;
+            let quote = pool.quote_swap(
+                trade_direction,
+                amount_in,
+                in_vault.amount,
+                out_vault.amount,
+                lp_token_mint.supply,
+                slot,
+            )?;
+            let (maker_amount, bounty) = split_proceeds(quote.amount_out, order.bounty_bps)?;
+            require!(
+                quote.amount_in == amount_in
+                    && out_mint.amount_received(maker_amount)? >= order.minimum_amount_out,
+                OrderNotFillable
+            );
+
+            order.transfer_from_escrow(
+                &fill.escrow,
+                in_vault,
+                in_mint,
+                in_program,
+                fill.escrow.amount,
+            )?;
+            pool.transfer_to_user(
+                out_vault,
+                &fill.maker_out_token_ata,
+                out_mint,
+                out_program,
+                maker_amount,
+            )?;
+            if bounty > 0 {
+                pool.transfer_to_user(out_vault, keeper_ata, out_mint, out_program, bounty)?;
+            }
+            if quote.owner_fee > 0 {
+                pool.mint_lp_to(lp_token_mint, fee_vault, token_program, quote.owner_fee)?;
+            }
+
+            // the rent of both accounts goes back to the maker
+            order.close_escrow(&fill.escrow, in_mint, &fill.maker, in_program)?;
+            order.close(fill.maker.clone())?;
+
+            // the orders after a trip wait until the swaps are resumed
+            if pool.circuit_breaker.is_tripped() {
+                break;
+            }
+
+            // the next order is priced against the new reserves
+            in_vault.reload()?;
+            out_vault.reload()?;
+            lp_token_mint.reload()?;
+        }
+    }
+}
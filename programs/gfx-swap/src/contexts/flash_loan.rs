@@ -0,0 +1,252 @@
+use crate::errors::ErrorCode::*;
+use crate::states::{
+    AllowlistEntry, DerivedAccountIdentifier, GlobalConfig, LPMint, Pool, PoolExt,
+};
+use crate::token_interface::{CpiAccount, InterfaceMint, InterfaceTokenAccount};
+use crate::{curve::TradeDirection, utils};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{hash::hash, instruction::Instruction, program::invoke};
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use fehler::throws;
+
+// The instruction every flash loan receiver implements. An Anchor program gets it by adding
+// `on_flash_loan(ctx, amount_1, amount_2, fee_1, fee_2, data)` to its `#[program]`.
+// The accounts are, in this order: borrower (signer), borrower_token_1 (mut), borrower_token_2 (mut),
+// token_vault_1 (mut), token_vault_2 (mut), token_mint_1, token_mint_2, token_program_1, token_program_2,
+// followed by the remaining accounts of `flash_loan`.
+// Before returning, the receiver has to transfer `amount + fee` of each token back into the vaults. With a
+// Token-2022 mint which has a transfer fee, the loan arrives without the transfer fee and the repayment has
+// to cover the transfer fee as well.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct OnFlashLoan {
+    pub amount_1: u64,
+    pub amount_2: u64,
+    pub fee_1: u64,
+    pub fee_2: u64,
+    pub data: Vec<u8>,
+}
+
+impl OnFlashLoan {
+    pub const NAME: &'static str = "on_flash_loan";
+
+    #[throws(ProgramError)]
+    pub fn data(&self) -> Vec<u8> {
+        let mut data = hash(format!("global:{}", Self::NAME).as_bytes()).to_bytes()[..8].to_vec();
+        data.extend(self.try_to_vec()?);
+        data
+    }
+}
+
+#[derive(Accounts)]
+pub struct FlashLoan<'info> {
+    #[account(mut, seeds = [Pool::IDENT, &pool.seed], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+
+    #[account(seeds = [GlobalConfig::IDENT], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(mut, constraint = pool.token_vault_1 == token_vault_1.key() @ TokenNotSupportedByPool)]
+    pub token_vault_1: CpiAccount<'info, InterfaceTokenAccount>,
+    #[account(mut, constraint = pool.token_vault_2 == token_vault_2.key() @ TokenNotSupportedByPool)]
+    pub token_vault_2: CpiAccount<'info, InterfaceTokenAccount>,
+
+    #[account(constraint = pool.token_mint_1 == token_mint_1.key() @ TokenNotSupportedByPool)]
+    pub token_mint_1: CpiAccount<'info, InterfaceMint>,
+    #[account(constraint = pool.token_mint_2 == token_mint_2.key() @ TokenNotSupportedByPool)]
+    pub token_mint_2: CpiAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        seeds = [LPMint::IDENT, &pool.seed],
+        bump = pool.lp_bump,
+        constraint = pool.mint == lp_token_mint.key() @ WrongLPMint,
+    )]
+    pub lp_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = fee_vault.owner == pool.key() @ WrongFeeVault,
+        constraint = pool.fee_vault == fee_vault.key() @ WrongFeeVault,
+    )]
+    pub fee_vault: Box<Account<'info, TokenAccount>>,
+
+    // the loan is sent to these accounts, the receiver repays from wherever it likes
+    #[account(mut, constraint = borrower_token_1.mint == pool.token_mint_1 @ TokenNotSupportedByPool)]
+    pub borrower_token_1: CpiAccount<'info, InterfaceTokenAccount>,
+    #[account(mut, constraint = borrower_token_2.mint == pool.token_mint_2 @ TokenNotSupportedByPool)]
+    pub borrower_token_2: CpiAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        constraint = receiver_program.executable @ InvalidFlashLoanReceiver,
+        constraint = receiver_program.key() != crate::ID @ InvalidFlashLoanReceiver,
+    )]
+    pub receiver_program: UncheckedAccount<'info>,
+
+    pub borrower: Signer<'info>,
+
+    // only read when the pool is permissioned, see `allowlisted`
+    #[account(seeds = [AllowlistEntry::IDENT, pool.key().as_ref(), borrower.key().as_ref()], bump)]
+    pub allowlist_entry: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>, // of the LP token
+    // of the mints, checked by `token_interface::transfer_checked`
+    pub token_program_1: UncheckedAccount<'info>,
+    pub token_program_2: UncheckedAccount<'info>,
+}
+
+impl<'info> FlashLoan<'info> {
+    #[throws(ProgramError)]
+    pub fn process(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+        amount_1: u64,
+        amount_2: u64,
+        data: Vec<u8>,
+    ) {
+        let FlashLoan {
+            pool,
+            token_vault_1,
+            token_vault_2,
+            lp_token_mint,
+            fee_vault,
+            borrower_token_1,
+            borrower_token_2,
+            receiver_program,
+            borrower,
+            token_mint_1,
+            token_mint_2,
+            token_program,
+            token_program_1,
+            token_program_2,
+            ..
+        } = self;
+
+        let flash_fees = pool.flash_fees;
+        let fee_1 = flash_fees
+            .flash_fee(amount_1.into())
+            .ok_or(FeeCalculationFailure)?;
+        let owner_fee_1 = flash_fees
+            .owner_flash_fee(amount_1.into())
+            .ok_or(FeeCalculationFailure)?;
+        let fee_2 = flash_fees
+            .flash_fee(amount_2.into())
+            .ok_or(FeeCalculationFailure)?;
+        let owner_fee_2 = flash_fees
+            .owner_flash_fee(amount_2.into())
+            .ok_or(FeeCalculationFailure)?;
+
+        let total_fee_1 = utils::to_u64(fee_1 + owner_fee_1)?;
+        let total_fee_2 = utils::to_u64(fee_2 + owner_fee_2)?;
+        let minimum_1 = token_vault_1
+            .amount
+            .checked_add(total_fee_1)
+            .ok_or(FeeCalculationFailure)?;
+        let minimum_2 = token_vault_2
+            .amount
+            .checked_add(total_fee_2)
+            .ok_or(FeeCalculationFailure)?;
+
+        // The lock has to be written to the account before the receiver is called,
+        // the nested instructions read the pool from there. Every instruction writing the pool
+        // checks it, their writes would be lost when this instruction saves the pool on exit.
+        pool.locked = true;
+        pool.exit(&crate::ID)?;
+
+        if amount_1 > 0 {
+            pool.transfer_to_user(
+                token_vault_1,
+                borrower_token_1,
+                token_mint_1,
+                token_program_1,
+                amount_1,
+            )?;
+        }
+        if amount_2 > 0 {
+            pool.transfer_to_user(
+                token_vault_2,
+                borrower_token_2,
+                token_mint_2,
+                token_program_2,
+                amount_2,
+            )?;
+        }
+
+        let receiver_accounts = [
+            borrower.to_account_info(),
+            borrower_token_1.to_account_info(),
+            borrower_token_2.to_account_info(),
+            token_vault_1.to_account_info(),
+            token_vault_2.to_account_info(),
+            token_mint_1.to_account_info(),
+            token_mint_2.to_account_info(),
+            token_program_1.to_account_info(),
+            token_program_2.to_account_info(),
+        ];
+        let mut accounts = vec![
+            AccountMeta::new_readonly(borrower.key(), true),
+            AccountMeta::new(borrower_token_1.key(), false),
+            AccountMeta::new(borrower_token_2.key(), false),
+            AccountMeta::new(token_vault_1.key(), false),
+            AccountMeta::new(token_vault_2.key(), false),
+            AccountMeta::new_readonly(token_mint_1.key(), false),
+            AccountMeta::new_readonly(token_mint_2.key(), false),
+            AccountMeta::new_readonly(token_program_1.key(), false),
+            AccountMeta::new_readonly(token_program_2.key(), false),
+        ];
+        accounts.extend(remaining_accounts.iter().map(|acc| AccountMeta {
+            pubkey: acc.key(),
+            is_signer: acc.is_signer,
+            is_writable: acc.is_writable,
+        }));
+        let ix = Instruction {
+            program_id: receiver_program.key(),
+            accounts,
+            data: OnFlashLoan {
+                amount_1,
+                amount_2,
+                fee_1: total_fee_1,
+                fee_2: total_fee_2,
+                data,
+            }
+            .data()?,
+        };
+        invoke(&ix, &[&receiver_accounts[..], remaining_accounts].concat())?;
+
+        token_vault_1.reload()?;
+        token_vault_2.reload()?;
+        require!(
+            token_vault_1.amount >= minimum_1 && token_vault_2.amount >= minimum_2,
+            FlashLoanNotRepaid
+        );
+
+        pool.locked = false;
+
+        // the owner fees stay in the vaults, the owner gets the equivalent in LP tokens
+        let mut owner_lp = 0;
+        for (owner_fee, trade_direction) in [
+            (owner_fee_1, TradeDirection::AtoB),
+            (owner_fee_2, TradeDirection::BtoA),
+        ] {
+            owner_lp += pool
+                .curve
+                .withdraw_single_token_type_exact_out(
+                    owner_fee,
+                    token_vault_1.amount.into(),
+                    token_vault_2.amount.into(),
+                    lp_token_mint.supply.into(),
+                    trade_direction,
+                    &pool.fees,
+                )
+                .ok_or(FeeCalculationFailure)?;
+        }
+
+        if owner_lp > 0 {
+            pool.mint_lp_to(
+                lp_token_mint,
+                fee_vault,
+                token_program,
+                utils::to_u64(owner_lp)?,
+            )?;
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::errors::ErrorCode::*;
+use crate::states::{DerivedAccountIdentifier, Farm, Pool};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount};
+use fehler::throws;
+
+#[derive(Accounts)]
+pub struct FundFarm<'info> {
+    #[account(
+        seeds = [Pool::IDENT, &pool.seed],
+        bump = pool.bump,
+        has_one = admin @ WrongAdmin
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(mut, seeds = [Farm::IDENT, pool.key().as_ref()], bump = farm.bump)]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(mut, constraint = farm.reward_vault == reward_vault.key() @ WrongRewardVault)]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = funder_reward_ata.mint == farm.reward_mint @ WrongRewardVault,
+        constraint = funder_reward_ata.owner == funder.key() @ WrongATAOwner,
+    )]
+    pub funder_reward_ata: Box<Account<'info, TokenAccount>>,
+
+    pub funder: Signer<'info>, // pays the rewards, the admin can be an AdminMultisig
+    pub admin: UncheckedAccount<'info>, // a signer or an AdminMultisig
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> FundFarm<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self, amount: u64) {
+        let FundFarm {
+            farm,
+            reward_vault,
+            funder_reward_ata,
+            funder,
+            token_program,
+            ..
+        } = self;
+
+        // the new rewards are only emitted from now on, even if the farm ran dry before
+        farm.update(Clock::get()?.unix_timestamp)?;
+        farm.rewards_left = farm
+            .rewards_left
+            .checked_add(amount)
+            .ok_or(CalculationFailure)?;
+
+        token::transfer(
+            CpiContext::new(
+                token_program.to_account_info(),
+                token::Transfer {
+                    from: funder_reward_ata.to_account_info(),
+                    to: reward_vault.to_account_info(),
+                    authority: funder.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+    }
+}
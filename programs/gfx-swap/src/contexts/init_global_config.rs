@@ -0,0 +1,55 @@
+use crate::states::{DerivedAccountIdentifier, GlobalConfig};
+use anchor_lang::prelude::*;
+use fehler::throws;
+use solana_program::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct InitGlobalConfig<'info> {
+    #[account(
+        init,
+        seeds = [GlobalConfig::IDENT],
+        bump = bump,
+        payer = owner,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub owner: Signer<'info>, // the upgrade authority of the program becomes the protocol owner
+
+    // the program data account of this program, which records its upgrade authority
+    #[account(owner = bpf_loader_upgradeable::id())]
+    pub program_data: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitGlobalConfig<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self, bump: u8) {
+        let InitGlobalConfig {
+            global_config,
+            owner,
+            program_data,
+            ..
+        } = self;
+
+        let (program_data_address, _) =
+            Pubkey::find_program_address(&[crate::ID.as_ref()], &bpf_loader_upgradeable::id());
+        require!(
+            program_data.key() == program_data_address,
+            ContractAddressNotCorrect
+        );
+        let upgrade_authority = match bincode::deserialize(&program_data.try_borrow_data()?) {
+            Ok(UpgradeableLoaderState::ProgramData {
+                upgrade_authority_address,
+                ..
+            }) => upgrade_authority_address,
+            _ => None,
+        };
+        require!(upgrade_authority == Some(owner.key()), NotUpgradeAuthority);
+
+        global_config.bump = bump;
+        global_config.owner = owner.key();
+    }
+}
@@ -0,0 +1,106 @@
+use crate::errors::ErrorCode::*;
+use crate::states::{DerivedAccountIdentifier, LPMint, LockedPosition, Pool};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+use fehler::throws;
+
+#[derive(Accounts)]
+#[instruction(id: u64, bump: u8)]
+pub struct LockLp<'info> {
+    #[account(seeds = [Pool::IDENT, &pool.seed], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init,
+        seeds = [LockedPosition::IDENT, pool.key().as_ref(), owner.key().as_ref(), &id.to_le_bytes()],
+        bump = bump,
+        payer = owner,
+    )]
+    pub position: Box<Account<'info, LockedPosition>>,
+
+    #[account(
+        seeds = [LPMint::IDENT, &pool.seed],
+        bump = pool.lp_bump,
+        constraint = pool.mint == lp_token_mint.key() @ WrongLPMint,
+    )]
+    pub lp_token_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = lp_token_mint,
+        associated_token::authority = position,
+    )]
+    pub escrow: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = owner_lp_ata.mint == lp_token_mint.key() @ WrongLPMint,
+        constraint = owner_lp_ata.owner == owner.key() @ WrongATAOwner,
+    )]
+    pub owner_lp_ata: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> LockLp<'info> {
+    #[throws(ProgramError)]
+    pub fn process(
+        &mut self,
+        id: u64,
+        bump: u8,
+        amount: u64,
+        cliff_duration: i64,
+        vesting_duration: i64,
+    ) {
+        let LockLp {
+            pool,
+            position,
+            lp_token_mint,
+            escrow,
+            owner_lp_ata,
+            owner,
+            token_program,
+            ..
+        } = self;
+
+        require!(
+            amount > 0 && 0 <= cliff_duration && cliff_duration <= vesting_duration,
+            InvalidLock
+        );
+
+        // the schedule starts now, so that it cannot be backdated
+        let now = Clock::get()?.unix_timestamp;
+        position.pool = pool.key();
+        position.owner = owner.key();
+        position.id = id;
+        position.bump = bump;
+        position.lp_mint = lp_token_mint.key();
+        position.total_amount = amount;
+        position.start_at = now;
+        position.cliff_at = now.checked_add(cliff_duration).ok_or(CalculationFailure)?;
+        position.end_at = now
+            .checked_add(vesting_duration)
+            .ok_or(CalculationFailure)?;
+
+        token::transfer(
+            CpiContext::new(
+                token_program.to_account_info(),
+                token::Transfer {
+                    from: owner_lp_ata.to_account_info(),
+                    to: escrow.to_account_info(),
+                    authority: owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+    }
+}
@@ -0,0 +1,186 @@
+use crate::errors::ErrorCode::*;
+use crate::states::{DerivedAccountIdentifier, LegacyPool, Pool, PoolRegistryPage, RegistryEntry};
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+};
+use fehler::throws;
+use spl_token::instruction::AuthorityType;
+
+// A legacy pool is too small for the current layout and the runtime cannot grow it, so the
+// migration moves the pool into a new account: the vaults, the owner fees and the authority of the
+// LP mint go to the new pool and the legacy pool and its vaults are closed.
+#[derive(Accounts)]
+#[instruction(seed: [u8; 32], pool_bump: u8)]
+pub struct MigratePool<'info> {
+    // it does not deserialize as a `Pool`, see `process`
+    #[account(mut, owner = crate::ID)]
+    pub legacy_pool: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub legacy_token_vault_1: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub legacy_token_vault_2: Box<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    pub legacy_fee_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        seeds = [Pool::IDENT, &seed],
+        bump = pool_bump,
+        payer = payer,
+        space = Pool::LEN,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    // checked against the legacy pool in `process`, its mint authority moves to the new pool
+    #[account(mut)]
+    pub lp_token_mint: Box<Account<'info, Mint>>,
+    pub token_mint_1: Box<Account<'info, Mint>>,
+    pub token_mint_2: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = token_mint_1,
+        associated_token::authority = pool,
+    )]
+    pub token_vault_1: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = token_mint_2,
+        associated_token::authority = pool,
+    )]
+    pub token_vault_2: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = lp_token_mint,
+        associated_token::authority = pool,
+    )]
+    pub fee_vault: Box<Account<'info, TokenAccount>>,
+
+    // any page of the registry which still has room for the pool
+    #[account(mut)]
+    pub registry_page: Box<Account<'info, PoolRegistryPage>>,
+
+    // pays for the new accounts and gets the rent of the closed ones
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigratePool<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self, seed: [u8; 32], pool_bump: u8) {
+        let MigratePool {
+            legacy_pool,
+            legacy_token_vault_1,
+            legacy_token_vault_2,
+            legacy_fee_vault,
+            pool,
+            lp_token_mint,
+            token_mint_1,
+            token_mint_2,
+            token_vault_1,
+            token_vault_2,
+            fee_vault,
+            registry_page,
+            payer,
+            token_program,
+            ..
+        } = self;
+
+        let data = legacy_pool.try_borrow_data()?.to_vec();
+        require!(
+            data.len() > 8 && data[..8] == Pool::discriminator(),
+            NotALegacyPool
+        );
+        // a legacy pool has no flash loan lock to check, the first version had no flash loans
+        let mut legacy_data = &data[8..];
+        let legacy = LegacyPool::deserialize(&mut legacy_data).map_err(|_| NotALegacyPool)?;
+        require!(legacy_data.is_empty(), NotALegacyPool);
+        require!(legacy.seed == seed, NotALegacyPool);
+        LegacyPool::verify_address(&crate::ID, &seed, &legacy_pool.key())?;
+        require!(
+            legacy.token_vault_1 == legacy_token_vault_1.key()
+                && legacy.token_vault_2 == legacy_token_vault_2.key(),
+            IncorrectSwapAccount
+        );
+        require!(legacy.fee_vault == legacy_fee_vault.key(), WrongFeeVault);
+        require!(
+            legacy.token_mint_1 == token_mint_1.key() && legacy.token_mint_2 == token_mint_2.key(),
+            TokenNotSupportedByPool
+        );
+        require!(legacy.mint == lp_token_mint.key(), WrongLPMint);
+
+        let legacy_seeds: &[&[&[u8]]] = &[&[LegacyPool::IDENT, &seed, &[legacy.bump]]];
+        for (from, to) in [
+            (&legacy_token_vault_1, &token_vault_1),
+            (&legacy_token_vault_2, &token_vault_2),
+            (&legacy_fee_vault, &fee_vault),
+        ] {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    token::Transfer {
+                        from: from.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: legacy_pool.to_account_info(),
+                    },
+                    legacy_seeds,
+                ),
+                from.amount,
+            )?;
+            token::close_account(CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token::CloseAccount {
+                    account: from.to_account_info(),
+                    destination: payer.to_account_info(),
+                    authority: legacy_pool.to_account_info(),
+                },
+                legacy_seeds,
+            ))?;
+        }
+
+        token::set_authority(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token::SetAuthority {
+                    current_authority: legacy_pool.to_account_info(),
+                    account_or_mint: lp_token_mint.to_account_info(),
+                },
+                legacy_seeds,
+            ),
+            AuthorityType::MintTokens,
+            Some(pool.key()),
+        )?;
+
+        // the legacy pool is closed the way anchor closes an account
+        let lamports = legacy_pool.lamports();
+        **legacy_pool.try_borrow_mut_lamports()? = 0;
+        **payer.try_borrow_mut_lamports()? += lamports;
+        legacy_pool.try_borrow_mut_data()?.fill(0);
+
+        ***pool = Pool::from(legacy);
+        pool.bump = pool_bump;
+        pool.token_vault_1 = token_vault_1.key();
+        pool.token_vault_2 = token_vault_2.key();
+        pool.fee_vault = fee_vault.key();
+
+        registry_page.register(RegistryEntry {
+            pool: pool.key(),
+            token_mint_1: pool.token_mint_1,
+            token_mint_2: pool.token_mint_2,
+            curve_type: pool.curve.curve_type(),
+            trade_fee_numerator: pool.fees.trade_fee_numerator,
+            trade_fee_denominator: pool.fees.trade_fee_denominator,
+        })?;
+    }
+}
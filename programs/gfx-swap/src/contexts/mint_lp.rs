@@ -21,12 +21,15 @@ pub struct MintLPTo<'info> {
     #[account(
         init_if_needed,
         associated_token::mint = lp_token_mint,
-        associated_token::authority = admin,
-        payer = admin,
+        associated_token::authority = recipient,
+        payer = payer,
     )]
     pub recipient_ata: Account<'info, TokenAccount>,
 
-    pub admin: Signer<'info>,
+    pub recipient: UncheckedAccount<'info>,
+    pub admin: UncheckedAccount<'info>, // a signer or an AdminMultisig
+    #[account(mut)]
+    pub payer: Signer<'info>,
 
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -1,19 +1,89 @@
+mod accept_admin;
+mod add_registry_page;
+mod add_to_allowlist;
+mod cancel_admin_proposal;
+mod cancel_config;
+mod cancel_dca_order;
+mod cancel_order;
+mod claim_rewards;
 mod config_pool;
+mod create_admin_multisig;
+mod create_dca_order;
+mod create_farm;
+mod create_farm_stake;
+mod create_legacy_pool;
 mod create_pool;
+mod delist_pool;
 mod deposit1;
 mod deposit2;
+mod execute_config;
+mod execute_dca_slice;
+mod fill_orders;
+mod flash_loan;
+mod fund_farm;
+mod init_global_config;
+mod lock_lp;
+mod migrate_pool;
 mod mint_lp;
+mod place_order;
+mod propose_admin;
+mod queue_config;
+mod remove_from_allowlist;
+mod route_swap;
+mod set_emission_rate;
+mod set_global_config;
+mod set_roles;
+mod stake_lp;
 mod swap;
+mod temp_wsol;
+mod transfer_global_owner;
+mod unlock_lp;
+mod update_admin_multisig;
 mod withdraw1;
 mod withdraw2;
 mod withdraw_fee;
 
+pub use accept_admin::*;
+pub use add_registry_page::*;
+pub use add_to_allowlist::*;
+pub use cancel_admin_proposal::*;
+pub use cancel_config::*;
+pub use cancel_dca_order::*;
+pub use cancel_order::*;
+pub use claim_rewards::*;
 pub use config_pool::*;
+pub use create_admin_multisig::*;
+pub use create_dca_order::*;
+pub use create_farm::*;
+pub use create_farm_stake::*;
+pub use create_legacy_pool::*;
 pub use create_pool::*;
+pub use delist_pool::*;
 pub use deposit1::*;
 pub use deposit2::*;
+pub use execute_config::*;
+pub use execute_dca_slice::*;
+pub use fill_orders::*;
+pub use flash_loan::*;
+pub use fund_farm::*;
+pub use init_global_config::*;
+pub use lock_lp::*;
+pub use migrate_pool::*;
 pub use mint_lp::*;
+pub use place_order::*;
+pub use propose_admin::*;
+pub use queue_config::*;
+pub use remove_from_allowlist::*;
+pub use route_swap::*;
+pub use set_emission_rate::*;
+pub use set_global_config::*;
+pub use set_roles::*;
+pub use stake_lp::*;
 pub use swap::*;
+pub use temp_wsol::*;
+pub use transfer_global_owner::*;
+pub use unlock_lp::*;
+pub use update_admin_multisig::*;
 pub use withdraw1::*;
 pub use withdraw2::*;
 pub use withdraw_fee::*;
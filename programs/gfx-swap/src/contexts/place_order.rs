@@ -0,0 +1,114 @@
+use crate::errors::ErrorCode::*;
+use crate::states::{AllowlistEntry, DerivedAccountIdentifier, GlobalConfig, LimitOrder, Pool};
+use crate::token_interface::{self, CpiAccount, InterfaceMint, InterfaceTokenAccount};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use fehler::throws;
+
+#[derive(Accounts)]
+#[instruction(id: u64, bump: u8)]
+pub struct PlaceOrder<'info> {
+    #[account(seeds = [Pool::IDENT, &pool.seed], bump = pool.bump)]
+    pub pool: Account<'info, Pool>,
+
+    #[account(seeds = [GlobalConfig::IDENT], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        init,
+        seeds = [LimitOrder::IDENT, pool.key().as_ref(), maker.key().as_ref(), &id.to_le_bytes()],
+        bump = bump,
+        payer = maker,
+    )]
+    pub order: Box<Account<'info, LimitOrder>>,
+
+    #[account(constraint = pool.swaps(&in_mint) @ TokenNotSupportedByPool)]
+    pub in_mint: CpiAccount<'info, InterfaceMint>,
+    // the associated token account of the order, created in `process` with the program of the mint
+    #[account(mut)]
+    pub escrow: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = maker_in_token_ata.mint == in_mint.key() @ TokenNotSupportedByPool,
+        constraint = maker_in_token_ata.owner == maker.key() @ WrongATAOwner,
+    )]
+    pub maker_in_token_ata: CpiAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    // only read when the pool is permissioned, see `allowlisted`
+    #[account(seeds = [AllowlistEntry::IDENT, pool.key().as_ref(), maker.key().as_ref()], bump)]
+    pub allowlist_entry: UncheckedAccount<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: UncheckedAccount<'info>, // of the input mint
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> PlaceOrder<'info> {
+    #[throws(ProgramError)]
+    pub fn process(
+        &mut self,
+        id: u64,
+        bump: u8,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        bounty_bps: u16,
+    ) {
+        let PlaceOrder {
+            pool,
+            order,
+            in_mint,
+            escrow,
+            maker_in_token_ata,
+            maker,
+            system_program,
+            token_program,
+            associated_token_program,
+            ..
+        } = self;
+
+        require!(
+            amount_in > 0 && minimum_amount_out > 0 && bounty_bps <= LimitOrder::MAX_BOUNTY_BPS,
+            InvalidOrder
+        );
+
+        order.pool = pool.key();
+        order.maker = maker.key();
+        order.id = id;
+        order.bump = bump;
+        order.in_mint = in_mint.key();
+        order.out_mint = if in_mint.key() == pool.token_mint_1 {
+            pool.token_mint_2
+        } else {
+            pool.token_mint_1
+        };
+        order.amount_in = amount_in;
+        order.minimum_amount_out = minimum_amount_out;
+        order.bounty_bps = bounty_bps;
+
+        token_interface::create_associated_token_account(
+            maker,
+            escrow,
+            &order.to_account_info(),
+            in_mint,
+            system_program,
+            token_program,
+            associated_token_program,
+        )?;
+
+        // the escrow receives the amount without the fee of a Token-2022 mint, the fill sells all of it
+        token_interface::transfer_checked(
+            maker_in_token_ata,
+            &CpiAccount::try_from(escrow)?,
+            in_mint,
+            maker,
+            token_program,
+            &[],
+            amount_in,
+        )?;
+    }
+}
@@ -0,0 +1,25 @@
+use crate::states::{DerivedAccountIdentifier, Pool};
+use crate::ErrorCode::*;
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [Pool::IDENT, &pool.seed],
+        bump = pool.bump,
+        has_one = admin @ WrongAdmin
+    )]
+    pub pool: Account<'info, Pool>,
+
+    pub admin: UncheckedAccount<'info>, // a signer or an AdminMultisig
+}
+
+impl<'info> ProposeAdmin<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self, new_admin: Pubkey) {
+        let ProposeAdmin { pool, .. } = self;
+        pool.propose_admin(new_admin);
+    }
+}
@@ -0,0 +1,65 @@
+use crate::states::{DerivedAccountIdentifier, GlobalConfig, Pool, PoolConfig, QueuedConfig};
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+#[derive(Accounts)]
+#[instruction(config: PoolConfig, bump: u8)]
+pub struct QueueConfig<'info> {
+    #[account(
+        seeds = [Pool::IDENT, &pool.seed],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init,
+        seeds = [QueuedConfig::IDENT, pool.key().as_ref()],
+        bump = bump,
+        payer = payer,
+        space = QueuedConfig::LEN,
+    )]
+    pub queued_config: Account<'info, QueuedConfig>,
+
+    #[account(seeds = [GlobalConfig::IDENT], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    pub authority: UncheckedAccount<'info>, // the admin or the role required by the config
+    pub payer: Signer<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> QueueConfig<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self, config: PoolConfig, bump: u8) {
+        let QueueConfig {
+            pool,
+            queued_config,
+            payer,
+            clock,
+            global_config,
+            ..
+        } = self;
+
+        require!(!config.is_empty(), EmptyConfig);
+
+        // reject an invalid config now rather than when it is executed
+        Pool::clone(pool).config(&config)?;
+        if let Some(fees) = &config.fees {
+            global_config.constraints.validate_fees(fees)?;
+        }
+        if let Some(config_delay) = config.config_delay {
+            global_config
+                .constraints
+                .validate_config_delay(config_delay)?;
+        }
+
+        queued_config.pool = pool.key();
+        queued_config.bump = bump;
+        queued_config.payer = payer.key();
+        queued_config.eta = clock.unix_timestamp + pool.config_delay;
+        queued_config.config = config;
+    }
+}
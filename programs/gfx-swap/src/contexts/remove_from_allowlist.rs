@@ -0,0 +1,35 @@
+use crate::states::{AllowlistEntry, DerivedAccountIdentifier, Pool};
+use crate::ErrorCode::*;
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+#[derive(Accounts)]
+pub struct RemoveFromAllowlist<'info> {
+    #[account(
+        seeds = [Pool::IDENT, &pool.seed],
+        bump = pool.bump,
+        has_one = admin @ WrongAdmin
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [AllowlistEntry::IDENT, pool.key().as_ref(), allowlist_entry.wallet.as_ref()],
+        bump = allowlist_entry.bump,
+        has_one = payer,
+        close = payer,
+    )]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    pub admin: UncheckedAccount<'info>, // a signer or an AdminMultisig
+}
+
+impl<'info> RemoveFromAllowlist<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self) {
+        // closing the entry is all we need
+    }
+}
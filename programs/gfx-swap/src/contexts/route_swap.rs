@@ -0,0 +1,221 @@
+use crate::constraints::{allowlisted, swaps_open, unlocked};
+use crate::curve::TradeDirection;
+use crate::errors::ErrorCode::*;
+use crate::states::{
+    AccessMode, AllowlistEntry, DerivedAccountIdentifier, GlobalConfig, Pool, PoolExt, SwapQuote,
+};
+use crate::token_interface::{CpiAccount, InterfaceMint, InterfaceTokenAccount};
+use crate::utils::TupleExt;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use fehler::{throw, throws};
+
+// Every hop passes its accounts in the remaining accounts, in this order:
+// pool (mut), in_token_vault (mut), out_token_vault (mut), lp_token_mint (mut), fee_vault (mut), allowlist_entry,
+// out_token_mint, out_token_program. The input mint of a hop is the output mint of the previous one.
+pub const ROUTE_HOP_ACCOUNTS: usize = 8;
+pub const MAX_ROUTE_HOPS: u8 = 4;
+
+#[derive(Accounts)]
+pub struct RouteSwap<'info> {
+    #[account(seeds = [GlobalConfig::IDENT], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
+    // owned by the user wallet or delegated to it, see `utils::check_user_authority`
+    #[account(mut)]
+    pub in_token_ata_user: CpiAccount<'info, InterfaceTokenAccount>,
+    #[account(constraint = in_token_mint.key() == in_token_ata_user.mint @ TokenNotSupportedByPool)]
+    pub in_token_mint: CpiAccount<'info, InterfaceMint>,
+    #[account(
+        mut,
+        constraint = out_token_ata_user.owner == user_wallet.key() @ WrongATAOwner,
+        constraint = in_token_ata_user.mint != out_token_ata_user.mint @ SameToken,
+    )]
+    pub out_token_ata_user: CpiAccount<'info, InterfaceTokenAccount>,
+
+    pub user_wallet: Signer<'info>,
+    pub token_program: Program<'info, Token>, // of the LP tokens
+    pub in_token_program: UncheckedAccount<'info>, // of the input mint
+}
+
+// The accounts of one hop, checked the same way as the accounts of `swap`
+struct Hop<'info> {
+    pool: Account<'info, Pool>,
+    in_token_vault: CpiAccount<'info, InterfaceTokenAccount>,
+    out_token_vault: CpiAccount<'info, InterfaceTokenAccount>,
+    lp_token_mint: Account<'info, Mint>,
+    fee_vault: Account<'info, TokenAccount>,
+    out_token_mint: CpiAccount<'info, InterfaceMint>,
+    out_token_program: AccountInfo<'info>,
+}
+
+impl<'info> Hop<'info> {
+    #[throws(ProgramError)]
+    fn load(accounts: &[AccountInfo<'info>], user_wallet: &Pubkey) -> Self {
+        let pool: Account<'info, Pool> = Account::try_from(&accounts[0])?;
+        Pool::verify_address_with_bump(&crate::ID, &pool.seed, pool.bump, &pool.key())?;
+        swaps_open(&pool)?;
+        unlocked(&pool)?;
+
+        let allowlist_entry = &accounts[5];
+        if pool.access_mode == AccessMode::Permissioned {
+            let expected = AllowlistEntry::address(&crate::ID, &pool.key(), user_wallet);
+            require!(allowlist_entry.key == &expected, NotAllowlisted);
+        }
+        allowlisted(&pool, allowlist_entry)?;
+
+        let in_token_vault: CpiAccount<'info, InterfaceTokenAccount> =
+            CpiAccount::try_from(&accounts[1])?;
+        let out_token_vault: CpiAccount<'info, InterfaceTokenAccount> =
+            CpiAccount::try_from(&accounts[2])?;
+        let vaults = (pool.token_vault_1, pool.token_vault_2);
+        require!(
+            vaults.contains(&in_token_vault.key()) && vaults.contains(&out_token_vault.key()),
+            TokenNotSupportedByPool
+        );
+        require!(in_token_vault.mint != out_token_vault.mint, SameToken);
+
+        let lp_token_mint: Account<'info, Mint> = Account::try_from(&accounts[3])?;
+        require!(lp_token_mint.key() == pool.mint, WrongLPMint);
+
+        let fee_vault: Account<'info, TokenAccount> = Account::try_from(&accounts[4])?;
+        require!(fee_vault.key() == pool.fee_vault, WrongFeeVault);
+
+        // the program is checked by `token_interface::transfer_checked`
+        let out_token_mint: CpiAccount<'info, InterfaceMint> = CpiAccount::try_from(&accounts[6])?;
+        require!(
+            out_token_mint.key() == out_token_vault.mint,
+            TokenNotSupportedByPool
+        );
+        let out_token_program = accounts[7].clone();
+
+        Hop {
+            pool,
+            in_token_vault,
+            out_token_vault,
+            lp_token_mint,
+            fee_vault,
+            out_token_mint,
+            out_token_program,
+        }
+    }
+
+    fn trade_direction(&self) -> TradeDirection {
+        if self.in_token_vault.key() == self.pool.token_vault_1 {
+            TradeDirection::AtoB
+        } else {
+            TradeDirection::BtoA
+        }
+    }
+}
+
+impl<'info> RouteSwap<'info> {
+    #[throws(ProgramError)]
+    pub fn process(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+        amount_in: u64,
+        minimum_final_out: u64,
+        hops: u8,
+    ) {
+        let RouteSwap {
+            in_token_ata_user,
+            in_token_mint,
+            out_token_ata_user,
+            user_wallet,
+            token_program,
+            in_token_program,
+            ..
+        } = self;
+
+        require!(
+            (1..=MAX_ROUTE_HOPS).contains(&hops)
+                && remaining_accounts.len() == hops as usize * ROUTE_HOP_ACCOUNTS,
+            InvalidRoute
+        );
+
+        let mut route = vec![];
+        for accounts in remaining_accounts.chunks(ROUTE_HOP_ACCOUNTS) {
+            let hop = Hop::load(accounts, &user_wallet.key())?;
+            // a pool appearing twice would be written back with stale data
+            require!(
+                route
+                    .iter()
+                    .all(|other: &Hop| other.pool.key() != hop.pool.key()),
+                InvalidRoute
+            );
+            route.push(hop);
+        }
+
+        // the output of every hop is the input of the next one
+        let mut mint = in_token_ata_user.mint;
+        for hop in &route {
+            require!(hop.in_token_vault.mint == mint, InvalidRoute);
+            mint = hop.out_token_vault.mint;
+        }
+        require!(mint == out_token_ata_user.mint, InvalidRoute);
+
+        // price the whole route before moving any token, every hop trades what its vault receives
+        // after the fee of a Token-2022 mint
+        let slot = Clock::get()?.slot;
+        let mut quotes: Vec<SwapQuote> = vec![];
+        let mut amount = in_token_mint.amount_received(amount_in)?;
+        for hop in &mut route {
+            let trade_direction = hop.trade_direction();
+            let quote = hop.pool.quote_swap(
+                trade_direction,
+                amount,
+                hop.in_token_vault.amount,
+                hop.out_token_vault.amount,
+                hop.lp_token_mint.supply,
+                slot,
+            )?;
+            amount = hop.out_token_mint.amount_received(quote.amount_out)?;
+            quotes.push(quote);
+        }
+        if amount < minimum_final_out {
+            throw!(ExceededSlippage);
+        }
+
+        for i in 0..route.len() {
+            let (head, tail) = route.split_at_mut(i + 1);
+            let hop = &mut head[i];
+            let quote = &quotes[i];
+            let pool = &mut hop.pool;
+
+            if i == 0 {
+                pool.transfer_to_pool(
+                    user_wallet,
+                    in_token_ata_user,
+                    &hop.in_token_vault,
+                    in_token_mint,
+                    in_token_program,
+                    in_token_mint.amount_to_send(quote.amount_in)?,
+                )?;
+            }
+
+            // the intermediate tokens go straight into the vault of the next pool
+            let destination = tail
+                .first()
+                .map_or(&*out_token_ata_user, |next| &next.in_token_vault);
+            pool.transfer_to_user(
+                &hop.out_token_vault,
+                destination,
+                &hop.out_token_mint,
+                &hop.out_token_program,
+                quote.amount_out,
+            )?;
+
+            if quote.owner_fee > 0 {
+                pool.mint_lp_to(
+                    &hop.lp_token_mint,
+                    &hop.fee_vault,
+                    token_program,
+                    quote.owner_fee,
+                )?;
+            }
+
+            pool.exit(&crate::ID)?;
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use crate::errors::ErrorCode::*;
+use crate::states::{DerivedAccountIdentifier, Farm, Pool};
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+#[derive(Accounts)]
+pub struct SetEmissionRate<'info> {
+    #[account(
+        seeds = [Pool::IDENT, &pool.seed],
+        bump = pool.bump,
+        has_one = admin @ WrongAdmin
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(mut, seeds = [Farm::IDENT, pool.key().as_ref()], bump = farm.bump)]
+    pub farm: Box<Account<'info, Farm>>,
+
+    pub admin: UncheckedAccount<'info>, // a signer or an AdminMultisig
+}
+
+impl<'info> SetEmissionRate<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self, emission_rate: u64) {
+        let SetEmissionRate { farm, .. } = self;
+
+        // the rewards until now are emitted at the old rate
+        farm.update(Clock::get()?.unix_timestamp)?;
+        farm.emission_rate = emission_rate;
+    }
+}
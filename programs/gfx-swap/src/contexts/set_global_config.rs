@@ -0,0 +1,23 @@
+use crate::states::{DerivedAccountIdentifier, GlobalConfig, GlobalConfigUpdate};
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+#[derive(Accounts)]
+pub struct SetGlobalConfig<'info> {
+    #[account(
+        mut,
+        seeds = [GlobalConfig::IDENT],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub owner: UncheckedAccount<'info>, // a signer or an AdminMultisig
+}
+
+impl<'info> SetGlobalConfig<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self, update: &GlobalConfigUpdate) {
+        let SetGlobalConfig { global_config, .. } = self;
+        global_config.update(update);
+    }
+}
@@ -0,0 +1,25 @@
+use crate::states::{DerivedAccountIdentifier, Pool, PoolRoles};
+use crate::ErrorCode::*;
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+#[derive(Accounts)]
+pub struct SetRoles<'info> {
+    #[account(
+        mut,
+        seeds = [Pool::IDENT, &pool.seed],
+        bump = pool.bump,
+        has_one = admin @ WrongAdmin
+    )]
+    pub pool: Account<'info, Pool>,
+
+    pub admin: UncheckedAccount<'info>, // only the admin can reassign the roles, a signer or an AdminMultisig
+}
+
+impl<'info> SetRoles<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self, roles: &PoolRoles) {
+        let SetRoles { pool, .. } = self;
+        pool.set_roles(roles);
+    }
+}
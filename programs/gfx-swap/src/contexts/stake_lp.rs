@@ -0,0 +1,81 @@
+use crate::errors::ErrorCode::*;
+use crate::states::{DerivedAccountIdentifier, Farm, FarmExt, FarmStake};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount};
+use fehler::throws;
+
+// Used by both `stake_lp` and `unstake_lp`
+#[derive(Accounts)]
+pub struct StakeLp<'info> {
+    #[account(mut, seeds = [Farm::IDENT, farm.pool.as_ref()], bump = farm.bump)]
+    pub farm: Box<Account<'info, Farm>>,
+
+    #[account(
+        mut,
+        seeds = [FarmStake::IDENT, farm.key().as_ref(), owner.key().as_ref()],
+        bump = stake.bump,
+        has_one = owner @ WrongATAOwner,
+    )]
+    pub stake: Box<Account<'info, FarmStake>>,
+
+    #[account(mut, constraint = farm.lp_vault == lp_vault.key() @ WrongLPMint)]
+    pub lp_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = owner_lp_ata.mint == lp_vault.mint @ WrongLPMint,
+        constraint = owner_lp_ata.owner == owner.key() @ WrongATAOwner,
+    )]
+    pub owner_lp_ata: Box<Account<'info, TokenAccount>>,
+
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> StakeLp<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self, amount: u64) {
+        let StakeLp {
+            farm,
+            stake,
+            lp_vault,
+            owner_lp_ata,
+            owner,
+            token_program,
+        } = self;
+
+        farm.update(Clock::get()?.unix_timestamp)?;
+        let staked = stake.amount.checked_add(amount).ok_or(CalculationFailure)?;
+        stake.restake(farm, staked)?;
+
+        token::transfer(
+            CpiContext::new(
+                token_program.to_account_info(),
+                token::Transfer {
+                    from: owner_lp_ata.to_account_info(),
+                    to: lp_vault.to_account_info(),
+                    authority: owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+    }
+
+    #[throws(ProgramError)]
+    pub fn process_unstake(&mut self, amount: u64) {
+        let StakeLp {
+            farm,
+            stake,
+            lp_vault,
+            owner_lp_ata,
+            token_program,
+            ..
+        } = self;
+
+        farm.update(Clock::get()?.unix_timestamp)?;
+        let staked = stake.amount.checked_sub(amount).ok_or(InsufficientStake)?;
+        stake.restake(farm, staked)?;
+
+        farm.transfer_from_vault(lp_vault, owner_lp_ata, token_program, amount)?;
+    }
+}
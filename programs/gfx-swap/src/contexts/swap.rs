@@ -1,29 +1,41 @@
+use super::{__client_accounts_temp_wsol, __cpi_client_accounts_temp_wsol, TempWsol};
 use crate::curve::TradeDirection;
 use crate::errors::ErrorCode::*;
-use crate::states::{DerivedAccountIdentifier, LPMint, Pool, PoolExt};
-use crate::utils::{self, TupleExt};
+use crate::states::{
+    AllowlistEntry, DerivedAccountIdentifier, GlobalConfig, LPMint, Pool, PoolExt,
+};
+use crate::token_interface::{CpiAccount, InterfaceMint, InterfaceTokenAccount};
+use crate::utils::TupleExt;
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
 use fehler::{throw, throws};
 
 #[derive(Accounts)]
 pub struct Swap<'info> {
-    #[account(seeds = [Pool::IDENT, &pool.seed], bump = pool.bump)]
+    #[account(mut, seeds = [Pool::IDENT, &pool.seed], bump = pool.bump)]
     pub pool: Account<'info, Pool>,
 
+    #[account(seeds = [GlobalConfig::IDENT], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
     #[account(
         mut,
         constraint = in_token_vault.owner == pool.key() @ WrongATAOwner,
         constraint = (pool.token_vault_1, pool.token_vault_2).contains(&in_token_vault.key()) @ TokenNotSupportedByPool,
     )]
-    pub in_token_vault: Box<Account<'info, TokenAccount>>,
+    pub in_token_vault: CpiAccount<'info, InterfaceTokenAccount>,
     #[account(
         mut,
         constraint = out_token_vault.owner == pool.key() @ WrongATAOwner,
         constraint = (pool.token_vault_1, pool.token_vault_2).contains(&out_token_vault.key()) @ TokenNotSupportedByPool,
         constraint = in_token_vault.mint != out_token_vault.mint @ SameToken,
     )]
-    pub out_token_vault: Box<Account<'info, TokenAccount>>,
+    pub out_token_vault: CpiAccount<'info, InterfaceTokenAccount>,
+
+    #[account(constraint = in_token_mint.key() == in_token_vault.mint @ TokenNotSupportedByPool)]
+    pub in_token_mint: CpiAccount<'info, InterfaceMint>,
+    #[account(constraint = out_token_mint.key() == out_token_vault.mint @ TokenNotSupportedByPool)]
+    pub out_token_mint: CpiAccount<'info, InterfaceMint>,
 
     #[account(
         mut,
@@ -40,22 +52,31 @@ pub struct Swap<'info> {
     )]
     pub fee_vault: Box<Account<'info, TokenAccount>>,
 
+    // owned by the user wallet or delegated to it, see `utils::check_user_authority`
     #[account(
         mut,
-        constraint = in_token_ata_user.owner == user_wallet.key() @ WrongATAOwner,
         constraint =  (pool.token_mint_1, pool.token_mint_2).contains(&in_token_ata_user.mint) @ TokenNotSupportedByPool,
     )]
-    pub in_token_ata_user: Box<Account<'info, TokenAccount>>,
+    pub in_token_ata_user: CpiAccount<'info, InterfaceTokenAccount>,
+    // owned by the user wallet or by the recipient of `swap_to`, see `receives`
     #[account(
         mut,
-        constraint = out_token_ata_user.owner == user_wallet.key() @ WrongATAOwner,
         constraint = (pool.token_mint_1, pool.token_mint_2).contains(&out_token_ata_user.mint) @ TokenNotSupportedByPool,
         constraint = in_token_ata_user.mint != out_token_ata_user.mint @ SameToken,
     )]
-    pub out_token_ata_user: Box<Account<'info, TokenAccount>>,
+    pub out_token_ata_user: CpiAccount<'info, InterfaceTokenAccount>,
 
+    #[account(mut)] // pays and receives the lamports of the native SOL variants
     pub user_wallet: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+
+    // only read when the pool is permissioned, see `allowlisted`
+    #[account(seeds = [AllowlistEntry::IDENT, pool.key().as_ref(), user_wallet.key().as_ref()], bump)]
+    pub allowlist_entry: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>, // of the LP token
+    // of the mints, checked by `token_interface::transfer_checked`
+    pub in_token_program: UncheckedAccount<'info>,
+    pub out_token_program: UncheckedAccount<'info>,
 }
 
 impl<'info> Swap<'info> {
@@ -70,7 +91,12 @@ impl<'info> Swap<'info> {
             user_wallet,
             in_token_ata_user,
             out_token_ata_user,
+            in_token_mint,
+            out_token_mint,
             token_program,
+            in_token_program,
+            out_token_program,
+            ..
         } = self;
 
         let trade_direction = if in_token_ata_user.mint == pool.token_mint_1 {
@@ -81,63 +107,42 @@ impl<'info> Swap<'info> {
             throw!(IncorrectSwapAccount);
         };
 
-        let result = pool
-            .curve
-            .swap(
-                utils::to_u128(amount_in)?,
-                utils::to_u128(in_token_ata_pool.amount)?,
-                utils::to_u128(out_token_ata_pool.amount)?,
-                trade_direction,
-                &pool.fees,
-            )
-            .ok_or(ZeroTradingTokens)?;
-        if result.destination_amount_swapped < utils::to_u128(minimum_amount_out)? {
+        // the curve trades what the pool receives and the user gets what arrives of the output,
+        // both differ from the amounts of the transfers by the fee of a Token-2022 mint
+        let quote = pool.quote_swap(
+            trade_direction,
+            in_token_mint.amount_received(amount_in)?,
+            in_token_ata_pool.amount,
+            out_token_ata_pool.amount,
+            lp_token_mint.supply,
+            Clock::get()?.slot,
+        )?;
+        if out_token_mint.amount_received(quote.amount_out)? < minimum_amount_out {
             throw!(ExceededSlippage);
         }
 
-        let (swap_token_a_amount, swap_token_b_amount) = match trade_direction {
-            TradeDirection::AtoB => (
-                result.new_swap_source_amount,
-                result.new_swap_destination_amount,
-            ),
-            TradeDirection::BtoA => (
-                result.new_swap_destination_amount,
-                result.new_swap_source_amount,
-            ),
-        };
-
         // transfer token_src to the pool
         pool.transfer_to_pool(
             user_wallet,
             in_token_ata_user,
             in_token_ata_pool,
-            token_program,
-            utils::to_u64(result.source_amount_swapped)?,
+            in_token_mint,
+            in_token_program,
+            in_token_mint.amount_to_send(quote.amount_in)?,
         )?;
 
         // transfer token_dst to the user
         pool.transfer_to_user(
             out_token_ata_pool,
             out_token_ata_user,
-            token_program,
-            utils::to_u64(result.destination_amount_swapped)?,
+            out_token_mint,
+            out_token_program,
+            quote.amount_out,
         )?;
 
         // trading fees
 
-        let lp_token_amount = pool
-            .curve
-            .withdraw_single_token_type_exact_out(
-                result.owner_fee,
-                swap_token_a_amount,
-                swap_token_b_amount,
-                utils::to_u128(lp_token_mint.supply)?,
-                trade_direction,
-                &pool.fees,
-            )
-            .ok_or(FeeCalculationFailure)?;
-
-        if lp_token_amount > 0 {
+        if quote.owner_fee > 0 {
             // Allow error to fall through
 
             // transfer some fee to the host
@@ -173,8 +178,33 @@ impl<'info> Swap<'info> {
                 lp_token_mint,
                 lp_token_ata_fee,
                 token_program,
-                utils::to_u64(lp_token_amount)?,
+                quote.owner_fee,
             )?;
         }
     }
 }
+
+// `swap_native` swaps native SOL in or out through a temporary WSOL account
+#[derive(Accounts)]
+pub struct SwapNative<'info> {
+    pub temp_wsol: TempWsol<'info>,
+    pub swap: Swap<'info>,
+}
+
+impl<'info> SwapNative<'info> {
+    // Wraps the lamports in if the input token is SOL
+    #[throws(ProgramError)]
+    pub fn wrap_sol(&self, amount_in: u64) {
+        let Swap {
+            in_token_ata_user,
+            out_token_ata_user,
+            ..
+        } = &self.swap;
+
+        if self.temp_wsol.is(in_token_ata_user) {
+            self.temp_wsol.wrap(amount_in)?;
+        } else {
+            require!(self.temp_wsol.is(out_token_ata_user), NoNativeToken);
+        }
+    }
+}
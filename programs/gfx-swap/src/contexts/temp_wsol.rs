@@ -0,0 +1,82 @@
+use crate::states::DerivedAccountIdentifier;
+use crate::token_interface::{CpiAccount, InterfaceTokenAccount};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use fehler::throws;
+use solana_program::{program::invoke, system_instruction};
+
+impl DerivedAccountIdentifier for TempWsol<'_> {
+    const IDENT: &'static [u8] = b"GFXTempWsol";
+}
+
+// The WSOL account of a native SOL instruction. It is created for the user wallet before the instruction
+// deserializes it as its SOL side token account, and closed back into the wallet at the end, so the other
+// WSOL accounts of the user are left alone. The `_native` contexts put it first for this reason.
+#[derive(Accounts)]
+pub struct TempWsol<'info> {
+    #[account(
+        init,
+        seeds = [TempWsol::IDENT, user_wallet.key().as_ref()],
+        bump,
+        payer = user_wallet,
+        token::mint = wsol_mint,
+        token::authority = user_wallet,
+    )]
+    pub wsol_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = spl_token::native_mint::ID)]
+    pub wsol_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub user_wallet: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> TempWsol<'info> {
+    pub fn is(&self, token_account: &CpiAccount<'info, InterfaceTokenAccount>) -> bool {
+        token_account.key() == self.wsol_account.key()
+    }
+
+    // Moves `amount` lamports of the wallet into the account.
+    #[throws(ProgramError)]
+    pub fn wrap(&self, amount: u64) {
+        invoke(
+            &system_instruction::transfer(
+                &self.user_wallet.key(),
+                &self.wsol_account.key(),
+                amount,
+            ),
+            &[
+                self.user_wallet.to_account_info(),
+                self.wsol_account.to_account_info(),
+                self.system_program.to_account_info(),
+            ],
+        )?;
+        invoke(
+            &spl_token::instruction::sync_native(
+                &self.token_program.key(),
+                &self.wsol_account.key(),
+            )?,
+            &[
+                self.wsol_account.to_account_info(),
+                self.token_program.to_account_info(),
+            ],
+        )?;
+    }
+
+    // Closes the account, which returns the rent, the unspent input and the output to the wallet.
+    #[throws(ProgramError)]
+    pub fn close(&self) {
+        token::close_account(CpiContext::new(
+            self.token_program.to_account_info(),
+            token::CloseAccount {
+                account: self.wsol_account.to_account_info(),
+                destination: self.user_wallet.to_account_info(),
+                authority: self.user_wallet.to_account_info(),
+            },
+        ))?;
+    }
+}
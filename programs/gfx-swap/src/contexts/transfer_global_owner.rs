@@ -0,0 +1,29 @@
+use crate::states::{DerivedAccountIdentifier, GlobalConfig};
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+#[derive(Accounts)]
+pub struct TransferGlobalOwner<'info> {
+    #[account(
+        mut,
+        seeds = [GlobalConfig::IDENT],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub owner: UncheckedAccount<'info>, // a signer or an AdminMultisig
+    pub new_owner: UncheckedAccount<'info>, // approves as well to prove the key is usable
+}
+
+impl<'info> TransferGlobalOwner<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self) {
+        let TransferGlobalOwner {
+            global_config,
+            new_owner,
+            ..
+        } = self;
+
+        global_config.owner = new_owner.key();
+    }
+}
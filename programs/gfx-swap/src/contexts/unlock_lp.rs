@@ -0,0 +1,80 @@
+use crate::errors::ErrorCode::*;
+use crate::states::{DerivedAccountIdentifier, EscrowExt, LockedPosition};
+use crate::token_interface::{CpiAccount, InterfaceMint, InterfaceTokenAccount};
+use anchor_lang::prelude::*;
+use anchor_lang::AccountsClose;
+use anchor_spl::token::Token;
+use fehler::throws;
+use spl_associated_token_account::get_associated_token_address;
+
+// Unlocking is always possible, the pool may be paused or even delisted
+#[derive(Accounts)]
+pub struct UnlockLp<'info> {
+    #[account(
+        mut,
+        seeds = [LockedPosition::IDENT, position.pool.as_ref(), owner.key().as_ref(), &position.id.to_le_bytes()],
+        bump = position.bump,
+        has_one = owner @ WrongATAOwner,
+    )]
+    pub position: Box<Account<'info, LockedPosition>>,
+
+    #[account(
+        mut,
+        constraint = escrow.key() == get_associated_token_address(&position.key(), &position.lp_mint) @ WrongATAOwner,
+    )]
+    pub escrow: CpiAccount<'info, InterfaceTokenAccount>,
+    #[account(constraint = lp_token_mint.key() == position.lp_mint @ WrongLPMint)]
+    pub lp_token_mint: CpiAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        constraint = owner_lp_ata.mint == position.lp_mint @ WrongLPMint,
+        constraint = owner_lp_ata.owner == owner.key() @ WrongATAOwner,
+    )]
+    pub owner_lp_ata: CpiAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> UnlockLp<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self) {
+        let UnlockLp {
+            position,
+            escrow,
+            lp_token_mint,
+            owner_lp_ata,
+            owner,
+            token_program,
+        } = self;
+
+        let amount = position.unlockable_amount(Clock::get()?.unix_timestamp)?;
+        require!(amount > 0, NothingToUnlock);
+
+        position.released_amount += amount; // at most `total_amount`
+        let fully_released = position.released_amount == position.total_amount;
+
+        // the last unlock also releases anything sent to the escrow besides the lock, so that it can be closed
+        let amount = if fully_released {
+            escrow.amount
+        } else {
+            amount
+        };
+        position.transfer_from_escrow(
+            escrow,
+            owner_lp_ata,
+            lp_token_mint,
+            token_program,
+            amount,
+        )?;
+
+        // the rent of both accounts goes back to the owner
+        if fully_released {
+            position.close_escrow(escrow, lp_token_mint, owner, token_program)?;
+            position.close(owner.to_account_info())?;
+        }
+    }
+}
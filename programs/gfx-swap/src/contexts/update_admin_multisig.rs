@@ -0,0 +1,21 @@
+use crate::states::{AdminMultisig, DerivedAccountIdentifier};
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+#[derive(Accounts)]
+pub struct UpdateAdminMultisig<'info> {
+    #[account(
+        mut,
+        seeds = [AdminMultisig::IDENT, &multisig.seed],
+        bump = multisig.bump,
+    )]
+    pub multisig: Account<'info, AdminMultisig>, // approved by its current signers
+}
+
+impl<'info> UpdateAdminMultisig<'info> {
+    #[throws(ProgramError)]
+    pub fn process(&mut self, signers: Vec<Pubkey>, threshold: u8) {
+        let UpdateAdminMultisig { multisig } = self;
+        multisig.set_signers(signers, threshold)?;
+    }
+}
@@ -1,6 +1,8 @@
+use super::{__client_accounts_temp_wsol, __cpi_client_accounts_temp_wsol, TempWsol};
 use crate::curve::TradeDirection;
 use crate::errors::ErrorCode::*;
-use crate::states::{DerivedAccountIdentifier, LPMint, Pool, PoolExt};
+use crate::states::{DerivedAccountIdentifier, GlobalConfig, LPMint, Pool, PoolExt};
+use crate::token_interface::{CpiAccount, InterfaceMint, InterfaceTokenAccount};
 use crate::utils::{self, PubkeyPairExt, TupleExt};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
@@ -8,22 +10,25 @@ use fehler::{throw, throws};
 
 #[derive(Accounts)]
 pub struct Withdraw1<'info> {
-    #[account(seeds = [Pool::IDENT, &pool.seed], bump = pool.bump)]
+    #[account(mut, seeds = [Pool::IDENT, &pool.seed], bump = pool.bump)]
     pub pool: Account<'info, Pool>,
 
+    #[account(seeds = [GlobalConfig::IDENT], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
     #[account(
         mut,
         constraint = token_a_vault.owner == pool.key() @ WrongATAOwner,
         constraint = (pool.token_vault_1, pool.token_vault_2).contains(&token_a_vault.key()) @ TokenNotSupportedByPool
     )]
-    pub token_a_vault: Account<'info, TokenAccount>,
+    pub token_a_vault: CpiAccount<'info, InterfaceTokenAccount>,
     #[account(
         mut,
         constraint = token_b_vault.owner == pool.key() @ WrongATAOwner,
         constraint = (pool.token_vault_1, pool.token_vault_2).contains(&token_b_vault.key()) @ TokenNotSupportedByPool,
         constraint = token_a_vault.mint != token_b_vault.mint @ SameToken,
     )]
-    pub token_b_vault: Account<'info, TokenAccount>,
+    pub token_b_vault: CpiAccount<'info, InterfaceTokenAccount>,
 
     #[account(
         mut,
@@ -39,21 +44,25 @@ pub struct Withdraw1<'info> {
     )]
     pub fee_vault: Account<'info, TokenAccount>,
 
+    // owned by the user wallet or by the recipient of `withdraw1_to`, see `receives`
     #[account(
         mut,
-        constraint = out_token_ata_user.owner == user_wallet.key() @ WrongATAOwner,
         constraint = (pool.token_mint_1, pool.token_mint_2).contains(&out_token_ata_user.mint) @ TokenNotSupportedByPool
     )]
-    pub out_token_ata_user: Box<Account<'info, TokenAccount>>, // let the spl program check the ownership
+    pub out_token_ata_user: CpiAccount<'info, InterfaceTokenAccount>, // let the spl program check the ownership
+    #[account(constraint = out_token_mint.key() == out_token_ata_user.mint @ TokenNotSupportedByPool)]
+    pub out_token_mint: CpiAccount<'info, InterfaceMint>,
+    // owned by the user wallet or delegated to it, see `utils::check_user_authority`
     #[account(
         mut,
-        constraint = user_lp_ata.owner == user_wallet.key() @ WrongATAOwner,
         constraint = pool.mint == user_lp_ata.mint  @ WrongLPMint
     )]
     pub user_lp_ata: Box<Account<'info, TokenAccount>>,
 
+    #[account(mut)] // pays and receives the lamports of the native SOL variants
     pub user_wallet: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Program<'info, Token>, // of the LP token
+    pub out_token_program: UncheckedAccount<'info>, // of the output mint
 }
 
 impl<'info> Withdraw1<'info> {
@@ -67,8 +76,11 @@ impl<'info> Withdraw1<'info> {
             fee_vault: lp_token_ata_fee,
             user_wallet,
             out_token_ata_user,
+            out_token_mint,
             user_lp_ata,
             token_program,
+            out_token_program,
+            ..
         } = self;
 
         let (token1_ata_pool, token2_ata_pool) =
@@ -82,6 +94,9 @@ impl<'info> Withdraw1<'info> {
             throw!(IncorrectSwapAccount);
         };
 
+        // the user receives exactly `out_token_amount`, the vault also pays the fee of a Token-2022 mint
+        let out_token_amount = out_token_mint.amount_to_send(out_token_amount)?;
+
         let lp_token_supply = utils::to_u128(lp_token_mint.supply)?;
         let pool_token1_amount = utils::to_u128(token1_ata_pool.amount)?;
         let pool_token2_amount = utils::to_u128(token2_ata_pool.amount)?;
@@ -113,6 +128,16 @@ impl<'info> Withdraw1<'info> {
         if lp_token_amount == 0 {
             throw!(ZeroTradingTokens);
         }
+        let reserves_before = (token1_ata_pool.amount, token2_ata_pool.amount);
+        let mut reserves_after = reserves_before;
+        let reserve = match trade_direction {
+            TradeDirection::AtoB => &mut reserves_after.0,
+            TradeDirection::BtoA => &mut reserves_after.1,
+        };
+        *reserve = reserve
+            .checked_sub(out_token_amount)
+            .ok_or(CalculationFailure)?;
+        pool.record_reserve_change(reserves_before, reserves_after, Clock::get()?.slot)?;
 
         if withdraw_fee > 0 {
             pool.transfer_lp_to_fee_vault(
@@ -137,7 +162,8 @@ impl<'info> Withdraw1<'info> {
                 pool.transfer_to_user(
                     token1_ata_pool,
                     out_token_ata_user,
-                    token_program,
+                    out_token_mint,
+                    out_token_program,
                     out_token_amount,
                 )?;
             }
@@ -145,10 +171,129 @@ impl<'info> Withdraw1<'info> {
                 pool.transfer_to_user(
                     token2_ata_pool,
                     out_token_ata_user,
-                    token_program,
+                    out_token_mint,
+                    out_token_program,
                     out_token_amount,
                 )?;
             }
         }
     }
+
+    // Burns exactly `lp_token_amount` for the token of `out_token_ata_user`, the exact in variant of `process`.
+    #[throws(ProgramError)]
+    pub fn process_exact_lp(&mut self, lp_token_amount: u64, minimum_out_token_amount: u64) {
+        let Withdraw1 {
+            pool,
+            token_a_vault,
+            token_b_vault,
+            lp_token_mint,
+            fee_vault: lp_token_ata_fee,
+            user_wallet,
+            out_token_ata_user,
+            out_token_mint,
+            user_lp_ata,
+            token_program,
+            out_token_program,
+            ..
+        } = self;
+
+        let (token1_ata_pool, token2_ata_pool) =
+            (token_a_vault.mint, token_b_vault.mint).sort(token_a_vault, token_b_vault)?;
+
+        let trade_direction = if out_token_ata_user.mint == pool.token_mint_1 {
+            TradeDirection::AtoB
+        } else if out_token_ata_user.mint == pool.token_mint_2 {
+            TradeDirection::BtoA
+        } else {
+            throw!(IncorrectSwapAccount);
+        };
+
+        let withdraw_fee: u128 = pool
+            .fees
+            .owner_withdraw_fee(utils::to_u128(lp_token_amount)?)
+            .ok_or(FeeCalculationFailure)?;
+
+        let burn_pool_token_amount = utils::to_u128(lp_token_amount)?
+            .checked_sub(withdraw_fee)
+            .ok_or(CalculationFailure)?;
+
+        let out_token_amount = pool
+            .curve
+            .withdraw_single_token_type_exact_in(
+                burn_pool_token_amount,
+                utils::to_u128(token1_ata_pool.amount)?,
+                utils::to_u128(token2_ata_pool.amount)?,
+                utils::to_u128(lp_token_mint.supply)?,
+                trade_direction,
+                &pool.fees,
+            )
+            .ok_or(ZeroTradingTokens)?;
+        let out_token_amount = utils::to_u64(out_token_amount)?;
+
+        // the minimum is on what arrives after the fee of a Token-2022 mint
+        if out_token_mint.amount_received(out_token_amount)? < minimum_out_token_amount {
+            throw!(ExceededSlippage);
+        }
+        if out_token_amount == 0 {
+            throw!(ZeroTradingTokens);
+        }
+        let reserves_before = (token1_ata_pool.amount, token2_ata_pool.amount);
+        let mut reserves_after = reserves_before;
+        let reserve = match trade_direction {
+            TradeDirection::AtoB => &mut reserves_after.0,
+            TradeDirection::BtoA => &mut reserves_after.1,
+        };
+        *reserve = reserve
+            .checked_sub(out_token_amount)
+            .ok_or(CalculationFailure)?;
+        pool.record_reserve_change(reserves_before, reserves_after, Clock::get()?.slot)?;
+
+        if withdraw_fee > 0 {
+            pool.transfer_lp_to_fee_vault(
+                user_wallet,
+                user_lp_ata,
+                lp_token_ata_fee,
+                token_program,
+                utils::to_u64(withdraw_fee)?,
+            )?;
+        }
+
+        pool.burn_lp(
+            lp_token_mint,
+            user_wallet,
+            user_lp_ata,
+            token_program,
+            utils::to_u64(burn_pool_token_amount)?,
+        )?;
+
+        let token_ata_pool = match trade_direction {
+            TradeDirection::AtoB => token1_ata_pool,
+            TradeDirection::BtoA => token2_ata_pool,
+        };
+        pool.transfer_to_user(
+            token_ata_pool,
+            out_token_ata_user,
+            out_token_mint,
+            out_token_program,
+            out_token_amount,
+        )?;
+    }
+}
+
+// `withdraw1_native` withdraws native SOL through a temporary WSOL account
+#[derive(Accounts)]
+pub struct Withdraw1Native<'info> {
+    pub temp_wsol: TempWsol<'info>,
+    pub withdraw: Withdraw1<'info>,
+}
+
+impl<'info> Withdraw1Native<'info> {
+    // The output has to be SOL
+    #[throws(ProgramError)]
+    pub fn verify_sol(&self) {
+        require!(
+            self.temp_wsol.is(&self.withdraw.out_token_ata_user),
+            NoNativeToken
+        );
+    }
 }
@@ -1,6 +1,8 @@
+use super::{__client_accounts_temp_wsol, __cpi_client_accounts_temp_wsol, TempWsol};
 use crate::curve::{CurveCalculator, RoundDirection};
 use crate::errors::ErrorCode::*;
-use crate::states::{DerivedAccountIdentifier, LPMint, Pool, PoolExt};
+use crate::states::{DerivedAccountIdentifier, GlobalConfig, LPMint, Pool, PoolExt};
+use crate::token_interface::{CpiAccount, InterfaceMint, InterfaceTokenAccount};
 use crate::utils::{self, PubkeyPairExt, TupleExt};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
@@ -8,22 +10,25 @@ use fehler::{throw, throws};
 
 #[derive(Accounts)]
 pub struct Withdraw2<'info> {
-    #[account(seeds = [Pool::IDENT, &pool.seed], bump = pool.bump)]
+    #[account(mut, seeds = [Pool::IDENT, &pool.seed], bump = pool.bump)]
     pub pool: Account<'info, Pool>,
 
+    #[account(seeds = [GlobalConfig::IDENT], bump = global_config.bump)]
+    pub global_config: Box<Account<'info, GlobalConfig>>,
+
     #[account(
         mut,
         constraint = token_a_vault.owner == pool.key() @ WrongATAOwner,
         constraint = (pool.token_vault_1, pool.token_vault_2).contains(&token_a_vault.key()) @ TokenNotSupportedByPool
     )]
-    pub token_a_vault: Box<Account<'info, TokenAccount>>,
+    pub token_a_vault: CpiAccount<'info, InterfaceTokenAccount>,
     #[account(
         mut,
         constraint = token_b_vault.owner == pool.key() @ WrongATAOwner,
         constraint = (pool.token_vault_1, pool.token_vault_2).contains(&token_b_vault.key()) @ TokenNotSupportedByPool,
         constraint = token_a_vault.mint != token_b_vault.mint @ SameToken,
     )]
-    pub token_b_vault: Box<Account<'info, TokenAccount>>,
+    pub token_b_vault: CpiAccount<'info, InterfaceTokenAccount>,
 
     #[account(
         mut,
@@ -40,29 +45,38 @@ pub struct Withdraw2<'info> {
     )]
     pub fee_vault: Box<Account<'info, TokenAccount>>,
 
+    // owned by the user wallet or by the recipient of `withdraw2_to`, see `receives`
     #[account(
         mut,
-        constraint = user_token_a_ata.owner == user_wallet.key() @ WrongATAOwner,
         constraint = (pool.token_mint_1, pool.token_mint_2).contains(&user_token_a_ata.mint) @ TokenNotSupportedByPool,
     )]
-    pub user_token_a_ata: Box<Account<'info, TokenAccount>>,
+    pub user_token_a_ata: CpiAccount<'info, InterfaceTokenAccount>,
     #[account(
         mut,
-        constraint = user_token_b_ata.owner == user_wallet.key() @ WrongATAOwner,
         constraint = (pool.token_mint_1, pool.token_mint_2).contains(&user_token_b_ata.mint) @ TokenNotSupportedByPool,
         constraint = user_token_a_ata.mint != user_token_b_ata.mint @ SameToken,
     )]
-    pub user_token_b_ata: Box<Account<'info, TokenAccount>>,
+    pub user_token_b_ata: CpiAccount<'info, InterfaceTokenAccount>,
+
+    #[account(constraint = token_a_mint.key() == user_token_a_ata.mint @ TokenNotSupportedByPool)]
+    pub token_a_mint: CpiAccount<'info, InterfaceMint>,
+    #[account(constraint = token_b_mint.key() == user_token_b_ata.mint @ TokenNotSupportedByPool)]
+    pub token_b_mint: CpiAccount<'info, InterfaceMint>,
+
+    // owned by the user wallet or delegated to it, see `utils::check_user_authority`
     #[account(
         mut,
-        constraint = user_lp_ata.owner == user_wallet.key() @ WrongATAOwner,
         constraint = pool.mint == user_lp_ata.mint @ WrongLPMint
     )]
     pub user_lp_ata: Box<Account<'info, TokenAccount>>,
 
+    #[account(mut)] // pays and receives the lamports of the native SOL variants
     pub user_wallet: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Program<'info, Token>, // of the LP token
+    // of the mints, checked by `token_interface::transfer_checked`
+    pub token_a_program: UncheckedAccount<'info>,
+    pub token_b_program: UncheckedAccount<'info>,
 }
 
 impl<'info> Withdraw2<'info> {
@@ -82,8 +96,13 @@ impl<'info> Withdraw2<'info> {
             user_wallet,
             user_token_a_ata,
             user_token_b_ata,
+            token_a_mint,
+            token_b_mint,
             user_lp_ata,
             token_program,
+            token_a_program,
+            token_b_program,
+            ..
         } = self;
 
         // sort the tokens into the increasing order based on address
@@ -94,6 +113,9 @@ impl<'info> Withdraw2<'info> {
                 .sort(minimum_token_a_amount, minimum_token_b_amount)?;
         let (user_token1_ata, user_token2_ata) = (user_token_a_ata.mint, user_token_b_ata.mint)
             .sort(user_token_a_ata, user_token_b_ata)?;
+        let mints = (token_a_mint.key(), token_b_mint.key());
+        let (token1_mint, token2_mint) = mints.sort(token_a_mint, token_b_mint)?;
+        let (token1_program, token2_program) = mints.sort(token_a_program, token_b_program)?;
 
         let calculator = &pool.curve;
 
@@ -118,7 +140,8 @@ impl<'info> Withdraw2<'info> {
 
         let token1_amount = utils::to_u64(results.token1_amount)?;
         let token1_amount = std::cmp::min(token1_ata_pool.amount, token1_amount);
-        if token1_amount < minimum_token1_amount {
+        // the minimums are on what arrives after the fee of a Token-2022 mint
+        if token1_mint.amount_received(token1_amount)? < minimum_token1_amount {
             throw!(ExceededSlippage);
         }
         if token1_amount == 0 && token1_ata_pool.amount != 0 {
@@ -126,7 +149,7 @@ impl<'info> Withdraw2<'info> {
         }
         let token2_amount = utils::to_u64(results.token2_amount)?;
         let token2_amount = std::cmp::min(token2_ata_pool.amount, token2_amount);
-        if token2_amount < minimum_token2_amount {
+        if token2_mint.amount_received(token2_amount)? < minimum_token2_amount {
             throw!(ExceededSlippage);
         }
         if token2_amount == 0 && token2_ata_pool.amount != 0 {
@@ -155,7 +178,8 @@ impl<'info> Withdraw2<'info> {
             pool.transfer_to_user(
                 token1_ata_pool,
                 user_token1_ata,
-                token_program,
+                token1_mint,
+                token1_program,
                 token1_amount,
             )?;
         }
@@ -163,9 +187,150 @@ impl<'info> Withdraw2<'info> {
             pool.transfer_to_user(
                 token2_ata_pool,
                 user_token2_ata,
-                token_program,
+                token2_mint,
+                token2_program,
                 token1_amount,
             )?;
         }
     }
+
+    // Withdraws exactly `amount_a` and `amount_b` in any ratio, see `SwapCurve::withdraw_imbalanced`.
+    // The burnt LP tokens pay the imbalance fee and, like `withdraw1`, the owner withdraw fee on top.
+    #[throws(ProgramError)]
+    pub fn process_imbalanced(
+        &mut self,
+        amount_a: u64,
+        amount_b: u64,
+        maximum_lp_token_amount: u64,
+    ) {
+        let Withdraw2 {
+            pool,
+            token_a_vault,
+            token_b_vault,
+            lp_token_mint,
+            fee_vault: lp_token_ata_fee,
+            user_wallet,
+            user_token_a_ata,
+            user_token_b_ata,
+            token_a_mint,
+            token_b_mint,
+            user_lp_ata,
+            token_program,
+            token_a_program,
+            token_b_program,
+            ..
+        } = self;
+
+        // sort the tokens into the increasing order based on address
+        let (token1_ata_pool, token2_ata_pool) =
+            (token_a_vault.mint, token_b_vault.mint).sort(token_a_vault, token_b_vault)?;
+        let (amount1, amount2) =
+            (user_token_a_ata.mint, user_token_b_ata.mint).sort(amount_a, amount_b)?;
+        let (user_token1_ata, user_token2_ata) = (user_token_a_ata.mint, user_token_b_ata.mint)
+            .sort(user_token_a_ata, user_token_b_ata)?;
+        let mints = (token_a_mint.key(), token_b_mint.key());
+        let (token1_mint, token2_mint) = mints.sort(token_a_mint, token_b_mint)?;
+        let (token1_program, token2_program) = mints.sort(token_a_program, token_b_program)?;
+
+        // the user receives exactly the amounts, the vaults also pay the fee of a Token-2022 mint
+        let (amount1, amount2) = (
+            token1_mint.amount_to_send(amount1)?,
+            token2_mint.amount_to_send(amount2)?,
+        );
+
+        let burn_pool_token_amount = pool
+            .curve
+            .withdraw_imbalanced(
+                utils::to_u128(amount1)?,
+                utils::to_u128(amount2)?,
+                utils::to_u128(token1_ata_pool.amount)?,
+                utils::to_u128(token2_ata_pool.amount)?,
+                utils::to_u128(lp_token_mint.supply)?,
+                &pool.fees,
+            )
+            .ok_or(ZeroTradingTokens)?;
+
+        let withdraw_fee: u128 = pool
+            .fees
+            .owner_withdraw_fee(burn_pool_token_amount)
+            .ok_or(FeeCalculationFailure)?;
+
+        let lp_token_amount = burn_pool_token_amount
+            .checked_add(withdraw_fee)
+            .ok_or(CalculationFailure)?;
+
+        if utils::to_u64(lp_token_amount)? > maximum_lp_token_amount {
+            throw!(ExceededSlippage);
+        }
+        if burn_pool_token_amount == 0 {
+            throw!(ZeroTradingTokens);
+        }
+        let reserves_before = (token1_ata_pool.amount, token2_ata_pool.amount);
+        let reserves_after = (
+            reserves_before
+                .0
+                .checked_sub(amount1)
+                .ok_or(CalculationFailure)?,
+            reserves_before
+                .1
+                .checked_sub(amount2)
+                .ok_or(CalculationFailure)?,
+        );
+        pool.record_reserve_change(reserves_before, reserves_after, Clock::get()?.slot)?;
+
+        if withdraw_fee > 0 {
+            pool.transfer_lp_to_fee_vault(
+                user_wallet,
+                user_lp_ata,
+                lp_token_ata_fee,
+                token_program,
+                utils::to_u64(withdraw_fee)?,
+            )?;
+        }
+
+        pool.burn_lp(
+            lp_token_mint,
+            user_wallet,
+            user_lp_ata,
+            token_program,
+            utils::to_u64(burn_pool_token_amount)?,
+        )?;
+
+        if amount1 > 0 {
+            pool.transfer_to_user(
+                token1_ata_pool,
+                user_token1_ata,
+                token1_mint,
+                token1_program,
+                amount1,
+            )?;
+        }
+        if amount2 > 0 {
+            pool.transfer_to_user(
+                token2_ata_pool,
+                user_token2_ata,
+                token2_mint,
+                token2_program,
+                amount2,
+            )?;
+        }
+    }
+}
+
+// `withdraw2_native` withdraws the SOL side as native SOL through a temporary WSOL account
+#[derive(Accounts)]
+pub struct Withdraw2Native<'info> {
+    pub temp_wsol: TempWsol<'info>,
+    pub withdraw: Withdraw2<'info>,
+}
+
+impl<'info> Withdraw2Native<'info> {
+    #[throws(ProgramError)]
+    pub fn verify_sol(&self) {
+        require!(
+            self.temp_wsol.is(&self.withdraw.user_token_a_ata)
+                || self.temp_wsol.is(&self.withdraw.user_token_b_ata),
+            NoNativeToken
+        );
+    }
 }
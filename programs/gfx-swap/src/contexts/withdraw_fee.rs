@@ -9,7 +9,6 @@ pub struct WithdrawFee<'info> {
     #[account(
         seeds = [Pool::IDENT, &pool.seed],
         bump = pool.bump,
-        has_one = admin @ WrongAdmin
     )]
     pub pool: Account<'info, Pool>,
 
@@ -31,11 +30,12 @@ pub struct WithdrawFee<'info> {
     #[account(
         mut,
         associated_token::mint = lp_token_mint,
-        associated_token::authority = admin,
+        associated_token::authority = recipient,
     )]
-    pub admin_ata: Box<Account<'info, TokenAccount>>,
+    pub recipient_ata: Box<Account<'info, TokenAccount>>,
 
-    pub admin: Signer<'info>,
+    pub recipient: UncheckedAccount<'info>,
+    pub authority: UncheckedAccount<'info>, // the admin or the fee manager
     pub token_program: Program<'info, Token>,
 }
 
@@ -44,12 +44,12 @@ impl<'info> WithdrawFee<'info> {
     pub fn process(&mut self) {
         let WithdrawFee {
             pool,
-            admin_ata,
+            recipient_ata,
             fee_vault,
             token_program,
             ..
         } = self;
 
-        pool.transfer_lp_from_fee_vault(admin_ata, fee_vault, token_program, fee_vault.amount)?;
+        pool.transfer_lp_from_fee_vault(recipient_ata, fee_vault, token_program, fee_vault.amount)?;
     }
 }
@@ -157,6 +157,36 @@ pub fn withdraw_single_token_type_exact_out(
     }
 }
 
+/// Get the amount of token A or B for the burnt amount of pool tokens.
+///
+/// The inverse of `withdraw_single_token_type_exact_out`, solved for the
+/// withdrawn amount: `source_amount = swap_source_amount * (1 - (1 - pool_tokens / pool_supply) ^ 2)`
+pub fn withdraw_single_token_type_exact_in(
+    pool_tokens: u128,
+    swap_token_a_amount: u128,
+    swap_token_b_amount: u128,
+    pool_supply: u128,
+    trade_direction: TradeDirection,
+    round_direction: RoundDirection,
+) -> Option<u128> {
+    let swap_source_amount = match trade_direction {
+        TradeDirection::AtoB => swap_token_a_amount,
+        TradeDirection::BtoA => swap_token_b_amount,
+    };
+    let swap_source_amount = PreciseNumber::new(swap_source_amount)?;
+    let pool_tokens = PreciseNumber::new(pool_tokens)?;
+    let pool_supply = PreciseNumber::new(pool_supply)?;
+    let ratio = pool_tokens.checked_div(&pool_supply)?;
+    let one = PreciseNumber::new(1)?;
+    let base = one.checked_sub(&ratio)?;
+    let remaining = base.checked_mul(&base)?;
+    let source_amount = swap_source_amount.checked_mul(&one.checked_sub(&remaining)?)?;
+    match round_direction {
+        RoundDirection::Floor => source_amount.floor()?.to_imprecise(),
+        RoundDirection::Ceiling => source_amount.ceiling()?.to_imprecise(),
+    }
+}
+
 /// Calculates the total normalized value of the curve given the liquidity
 /// parameters.
 ///
@@ -241,6 +271,24 @@ impl CurveCalculator for ConstantProductCurve {
         )
     }
 
+    fn withdraw_single_token_type_exact_in(
+        &self,
+        pool_tokens: u128,
+        swap_token_a_amount: u128,
+        swap_token_b_amount: u128,
+        pool_supply: u128,
+        trade_direction: TradeDirection,
+    ) -> Option<u128> {
+        withdraw_single_token_type_exact_in(
+            pool_tokens,
+            swap_token_a_amount,
+            swap_token_b_amount,
+            pool_supply,
+            trade_direction,
+            RoundDirection::Floor,
+        )
+    }
+
     fn normalized_value(
         &self,
         swap_token_a_amount: u128,
@@ -249,6 +297,14 @@ impl CurveCalculator for ConstantProductCurve {
         normalized_value(swap_token_a_amount, swap_token_b_amount)
     }
 
+    fn invariant(
+        &self,
+        swap_token_a_amount: u128,
+        swap_token_b_amount: u128,
+    ) -> Option<PreciseNumber> {
+        normalized_value(swap_token_a_amount, swap_token_b_amount)
+    }
+
     fn validate(&self) -> Result<(), ErrorCode> {
         Ok(())
     }
@@ -443,6 +499,38 @@ mod tests {
         }
     }
 
+    proptest! {
+        #[test]
+        fn withdraw_single_token_type_exact_in_round_trip(
+            (pool_supply, pool_tokens) in total_and_intermediate(),
+            swap_token_a_amount in 1..u64::MAX,
+            swap_token_b_amount in 1..u64::MAX,
+        ) {
+            let curve = ConstantProductCurve::new();
+            let withdrawn = curve
+                .withdraw_single_token_type_exact_in(
+                    pool_tokens as u128,
+                    swap_token_a_amount as u128,
+                    swap_token_b_amount as u128,
+                    pool_supply as u128,
+                    TradeDirection::AtoB,
+                )
+                .unwrap();
+            prop_assume!(withdrawn > 0);
+            // withdrawing the same amount the other way never costs more pool tokens
+            let burnt = curve
+                .withdraw_single_token_type_exact_out(
+                    withdrawn,
+                    swap_token_a_amount as u128,
+                    swap_token_b_amount as u128,
+                    pool_supply as u128,
+                    TradeDirection::AtoB,
+                )
+                .unwrap();
+            prop_assert!(burnt <= pool_tokens as u128);
+        }
+    }
+
     proptest! {
         #[test]
         fn curve_value_does_not_decrease_from_swap(
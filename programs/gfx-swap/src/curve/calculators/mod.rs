@@ -80,6 +80,31 @@ pub trait CurveCalculator {
         trade_direction: TradeDirection,
     ) -> Option<u128>;
 
+    /// Get the amount of token A or B for the burnt amount of pool tokens.
+    ///
+    /// This is the inverse of `withdraw_single_token_type_exact_out`, used
+    /// for single-sided withdrawals of a given amount of pool tokens. It
+    /// rounds the withdrawn amount down.
+    fn withdraw_single_token_type_exact_in(
+        &self,
+        pool_tokens: u128,
+        swap_token1_amount: u128,
+        swap_token2_amount: u128,
+        pool_supply: u128,
+        trade_direction: TradeDirection,
+    ) -> Option<u128>;
+
+    /// Calculates the invariant of the curve in the dimension of `tokens ^ 1`,
+    /// i.e. D of the stable curve and the square root of the Uniswap invariant.
+    ///
+    /// It grows proportionally with balanced deposits, so the growth of the
+    /// invariant prices the pool tokens of deposits in any ratio.
+    fn invariant(
+        &self,
+        swap_token1_amount: u128,
+        swap_token2_amount: u128,
+    ) -> Option<PreciseNumber>;
+
     /// Validate that the given curve has no invalid parameters
     fn validate(&self) -> Result<(), ErrorCode>;
 
@@ -262,6 +262,41 @@ impl CurveCalculator for StableCurve {
         final_amount.ceiling()?.to_imprecise()
     }
 
+    /// Re-implementation of `calc_withdraw_one_coin` without the fees:
+    ///
+    /// <https://github.com/curvefi/curve-contract/blob/80bbe179083c9a7062e4c482b0be3bfb7501f2bd/contracts/pool-templates/base/SwapTemplateBase.vy>
+    fn withdraw_single_token_type_exact_in(
+        &self,
+        pool_tokens: u128,
+        swap_token_a_amount: u128,
+        swap_token_b_amount: u128,
+        pool_supply: u128,
+        trade_direction: TradeDirection,
+    ) -> Option<u128> {
+        if pool_tokens == 0 {
+            return Some(0);
+        }
+        let leverage = self.amp.checked_mul(N_COINS as u64)?;
+        let d0 = compute_d(leverage, swap_token_a_amount, swap_token_b_amount)?;
+        let (withdraw_token_amount, other_token_amount) = match trade_direction {
+            TradeDirection::AtoB => (swap_token_a_amount, swap_token_b_amount),
+            TradeDirection::BtoA => (swap_token_b_amount, swap_token_a_amount),
+        };
+        // the invariant drops by the share of the pool tokens, rounded up
+        let d_diff = PreciseNumber::new(d0)?
+            .checked_mul(&PreciseNumber::new(pool_tokens)?)?
+            .checked_div(&PreciseNumber::new(pool_supply)?)?
+            .ceiling()?
+            .to_imprecise()?;
+        let d1 = d0.checked_sub(d_diff)?;
+        let new_withdraw_token_amount =
+            compute_new_destination_amount(leverage, other_token_amount, d1)?;
+        // withdraw one less to account for the rounding of `y`, like Curve
+        withdraw_token_amount
+            .checked_sub(new_withdraw_token_amount)?
+            .checked_sub(1)
+    }
+
     fn normalized_value(
         &self,
         swap_token_a_amount: u128,
@@ -301,6 +336,19 @@ impl CurveCalculator for StableCurve {
         }
     }
 
+    fn invariant(
+        &self,
+        swap_token_a_amount: u128,
+        swap_token_b_amount: u128,
+    ) -> Option<PreciseNumber> {
+        let leverage = self.amp.checked_mul(N_COINS as u64)?;
+        PreciseNumber::new(compute_d(
+            leverage,
+            swap_token_a_amount,
+            swap_token_b_amount,
+        )?)
+    }
+
     fn validate(&self) -> Result<(), ErrorCode> {
         // TODO are all amps valid?
         Ok(())
@@ -406,6 +454,73 @@ mod tests {
         }
     }
 
+    proptest! {
+        #[test]
+        fn withdraw_single_token_type_exact_in_no_fee(
+            swap_token_a_amount in 100..1_000_000_000_000_000_000u128,
+            swap_token_b_amount in 100..1_000_000_000_000_000_000u128,
+            pool_supply in 100..1_000_000_000_000_000_000u128,
+            pool_token_basis_points in 1..9_000u128,
+            amp in 1..150u64
+        ) {
+            let pool_tokens = pool_supply * pool_token_basis_points / 10_000;
+            prop_assume!(pool_tokens > 0);
+
+            let curve = StableCurve { amp };
+
+            let model: StableSwapModel = StableSwapModel::new_with_pool_tokens(
+                curve.amp.into(),
+                vec![swap_token_a_amount, swap_token_b_amount],
+                N_COINS,
+                pool_supply,
+            );
+
+            let result = curve.withdraw_single_token_type_exact_in(
+                pool_tokens,
+                swap_token_a_amount,
+                swap_token_b_amount,
+                pool_supply,
+                TradeDirection::AtoB,
+            );
+            let sim_result = model.sim_calc_withdraw_one_coin(pool_tokens, 0);
+            let result = match result {
+                Some(result) => result,
+                // only a withdrawal rounding down to nothing is refused
+                None => {
+                    assert!(
+                        sim_result <= 1,
+                        "sim_result={}, amp={}, pool_tokens={}, pool_supply={}, swap_token_a_amount={}, swap_token_b_amount={}",
+                        sim_result,
+                        amp,
+                        pool_tokens,
+                        pool_supply,
+                        swap_token_a_amount,
+                        swap_token_b_amount,
+                    );
+                    return Ok(());
+                }
+            };
+
+            let diff = (sim_result as i128 - result as i128).abs();
+
+            // the invariant drop is rounded up and one less is withdrawn
+            let tolerance = std::cmp::max(2, sim_result as i128 / 1_000_000_000);
+
+            assert!(
+                diff <= tolerance,
+                "result={}, sim_result={}, amp={}, pool_tokens={}, pool_supply={}, swap_token_a_amount={}, swap_token_b_amount={}, diff={}",
+                result,
+                sim_result,
+                amp,
+                pool_tokens,
+                pool_supply,
+                swap_token_a_amount,
+                swap_token_b_amount,
+                diff
+            );
+        }
+    }
+
     proptest! {
         #[test]
         fn curve_value_does_not_decrease_from_deposit(
@@ -37,6 +37,26 @@ pub struct Fees {
     pub host_fee_denominator: u64,
 }
 
+/// Encapsulates the fees of flash loans. They are kept out of `Fees`, whose
+/// layout is part of the existing pool accounts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, AnchorDeserialize, AnchorSerialize)]
+pub struct FlashFees {
+    /// Flash loan fees are extra token amounts that the borrower returns on
+    /// top of a flash loan, held inside the token accounts like trade fees.
+    /// Flash loan fee numerator
+    pub flash_fee_numerator: u64,
+    /// Flash loan fee denominator
+    pub flash_fee_denominator: u64,
+
+    /// Owner flash loan fees are extra token amounts that the borrower returns
+    /// on top of a flash loan, with the equivalent in pool tokens minted to
+    /// the owner of the program.
+    /// Owner flash loan fee numerator
+    pub owner_flash_fee_numerator: u64,
+    /// Owner flash loan fee denominator
+    pub owner_flash_fee_denominator: u64,
+}
+
 /// Helper function for calculating swap fee
 pub fn calculate_fee(
     token_amount: u128,
@@ -68,6 +88,9 @@ fn validate_fraction(numerator: u64, denominator: u64) -> Result<(), ErrorCode>
 }
 
 impl Fees {
+    /// Serialized size of the fees
+    pub const LEN: usize = 8 * 8;
+
     /// Calculate the withdraw fee in pool tokens
     pub fn owner_withdraw_fee(&self, pool_tokens: u128) -> Option<u128> {
         calculate_fee(
@@ -86,6 +109,19 @@ impl Fees {
         )
     }
 
+    /// Calculate the fee on the deviation of an imbalanced deposit from the
+    /// balanced one, in trading tokens. It is half the trading fee, like the
+    /// `fee * n / (4 * (n - 1))` of Curve for two tokens.
+    pub fn imbalance_fee(&self, deviation: u128) -> Option<u128> {
+        calculate_fee(
+            deviation,
+            u128::try_from(self.trade_fee_numerator).ok()?,
+            u128::try_from(self.trade_fee_denominator)
+                .ok()?
+                .checked_mul(2)?,
+        )
+    }
+
     /// Calculate the owner trading fee in trading tokens
     pub fn owner_trading_fee(&self, trading_tokens: u128) -> Option<u128> {
         calculate_fee(
@@ -120,3 +156,36 @@ impl Fees {
         Ok(())
     }
 }
+
+impl FlashFees {
+    /// Serialized size of the flash loan fees
+    pub const LEN: usize = 4 * 8;
+
+    /// Calculate the flash loan fee in borrowed tokens
+    pub fn flash_fee(&self, borrowed_tokens: u128) -> Option<u128> {
+        calculate_fee(
+            borrowed_tokens,
+            u128::try_from(self.flash_fee_numerator).ok()?,
+            u128::try_from(self.flash_fee_denominator).ok()?,
+        )
+    }
+
+    /// Calculate the owner flash loan fee in borrowed tokens
+    pub fn owner_flash_fee(&self, borrowed_tokens: u128) -> Option<u128> {
+        calculate_fee(
+            borrowed_tokens,
+            u128::try_from(self.owner_flash_fee_numerator).ok()?,
+            u128::try_from(self.owner_flash_fee_denominator).ok()?,
+        )
+    }
+
+    /// Validate that the fees are reasonable
+    pub fn validate(&self) -> Result<(), ErrorCode> {
+        validate_fraction(self.flash_fee_numerator, self.flash_fee_denominator)?;
+        validate_fraction(
+            self.owner_flash_fee_numerator,
+            self.owner_flash_fee_denominator,
+        )?;
+        Ok(())
+    }
+}
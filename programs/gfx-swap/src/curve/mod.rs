@@ -8,5 +8,5 @@ pub use calculators::{
     ConstantProductCurve, CurveCalculator, RoundDirection, StableCurve, SwapWithoutFeesResult,
     TradeDirection,
 };
-pub use fees::Fees;
-pub use swap_curve::{SwapCurve, SwapResult};
+pub use fees::{Fees, FlashFees};
+pub use swap_curve::{CurveType, SwapCurve, SwapResult};
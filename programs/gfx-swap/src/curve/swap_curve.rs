@@ -42,6 +42,22 @@ pub enum SwapCurve {
     StableCurve,
 }
 
+/// Plain tag of the curve variants, without the curve parameters
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, AnchorDeserialize, AnchorSerialize)]
+pub enum CurveType {
+    /// Uniswap-style constant product curve
+    ConstantProduct,
+    /// Stable curve
+    Stable,
+}
+
+impl Default for CurveType {
+    fn default() -> Self {
+        CurveType::ConstantProduct
+    }
+}
+
 impl Default for SwapCurve {
     fn default() -> Self {
         Self::ConstantProductCurve(ConstantProductCurve::default())
@@ -49,6 +65,17 @@ impl Default for SwapCurve {
 }
 
 impl SwapCurve {
+    /// Serialized size of the largest curve variant, i.e. the stable curve
+    pub const MAX_LEN: usize = 1 + 8;
+
+    /// The type of the curve
+    pub fn curve_type(&self) -> CurveType {
+        match self {
+            SwapCurve::ConstantProductCurve(_) => CurveType::ConstantProduct,
+            SwapCurve::StableCurve(_) => CurveType::Stable,
+        }
+    }
+
     /// Subtract fees and calculate how much destination token will be provided
     /// given an amount of source token.
     pub fn swap(
@@ -118,6 +145,103 @@ impl SwapCurve {
         )
     }
 
+    /// Get the amount of pool tokens for depositing both tokens in any ratio.
+    /// Re-implementation of `add_liquidity`:
+    ///
+    /// <https://github.com/curvefi/curve-contract/blob/80bbe179083c9a7062e4c482b0be3bfb7501f2bd/contracts/pool-templates/base/SwapTemplateBase.vy>
+    ///
+    /// The pool tokens follow the growth of the invariant after the imbalance
+    /// fees, which stay in the pool.
+    pub fn deposit_imbalanced(
+        &self,
+        token1_amount: u128,
+        token2_amount: u128,
+        swap_token1_amount: u128,
+        swap_token2_amount: u128,
+        pool_supply: u128,
+        fees: &Fees,
+    ) -> Option<u128> {
+        let (d0, d2) = self.invariants_less_imbalance_fees(
+            swap_token1_amount,
+            swap_token2_amount,
+            swap_token1_amount.checked_add(token1_amount)?,
+            swap_token2_amount.checked_add(token2_amount)?,
+            fees,
+        )?;
+
+        if !d2.greater_than(&d0) {
+            return Some(0);
+        }
+        d2.checked_sub(&d0)?
+            .checked_mul(&PreciseNumber::new(pool_supply)?)?
+            .checked_div(&d0)?
+            .floor()?
+            .to_imprecise()
+    }
+
+    /// Get the amount of pool tokens to burn for withdrawing both tokens in
+    /// any ratio. Re-implementation of `remove_liquidity_imbalance`:
+    ///
+    /// <https://github.com/curvefi/curve-contract/blob/80bbe179083c9a7062e4c482b0be3bfb7501f2bd/contracts/pool-templates/base/SwapTemplateBase.vy>
+    ///
+    /// The pool tokens follow the drop of the invariant after the imbalance
+    /// fees and are rounded up.
+    pub fn withdraw_imbalanced(
+        &self,
+        token1_amount: u128,
+        token2_amount: u128,
+        swap_token1_amount: u128,
+        swap_token2_amount: u128,
+        pool_supply: u128,
+        fees: &Fees,
+    ) -> Option<u128> {
+        let (d0, d2) = self.invariants_less_imbalance_fees(
+            swap_token1_amount,
+            swap_token2_amount,
+            swap_token1_amount.checked_sub(token1_amount)?,
+            swap_token2_amount.checked_sub(token2_amount)?,
+            fees,
+        )?;
+
+        d0.checked_sub(&d2)?
+            .checked_mul(&PreciseNumber::new(pool_supply)?)?
+            .checked_div(&d0)?
+            .ceiling()?
+            .to_imprecise()
+    }
+
+    /// Get the invariant before and after moving the pool from the old to the
+    /// new amounts. The deviation of every token from the balanced move that
+    /// changes the invariant as much pays the imbalance fee, which is taken out
+    /// of the new amounts before the second invariant.
+    fn invariants_less_imbalance_fees(
+        &self,
+        swap_token1_amount: u128,
+        swap_token2_amount: u128,
+        new_swap_token1_amount: u128,
+        new_swap_token2_amount: u128,
+        fees: &Fees,
+    ) -> Option<(PreciseNumber, PreciseNumber)> {
+        let d0 = self.invariant(swap_token1_amount, swap_token2_amount)?;
+        let d1 = self.invariant(new_swap_token1_amount, new_swap_token2_amount)?;
+
+        let less_imbalance_fee = |old_amount: u128, new_amount: u128| -> Option<u128> {
+            let ideal_amount = d1
+                .checked_mul(&PreciseNumber::new(old_amount)?)?
+                .checked_div(&d0)?
+                .floor()?
+                .to_imprecise()?;
+            let deviation = ideal_amount.abs_diff(new_amount);
+            new_amount.checked_sub(fees.imbalance_fee(deviation)?)
+        };
+        let d2 = self.invariant(
+            less_imbalance_fee(swap_token1_amount, new_swap_token1_amount)?,
+            less_imbalance_fee(swap_token2_amount, new_swap_token2_amount)?,
+        )?;
+
+        Some((d0, d2))
+    }
+
     /// Get the amount of pool tokens for the withdrawn amount of token A or B
     pub fn withdraw_single_token_type_exact_out(
         &self,
@@ -147,11 +271,44 @@ impl SwapCurve {
             trade_direction,
         )
     }
+
+    /// Get the amount of token A or B for the burnt amount of pool tokens
+    pub fn withdraw_single_token_type_exact_in(
+        &self,
+        pool_tokens: u128,
+        swap_token_a_amount: u128,
+        swap_token_b_amount: u128,
+        pool_supply: u128,
+        trade_direction: TradeDirection,
+        fees: &Fees,
+    ) -> Option<u128> {
+        if pool_tokens == 0 {
+            return Some(0);
+        }
+        let source_amount = CurveCalculator::withdraw_single_token_type_exact_in(
+            self,
+            pool_tokens,
+            swap_token_a_amount,
+            swap_token_b_amount,
+            pool_supply,
+            trade_direction,
+        )?;
+        if source_amount == 0 {
+            return Some(0);
+        }
+        // Same as the exact out variant, the trading fee is incurred if *half*
+        // the withdrawn amount is swapped from the other side.
+        let half_source_amount = std::cmp::max(1, source_amount.checked_div(2)?);
+        let trade_fee = fees.trading_fee(half_source_amount)?;
+        source_amount.checked_sub(trade_fee)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+    use sim::{StableSwapModel, MODEL_FEE_DENOMINATOR, MODEL_FEE_NUMERATOR};
 
     #[test]
     fn constant_product_trade_fee() {
@@ -256,4 +413,269 @@ mod tests {
         assert_eq!(result.destination_amount_swapped, 4545);
         assert_eq!(result.new_swap_destination_amount, 45455);
     }
+
+    fn imbalance_fees() -> Fees {
+        Fees {
+            trade_fee_numerator: 1,
+            trade_fee_denominator: 100,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn constant_product_deposit_imbalanced() {
+        let swap_curve = SwapCurve::ConstantProductCurve(ConstantProductCurve::default());
+        let fees = imbalance_fees();
+
+        // a balanced deposit pays no imbalance fee
+        let pool_tokens = swap_curve
+            .deposit_imbalanced(100, 5_000, 1_000, 50_000, 1_000, &fees)
+            .unwrap();
+        assert!((99..=100).contains(&pool_tokens));
+
+        let with_fees = swap_curve
+            .deposit_imbalanced(100_000, 0, 1_000_000, 50_000_000, 1_000_000, &fees)
+            .unwrap();
+        let without_fees = swap_curve
+            .deposit_imbalanced(
+                100_000,
+                0,
+                1_000_000,
+                50_000_000,
+                1_000_000,
+                &Fees::default(),
+            )
+            .unwrap();
+        assert!(with_fees > 0);
+        assert!(with_fees < without_fees);
+        // the no-fee imbalanced deposit is priced by the invariant, like a swap of half of it
+        assert!(without_fees < 50_000);
+    }
+
+    #[test]
+    fn stable_deposit_imbalanced() {
+        let swap_curve = SwapCurve::StableCurve(StableCurve { amp: 100 });
+        let fees = imbalance_fees();
+
+        let pool_tokens = swap_curve
+            .deposit_imbalanced(1_000, 1_000, 100_000, 100_000, 200_000, &fees)
+            .unwrap();
+        assert!((1_999..=2_000).contains(&pool_tokens));
+
+        let with_fees = swap_curve
+            .deposit_imbalanced(2_000, 0, 100_000, 100_000, 200_000, &fees)
+            .unwrap();
+        let without_fees = swap_curve
+            .deposit_imbalanced(2_000, 0, 100_000, 100_000, 200_000, &Fees::default())
+            .unwrap();
+        assert!(with_fees < without_fees);
+        // close to the balanced deposit of the same value on a flat curve
+        assert!(with_fees > 1_980);
+    }
+
+    fn check_value_per_pool_token_from_deposit_imbalanced(
+        swap_curve: &SwapCurve,
+        token1_amount: u128,
+        token2_amount: u128,
+        swap_token1_amount: u128,
+        swap_token2_amount: u128,
+        pool_supply: u128,
+    ) {
+        let pool_tokens = swap_curve
+            .deposit_imbalanced(
+                token1_amount,
+                token2_amount,
+                swap_token1_amount,
+                swap_token2_amount,
+                pool_supply,
+                &imbalance_fees(),
+            )
+            .unwrap();
+        let d0 = swap_curve
+            .invariant(swap_token1_amount, swap_token2_amount)
+            .unwrap();
+        let d1 = swap_curve
+            .invariant(
+                swap_token1_amount + token1_amount,
+                swap_token2_amount + token2_amount,
+            )
+            .unwrap();
+        // d1 / (supply + minted) >= d0 / supply
+        let before = d0.checked_mul(&PreciseNumber::new(pool_supply + pool_tokens).unwrap());
+        let after = d1.checked_mul(&PreciseNumber::new(pool_supply).unwrap());
+        let (before, after) = (before.unwrap(), after.unwrap());
+        assert!(
+            after.greater_than_or_equal(&before)
+                || after.almost_eq(&before, PreciseNumber::new(pool_supply).unwrap().value)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn value_per_pool_token_does_not_decrease_from_deposit_imbalanced(
+            token1_amount in 0..u32::MAX,
+            token2_amount in 0..u32::MAX,
+            swap_token1_amount in 1_000..u32::MAX,
+            swap_token2_amount in 1_000..u32::MAX,
+            pool_supply in 1_000..u64::MAX,
+            amp in 1..10_000u64,
+        ) {
+            for swap_curve in [
+                SwapCurve::ConstantProductCurve(ConstantProductCurve::default()),
+                SwapCurve::StableCurve(StableCurve { amp }),
+            ] {
+                check_value_per_pool_token_from_deposit_imbalanced(
+                    &swap_curve,
+                    token1_amount as u128,
+                    token2_amount as u128,
+                    swap_token1_amount as u128,
+                    swap_token2_amount as u128,
+                    pool_supply as u128,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn constant_product_withdraw_imbalanced() {
+        let swap_curve = SwapCurve::ConstantProductCurve(ConstantProductCurve::default());
+        let fees = imbalance_fees();
+
+        // a balanced withdrawal pays no imbalance fee, only the rounding up
+        let pool_tokens = swap_curve
+            .withdraw_imbalanced(100, 5_000, 1_000, 50_000, 1_000, &fees)
+            .unwrap();
+        assert!((100..=101).contains(&pool_tokens));
+
+        let with_fees = swap_curve
+            .withdraw_imbalanced(100_000, 0, 1_000_000, 50_000_000, 1_000_000, &fees)
+            .unwrap();
+        let without_fees = swap_curve
+            .withdraw_imbalanced(
+                100_000,
+                0,
+                1_000_000,
+                50_000_000,
+                1_000_000,
+                &Fees::default(),
+            )
+            .unwrap();
+        assert!(with_fees > without_fees);
+        // more than the share of the balanced withdrawal of the same token
+        assert!(without_fees > 50_000);
+    }
+
+    proptest! {
+        #[test]
+        fn stable_deposit_imbalanced_matches_model(
+            swap_token1_amount in 1_000_000..1_000_000_000_000_000u128,
+            swap_token2_amount in 1_000_000..1_000_000_000_000_000u128,
+            token1_basis_points in 0..10_000u128,
+            token2_basis_points in 0..10_000u128,
+            amp in 1..150u64,
+        ) {
+            let token1_amount = swap_token1_amount * token1_basis_points / 10_000;
+            let token2_amount = swap_token2_amount * token2_basis_points / 10_000;
+            let pool_supply = swap_token1_amount + swap_token2_amount;
+
+            let swap_curve = SwapCurve::StableCurve(StableCurve { amp });
+            let fees = Fees {
+                trade_fee_numerator: MODEL_FEE_NUMERATOR as u64,
+                trade_fee_denominator: MODEL_FEE_DENOMINATOR as u64,
+                ..Default::default()
+            };
+            let result = swap_curve
+                .deposit_imbalanced(
+                    token1_amount,
+                    token2_amount,
+                    swap_token1_amount,
+                    swap_token2_amount,
+                    pool_supply,
+                    &fees,
+                )
+                .unwrap();
+
+            let mut model = StableSwapModel::new_with_pool_tokens(
+                amp.into(),
+                vec![swap_token1_amount, swap_token2_amount],
+                2,
+                pool_supply,
+            );
+            model.fee = MODEL_FEE_NUMERATOR * 10_000_000_000 / MODEL_FEE_DENOMINATOR;
+            let sim_result = model.sim_add_liquidity(vec![token1_amount, token2_amount]);
+
+            // the integer invariants of the model and the precise ones of the curve differ by a few units
+            let tolerance = std::cmp::max(4, sim_result / 1_000_000_000);
+            assert!(
+                result.abs_diff(sim_result) <= tolerance,
+                "result={}, sim_result={}, amp={}, token1_amount={}, token2_amount={}, swap_token1_amount={}, swap_token2_amount={}",
+                result,
+                sim_result,
+                amp,
+                token1_amount,
+                token2_amount,
+                swap_token1_amount,
+                swap_token2_amount,
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn stable_withdraw_imbalanced_matches_model(
+            swap_token1_amount in 1_000_000..1_000_000_000_000_000u128,
+            swap_token2_amount in 1_000_000..1_000_000_000_000_000u128,
+            token1_basis_points in 0..9_000u128,
+            token2_basis_points in 0..9_000u128,
+            amp in 1..150u64,
+        ) {
+            let token1_amount = swap_token1_amount * token1_basis_points / 10_000;
+            let token2_amount = swap_token2_amount * token2_basis_points / 10_000;
+            // keeps the pool tokens in the order of the invariant, so that the
+            // differences in rounding stay within a few pool tokens
+            let pool_supply = swap_token1_amount + swap_token2_amount;
+
+            let swap_curve = SwapCurve::StableCurve(StableCurve { amp });
+            let fees = Fees {
+                trade_fee_numerator: MODEL_FEE_NUMERATOR as u64,
+                trade_fee_denominator: MODEL_FEE_DENOMINATOR as u64,
+                ..Default::default()
+            };
+            let result = swap_curve
+                .withdraw_imbalanced(
+                    token1_amount,
+                    token2_amount,
+                    swap_token1_amount,
+                    swap_token2_amount,
+                    pool_supply,
+                    &fees,
+                )
+                .unwrap();
+
+            let mut model = StableSwapModel::new_with_pool_tokens(
+                amp.into(),
+                vec![swap_token1_amount, swap_token2_amount],
+                2,
+                pool_supply,
+            );
+            // the fee of the model is in 1e10, it charges `fee * n / (4 * (n - 1))` on the imbalance
+            model.fee = MODEL_FEE_NUMERATOR * 10_000_000_000 / MODEL_FEE_DENOMINATOR;
+            let sim_result = model.sim_remove_liquidity_imbalance(vec![token1_amount, token2_amount]);
+
+            // the model rounds down and takes no minimum fee, the curve rounds in favor of the pool.
+            // The integer invariants of the model can still land one pool token above the curve.
+            let tolerance = std::cmp::max(4, sim_result / 1_000_000_000);
+            assert!(
+                result + 1 >= sim_result && result <= sim_result + tolerance,
+                "result={}, sim_result={}, amp={}, token1_amount={}, token2_amount={}, swap_token1_amount={}, swap_token2_amount={}",
+                result,
+                sim_result,
+                amp,
+                token1_amount,
+                token2_amount,
+                swap_token1_amount,
+                swap_token2_amount,
+            );
+        }
+    }
 }
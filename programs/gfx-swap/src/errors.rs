@@ -62,18 +62,149 @@ pub enum ErrorCode {
     #[msg("[G018] Wrong admin")] //0x13D (318)
     WrongAdmin,
 
-    #[msg("[G019] Pool is suspended")] //0x13E (319)
-    Suspended,
+    #[msg("[G019] Swaps of the pool are suspended")] //0x13E (319)
+    SwapsSuspended,
 
     #[msg("[G020] Wrong fee vault")] //0x13F (320)
     WrongFeeVault,
+
+    #[msg("[G021] The registry page is full")] //0x140 (321)
+    RegistryPageFull,
+
+    #[msg("[G022] The pool is not in the registry page")] //0x141 (322)
+    PoolNotRegistered,
+
+    #[msg("[G023] Not the pending admin of the pool")] //0x142 (323)
+    WrongPendingAdmin,
+
+    #[msg("[G024] The pool has no pending admin")] //0x143 (324)
+    NoPendingAdmin,
+
+    #[msg("[G025] Invalid multisig signers or threshold")] //0x144 (325)
+    InvalidMultisig,
+
+    #[msg("[G026] Not enough multisig signers approved")] //0x145 (326)
+    NotEnoughApprovals,
+
+    #[msg("[G027] The authority neither signed nor is a multisig")] //0x146 (327)
+    AuthorityNotSigner,
+
+    #[msg("[G028] Only pausing and resuming are instant, queue the config instead")] //0x147 (328)
+    TimelockRequired,
+
+    #[msg("[G029] The queued config is not ready to be executed")] //0x148 (329)
+    ConfigNotReady,
+
+    #[msg("[G030] Invalid config delay")] //0x149 (330)
+    InvalidConfigDelay,
+
+    #[msg("[G031] Deposits of the pool are suspended")] //0x14A (331)
+    DepositsSuspended,
+
+    #[msg("[G032] Single sided deposits and withdrawals of the pool are suspended")] //0x14B (332)
+    SingleSidedSuspended,
+
+    #[msg("[G033] Withdrawals of the pool are suspended")] //0x14C (333)
+    WithdrawalsSuspended,
+
+    #[msg("[G034] All the pools are suspended")] //0x14D (334)
+    GloballySuspended,
+
+    #[msg("[G035] Withdrawals of all the pools are suspended")] //0x14E (335)
+    GlobalWithdrawalsSuspended,
+
+    #[msg("[G036] Wrong owner of the global config")] //0x14F (336)
+    WrongGlobalOwner,
+
+    #[msg("[G037] The pool violates the constraints of the global config")] //0x150 (337)
+    ConstraintViolated,
+
+    #[msg("[G038] The trade moves the price of the pool too much")] //0x151 (338)
+    PriceMoveTooLarge,
+
+    #[msg("[G039] The circuit breaker of the pool is tripped")] //0x152 (339)
+    CircuitBreakerTripped,
+
+    #[msg("[G040] Invalid circuit breaker parameters")] //0x153 (340)
+    InvalidCircuitBreaker,
+
+    #[msg("[G041] The trade exceeds the size limit of the pool")] //0x154 (341)
+    TradeTooLarge,
+
+    #[msg("[G042] The trade exceeds the volume limit of the pool in this slot")] //0x155 (342)
+    SlotVolumeExceeded,
+
+    #[msg("[G043] The deposit exceeds the LP supply cap of the pool")] //0x156 (343)
+    DepositCapExceeded,
+
+    #[msg("[G044] The deadline of the instruction has passed")] //0x157 (344)
+    Expired,
+
+    #[msg("[G045] The wallet is not on the allowlist of the pool")] //0x158 (345)
+    NotAllowlisted,
+
+    #[msg("[G046] The SOL side is not the temporary WSOL account")] //0x159 (346)
+    NoNativeToken,
+
+    #[msg("[G047] The hops of the route do not connect")] //0x15a (347)
+    InvalidRoute,
+
+    #[msg("[G048] The pool is locked by a flash loan")] //0x15b (348)
+    PoolLocked,
+
+    #[msg("[G049] The flash loan was not repaid with the fees")] //0x15c (349)
+    FlashLoanNotRepaid,
+
+    #[msg("[G050] The flash loan receiver is not a program")] //0x15d (350)
+    InvalidFlashLoanReceiver,
+
+    #[msg("[G051] The user is a delegate of the token account for less than the amount")]
+    //0x15e (351)
+    InsufficientDelegation,
+
+    #[msg("[G052] The order has no amount or a too large bounty")] //0x15f (352)
+    InvalidOrder,
+
+    #[msg("[G053] The pool does not pay the limit price of the order")] //0x160 (353)
+    OrderNotFillable,
+
+    #[msg("[G054] The interval since the last slice of the order has not elapsed")] //0x161 (354)
+    SliceNotDue,
+
+    #[msg("[G055] The stake is smaller than the amount to unstake")] //0x162 (355)
+    InsufficientStake,
+
+    #[msg("[G056] The reward vault or the reward mint is not the one of the farm")] //0x163 (356)
+    WrongRewardVault,
+
+    #[msg("[G057] The lock has no amount or its cliff is after its end")] //0x164 (357)
+    InvalidLock,
+
+    #[msg("[G058] No LP token of the position has vested since the last unlock")] //0x165 (358)
+    NothingToUnlock,
+
+    #[msg("[G059] The config does not change anything")] //0x166 (359)
+    EmptyConfig,
+
+    #[msg("[G060] Only the upgrade authority of the program can initialize it")] //0x167 (360)
+    NotUpgradeAuthority,
+
+    #[msg("[G061] The account is not a pool of the first version of the program")] //0x168 (361)
+    NotALegacyPool,
+
+    #[msg("[G062] The token program is not the one of the mint and its token accounts")]
+    //0x169 (362)
+    WrongTokenProgram,
+
+    #[msg("[G063] The mint has a Token-2022 extension the pool does not support")] //0x16A (363)
+    UnsupportedMintExtension,
 }
 
 impl TryInto<ErrorCode> for u32 {
     type Error = (); // Error if u32 is out of range
 
     fn try_into(self) -> std::result::Result<ErrorCode, ()> {
-        if (300..=320).contains(&self) {
+        if (300..=363).contains(&self) {
             Ok(unsafe { std::mem::transmute(self - 300) })
         } else {
             Err(())
@@ -20,19 +20,32 @@ mod contexts;
 mod curve;
 mod errors;
 mod states;
+mod token_interface;
 mod utils;
 
 pub use contexts::*;
 pub use curve::{
-    ConstantProductCurve, CurveCalculator, Fees, RoundDirection, StableCurve, SwapCurve,
-    TradeDirection,
+    ConstantProductCurve, CurveCalculator, CurveType, Fees, FlashFees, RoundDirection, StableCurve,
+    SwapCurve, TradeDirection,
 };
 pub use errors::ErrorCode;
 pub use program_id::*;
-pub use states::{DerivedAccountIdentifier, LPMint, Pool, PoolConfig};
+pub use states::{
+    AccessMode, AdminMultisig, AllowlistEntry, CircuitBreaker, CircuitBreakerParams, DcaOrder,
+    DcaSchedule, Deadline, DerivedAccountIdentifier, Farm, FarmStake, GlobalConfig,
+    GlobalConfigUpdate, LPMint, LegacyPool, LimitOrder, LockSummary, LockedPosition, Pool,
+    PoolConfig, PoolConstraints, PoolLimits, PoolRegistry, PoolRegistryPage, PoolRole, PoolRoles,
+    QueuedConfig, RegistryEntry, SlotVolume, TokenLimits, TripReason, MAX_MULTISIG_SIGNERS,
+    REGISTRY_PAGE_CAPACITY,
+};
+pub use token_interface::{associated_token_address, token_2022};
 
 use anchor_lang::prelude::*;
-use constraints::suspended;
+use constraints::{
+    allowlisted, approved, authorized, authorized_config, deposits_open, global_owner_approved,
+    global_withdrawals_open, globally_open, multisig_approved, not_expired, receives,
+    single_sided_open, swaps_open, unlocked, withdrawals_open,
+};
 use fehler::throws;
 
 #[cfg(not(feature = "ci"))]
@@ -56,7 +69,7 @@ pub mod pool {
     // Action: Deduct corresponding amount of token a and token b from user's ata account
     // Constraint: the deducted amount of token a and token b cannot exceed `maximum_token_a_amount` and `maximum_token_b_amount`
     #[throws(ProgramError)]
-    #[access_control(suspended(&ctx.accounts.pool))]
+    #[access_control(globally_open(&ctx.accounts.global_config) deposits_open(&ctx.accounts.pool) allowlisted(&ctx.accounts.pool, &ctx.accounts.allowlist_entry) unlocked(&ctx.accounts.pool))]
     pub fn deposit2(
         ctx: Context<Deposit2>,
         lp_token_amount: u64,
@@ -70,8 +83,34 @@ pub mod pool {
         )?
     }
 
+    // Deposits at most `amount_a` and `amount_b` at the ratio of the pool, `deposit2` fixes the LP amount instead.
     #[throws(ProgramError)]
-    #[access_control(suspended(&ctx.accounts.pool))]
+    #[access_control(globally_open(&ctx.accounts.global_config) deposits_open(&ctx.accounts.pool) allowlisted(&ctx.accounts.pool, &ctx.accounts.allowlist_entry) unlocked(&ctx.accounts.pool))]
+    pub fn deposit_exact_tokens(
+        ctx: Context<Deposit2>,
+        amount_a: u64,
+        amount_b: u64,
+        minimum_lp_token_amount: u64,
+    ) {
+        ctx.accounts
+            .process_exact_tokens(amount_a, amount_b, minimum_lp_token_amount)?
+    }
+
+    // Deposits `amount_a` and `amount_b` in any ratio, with a fee on the deviation from the pool ratio.
+    #[throws(ProgramError)]
+    #[access_control(globally_open(&ctx.accounts.global_config) deposits_open(&ctx.accounts.pool) single_sided_open(&ctx.accounts.pool) allowlisted(&ctx.accounts.pool, &ctx.accounts.allowlist_entry) unlocked(&ctx.accounts.pool))]
+    pub fn deposit_imbalanced(
+        ctx: Context<Deposit2>,
+        amount_a: u64,
+        amount_b: u64,
+        minimum_lp_token_amount: u64,
+    ) {
+        ctx.accounts
+            .process_imbalanced(amount_a, amount_b, minimum_lp_token_amount)?
+    }
+
+    #[throws(ProgramError)]
+    #[access_control(global_withdrawals_open(&ctx.accounts.global_config) withdrawals_open(&ctx.accounts.pool) unlocked(&ctx.accounts.pool) receives(&ctx.accounts.user_token_a_ata, ctx.accounts.user_wallet.key) receives(&ctx.accounts.user_token_b_ata, ctx.accounts.user_wallet.key))]
     pub fn withdraw2(
         ctx: Context<Withdraw2>,
         lp_token_amount: u64,
@@ -85,29 +124,423 @@ pub mod pool {
         )?
     }
 
+    // Withdraws `amount_a` and `amount_b` in any ratio, with a fee on the deviation from the pool ratio.
     #[throws(ProgramError)]
-    #[access_control(suspended(&ctx.accounts.pool))]
+    #[access_control(globally_open(&ctx.accounts.global_config) global_withdrawals_open(&ctx.accounts.global_config) withdrawals_open(&ctx.accounts.pool) single_sided_open(&ctx.accounts.pool) unlocked(&ctx.accounts.pool) receives(&ctx.accounts.user_token_a_ata, ctx.accounts.user_wallet.key) receives(&ctx.accounts.user_token_b_ata, ctx.accounts.user_wallet.key))]
+    pub fn withdraw_imbalanced(
+        ctx: Context<Withdraw2>,
+        amount_a: u64,
+        amount_b: u64,
+        maximum_lp_token_amount: u64,
+    ) {
+        ctx.accounts
+            .process_imbalanced(amount_a, amount_b, maximum_lp_token_amount)?
+    }
+
+    #[throws(ProgramError)]
+    #[access_control(globally_open(&ctx.accounts.global_config) deposits_open(&ctx.accounts.pool) single_sided_open(&ctx.accounts.pool) allowlisted(&ctx.accounts.pool, &ctx.accounts.allowlist_entry) unlocked(&ctx.accounts.pool))]
     pub fn deposit1(ctx: Context<Deposit1>, in_token_amount: u64, minimum_pool_token_amount: u64) {
         ctx.accounts
             .process(in_token_amount, minimum_pool_token_amount)?
     }
 
     #[throws(ProgramError)]
-    #[access_control(suspended(&ctx.accounts.pool))]
+    #[access_control(globally_open(&ctx.accounts.global_config) global_withdrawals_open(&ctx.accounts.global_config) withdrawals_open(&ctx.accounts.pool) single_sided_open(&ctx.accounts.pool) unlocked(&ctx.accounts.pool) receives(&ctx.accounts.out_token_ata_user, ctx.accounts.user_wallet.key))]
     pub fn withdraw1(ctx: Context<Withdraw1>, out_token_amount: u64, maximum_lp_token_amount: u64) {
         ctx.accounts
             .process(out_token_amount, maximum_lp_token_amount)?
     }
 
+    // Burns exactly `lp_token_amount` into the token of `out_token_ata_user`, `withdraw1` fixes the output instead.
+    #[throws(ProgramError)]
+    #[access_control(globally_open(&ctx.accounts.global_config) global_withdrawals_open(&ctx.accounts.global_config) withdrawals_open(&ctx.accounts.pool) single_sided_open(&ctx.accounts.pool) unlocked(&ctx.accounts.pool) receives(&ctx.accounts.out_token_ata_user, ctx.accounts.user_wallet.key))]
+    pub fn withdraw_one_exact_lp(
+        ctx: Context<Withdraw1>,
+        lp_token_amount: u64,
+        minimum_out_token_amount: u64,
+    ) {
+        ctx.accounts
+            .process_exact_lp(lp_token_amount, minimum_out_token_amount)?
+    }
+
     #[throws(ProgramError)]
-    #[access_control(suspended(&ctx.accounts.pool))]
+    #[access_control(globally_open(&ctx.accounts.global_config) swaps_open(&ctx.accounts.pool) allowlisted(&ctx.accounts.pool, &ctx.accounts.allowlist_entry) unlocked(&ctx.accounts.pool) receives(&ctx.accounts.out_token_ata_user, ctx.accounts.user_wallet.key))]
     pub fn swap(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u64) {
         ctx.accounts.process(amount_in, minimum_amount_out)?
     }
 
+    // Swaps through up to `MAX_ROUTE_HOPS` pools, e.g. A -> B -> C. The accounts of each hop are passed
+    // in the remaining accounts, see `ROUTE_HOP_ACCOUNTS`. Only the final output is checked for slippage.
+    #[throws(ProgramError)]
+    #[access_control(globally_open(&ctx.accounts.global_config))]
+    pub fn route_swap<'info>(
+        ctx: Context<'_, '_, '_, 'info, RouteSwap<'info>>,
+        amount_in: u64,
+        minimum_final_out: u64,
+        hops: u8,
+    ) {
+        ctx.accounts
+            .process(ctx.remaining_accounts, amount_in, minimum_final_out, hops)?
+    }
+
+    // Lends `amount_1` and `amount_2` from the vaults to the borrower and calls `on_flash_loan` of the
+    // receiver program, see `OnFlashLoan`. The vaults have to hold the loan plus the flash fees afterwards.
+    // Flash loans stop together with the swaps, and the pool is locked against reentrance meanwhile.
+    #[throws(ProgramError)]
+    #[access_control(globally_open(&ctx.accounts.global_config) swaps_open(&ctx.accounts.pool) allowlisted(&ctx.accounts.pool, &ctx.accounts.allowlist_entry) unlocked(&ctx.accounts.pool))]
+    pub fn flash_loan<'info>(
+        ctx: Context<'_, '_, '_, 'info, FlashLoan<'info>>,
+        amount_1: u64,
+        amount_2: u64,
+        data: Vec<u8>,
+    ) {
+        ctx.accounts
+            .process(ctx.remaining_accounts, amount_1, amount_2, data)?
+    }
+
+    // Escrows `amount_in` of `in_mint` in a `LimitOrder` that sells it for at least `minimum_amount_out`
+    // of the other token of the pool. The keeper filling it gets `bounty_bps` of the swap output.
+    #[throws(ProgramError)]
+    #[access_control(globally_open(&ctx.accounts.global_config) swaps_open(&ctx.accounts.pool) allowlisted(&ctx.accounts.pool, &ctx.accounts.allowlist_entry))]
+    pub fn place_order(
+        ctx: Context<PlaceOrder>,
+        id: u64,
+        bump: u8,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        bounty_bps: u16,
+    ) {
+        ctx.accounts
+            .process(id, bump, amount_in, minimum_amount_out, bounty_bps)?
+    }
+
+    #[throws(ProgramError)]
+    pub fn cancel_order(ctx: Context<CancelOrder>) {
+        ctx.accounts.process()?
+    }
+
+    // Fills the orders passed in the remaining accounts against the pool, see `FILL_ORDER_ACCOUNTS`.
+    // Anyone can call it, it fails unless every order gets its limit price after the bounty.
+    #[throws(ProgramError)]
+    #[access_control(globally_open(&ctx.accounts.global_config) swaps_open(&ctx.accounts.pool) unlocked(&ctx.accounts.pool))]
+    pub fn fill_orders<'info>(ctx: Context<'_, '_, '_, 'info, FillOrders<'info>>) {
+        ctx.accounts.process(ctx.remaining_accounts)?
+    }
+
+    // Escrows the `total_amount` of the schedule in a `DcaOrder` that sells it in slices of `slice_amount`,
+    // at most one every `interval` seconds, each for at least `minimum_slice_amount_out`.
+    #[throws(ProgramError)]
+    #[access_control(globally_open(&ctx.accounts.global_config) swaps_open(&ctx.accounts.pool) allowlisted(&ctx.accounts.pool, &ctx.accounts.allowlist_entry))]
+    pub fn create_dca_order(
+        ctx: Context<CreateDcaOrder>,
+        id: u64,
+        bump: u8,
+        schedule: DcaSchedule,
+    ) {
+        ctx.accounts.process(id, bump, schedule)?
+    }
+
+    // Swaps the next slice of a `DcaOrder` once its interval has elapsed. Anyone can call it,
+    // the keeper gets `keeper_fee_bps` of the swap output.
+    #[throws(ProgramError)]
+    #[access_control(globally_open(&ctx.accounts.global_config) swaps_open(&ctx.accounts.pool) unlocked(&ctx.accounts.pool))]
+    pub fn execute_dca_slice(ctx: Context<ExecuteDcaSlice>) {
+        ctx.accounts.process()?
+    }
+
+    // Gives the remainder of a `DcaOrder` back to its owner and closes it
+    #[throws(ProgramError)]
+    pub fn cancel_dca_order(ctx: Context<CancelDcaOrder>) {
+        ctx.accounts.process()?
+    }
+
+    // Every wallet creates its `FarmStake` once before staking. Staking, unstaking and claiming
+    // stay open while the pool is paused, they do not touch its vaults.
+    #[throws(ProgramError)]
+    pub fn create_farm_stake(ctx: Context<CreateFarmStake>, bump: u8) {
+        ctx.accounts.process(bump)?
+    }
+
+    #[throws(ProgramError)]
+    pub fn stake_lp(ctx: Context<StakeLp>, amount: u64) {
+        ctx.accounts.process(amount)?
+    }
+
+    #[throws(ProgramError)]
+    pub fn unstake_lp(ctx: Context<StakeLp>, amount: u64) {
+        ctx.accounts.process_unstake(amount)?
+    }
+
+    #[throws(ProgramError)]
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) {
+        ctx.accounts.process()?
+    }
+
+    // Locks `amount` LP tokens in a `LockedPosition` that vests linearly over `vesting_duration` seconds
+    // from now, with nothing vested during the first `cliff_duration` seconds. See `LockSummary` to verify
+    // the liquidity locked in a pool.
+    #[throws(ProgramError)]
+    pub fn lock_lp(
+        ctx: Context<LockLp>,
+        id: u64,
+        bump: u8,
+        amount: u64,
+        cliff_duration: i64,
+        vesting_duration: i64,
+    ) {
+        ctx.accounts
+            .process(id, bump, amount, cliff_duration, vesting_duration)?
+    }
+
+    // Releases the LP tokens vested since the last unlock, and closes the position once it is empty
+    #[throws(ProgramError)]
+    pub fn unlock_lp(ctx: Context<UnlockLp>) {
+        ctx.accounts.process()?
+    }
+
+    // The same user instructions with a deadline, the ones above are kept for existing integrators.
+
+    #[throws(ProgramError)]
+    #[access_control(not_expired(&deadline))]
+    pub fn deposit2_with_deadline(
+        ctx: Context<Deposit2>,
+        lp_token_amount: u64,
+        maximum_token_a_amount: u64,
+        maximum_token_b_amount: u64,
+        deadline: Deadline,
+    ) {
+        deposit2(
+            ctx,
+            lp_token_amount,
+            maximum_token_a_amount,
+            maximum_token_b_amount,
+        )?
+    }
+
+    #[throws(ProgramError)]
+    #[access_control(not_expired(&deadline))]
+    pub fn withdraw2_with_deadline(
+        ctx: Context<Withdraw2>,
+        lp_token_amount: u64,
+        minimum_token_a_amount: u64,
+        minimum_token_b_amount: u64,
+        deadline: Deadline,
+    ) {
+        withdraw2(
+            ctx,
+            lp_token_amount,
+            minimum_token_a_amount,
+            minimum_token_b_amount,
+        )?
+    }
+
+    #[throws(ProgramError)]
+    #[access_control(not_expired(&deadline))]
+    pub fn deposit1_with_deadline(
+        ctx: Context<Deposit1>,
+        in_token_amount: u64,
+        minimum_pool_token_amount: u64,
+        deadline: Deadline,
+    ) {
+        deposit1(ctx, in_token_amount, minimum_pool_token_amount)?
+    }
+
+    #[throws(ProgramError)]
+    #[access_control(not_expired(&deadline))]
+    pub fn withdraw1_with_deadline(
+        ctx: Context<Withdraw1>,
+        out_token_amount: u64,
+        maximum_lp_token_amount: u64,
+        deadline: Deadline,
+    ) {
+        withdraw1(ctx, out_token_amount, maximum_lp_token_amount)?
+    }
+
+    #[throws(ProgramError)]
+    #[access_control(not_expired(&deadline))]
+    pub fn swap_with_deadline(
+        ctx: Context<Swap>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        deadline: Deadline,
+    ) {
+        swap(ctx, amount_in, minimum_amount_out)?
+    }
+
+    // The same user instructions for native SOL. The SOL side token account of the user is the `TempWsol` account,
+    // which is created by the instruction, wraps the input lamports and is closed back into the wallet at the
+    // end with the output and what is left of the input. One of them fits in a transaction per user wallet.
+
+    #[throws(ProgramError)]
+    pub fn deposit2_native<'info>(
+        ctx: Context<'_, '_, '_, 'info, Deposit2Native<'info>>,
+        lp_token_amount: u64,
+        maximum_token_a_amount: u64,
+        maximum_token_b_amount: u64,
+    ) {
+        ctx.accounts
+            .wrap_sol(maximum_token_a_amount, maximum_token_b_amount)?;
+        deposit2(
+            Context::new(
+                ctx.program_id,
+                &mut ctx.accounts.deposit,
+                ctx.remaining_accounts,
+            ),
+            lp_token_amount,
+            maximum_token_a_amount,
+            maximum_token_b_amount,
+        )?;
+        ctx.accounts.temp_wsol.close()?
+    }
+
+    #[throws(ProgramError)]
+    pub fn withdraw2_native<'info>(
+        ctx: Context<'_, '_, '_, 'info, Withdraw2Native<'info>>,
+        lp_token_amount: u64,
+        minimum_token_a_amount: u64,
+        minimum_token_b_amount: u64,
+    ) {
+        ctx.accounts.verify_sol()?;
+        withdraw2(
+            Context::new(
+                ctx.program_id,
+                &mut ctx.accounts.withdraw,
+                ctx.remaining_accounts,
+            ),
+            lp_token_amount,
+            minimum_token_a_amount,
+            minimum_token_b_amount,
+        )?;
+        ctx.accounts.temp_wsol.close()?
+    }
+
+    #[throws(ProgramError)]
+    pub fn deposit1_native<'info>(
+        ctx: Context<'_, '_, '_, 'info, Deposit1Native<'info>>,
+        in_token_amount: u64,
+        minimum_pool_token_amount: u64,
+    ) {
+        ctx.accounts.wrap_sol(in_token_amount)?;
+        deposit1(
+            Context::new(
+                ctx.program_id,
+                &mut ctx.accounts.deposit,
+                ctx.remaining_accounts,
+            ),
+            in_token_amount,
+            minimum_pool_token_amount,
+        )?;
+        ctx.accounts.temp_wsol.close()?
+    }
+
+    #[throws(ProgramError)]
+    pub fn withdraw1_native<'info>(
+        ctx: Context<'_, '_, '_, 'info, Withdraw1Native<'info>>,
+        out_token_amount: u64,
+        maximum_lp_token_amount: u64,
+    ) {
+        ctx.accounts.verify_sol()?;
+        withdraw1(
+            Context::new(
+                ctx.program_id,
+                &mut ctx.accounts.withdraw,
+                ctx.remaining_accounts,
+            ),
+            out_token_amount,
+            maximum_lp_token_amount,
+        )?;
+        ctx.accounts.temp_wsol.close()?
+    }
+
+    #[throws(ProgramError)]
+    pub fn swap_native<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapNative<'info>>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) {
+        ctx.accounts.wrap_sol(amount_in)?;
+        swap(
+            Context::new(
+                ctx.program_id,
+                &mut ctx.accounts.swap,
+                ctx.remaining_accounts,
+            ),
+            amount_in,
+            minimum_amount_out,
+        )?;
+        ctx.accounts.temp_wsol.close()?
+    }
+
+    // The same user instructions paying the output to the token accounts of `recipient`, e.g. a merchant.
+    // The user wallet still signs, pays the input and burns its LP tokens.
+
+    #[throws(ProgramError)]
+    #[access_control(global_withdrawals_open(&ctx.accounts.global_config) withdrawals_open(&ctx.accounts.pool) unlocked(&ctx.accounts.pool) receives(&ctx.accounts.user_token_a_ata, &recipient) receives(&ctx.accounts.user_token_b_ata, &recipient))]
+    pub fn withdraw2_to(
+        ctx: Context<Withdraw2>,
+        lp_token_amount: u64,
+        minimum_token_a_amount: u64,
+        minimum_token_b_amount: u64,
+        recipient: Pubkey,
+    ) {
+        ctx.accounts.process(
+            lp_token_amount,
+            minimum_token_a_amount,
+            minimum_token_b_amount,
+        )?
+    }
+
+    #[throws(ProgramError)]
+    #[access_control(globally_open(&ctx.accounts.global_config) global_withdrawals_open(&ctx.accounts.global_config) withdrawals_open(&ctx.accounts.pool) single_sided_open(&ctx.accounts.pool) unlocked(&ctx.accounts.pool) receives(&ctx.accounts.out_token_ata_user, &recipient))]
+    pub fn withdraw1_to(
+        ctx: Context<Withdraw1>,
+        out_token_amount: u64,
+        maximum_lp_token_amount: u64,
+        recipient: Pubkey,
+    ) {
+        ctx.accounts
+            .process(out_token_amount, maximum_lp_token_amount)?
+    }
+
+    #[throws(ProgramError)]
+    #[access_control(globally_open(&ctx.accounts.global_config) swaps_open(&ctx.accounts.pool) allowlisted(&ctx.accounts.pool, &ctx.accounts.allowlist_entry) unlocked(&ctx.accounts.pool) receives(&ctx.accounts.out_token_ata_user, &recipient))]
+    pub fn swap_to(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u64, recipient: Pubkey) {
+        ctx.accounts.process(amount_in, minimum_amount_out)?
+    }
+
+    // ========== Registry Instructions ==========
+
+    // Anyone can pay for a new registry page once the existing ones are full.
+    #[throws(ProgramError)]
+    pub fn add_registry_page(ctx: Context<AddRegistryPage>, registry_bump: u8, page_bump: u8) {
+        ctx.accounts.process(registry_bump, page_bump)?
+    }
+
+    // ========== Global Instructions ==========
+
+    // The global config has to be initialized once before any pool is created.
+    #[throws(ProgramError)]
+    pub fn init_global_config(ctx: Context<InitGlobalConfig>, bump: u8) {
+        ctx.accounts.process(bump)?
+    }
+
+    // The kill switch: `suspended` stops every pool at once.
+    #[throws(ProgramError)]
+    #[access_control(global_owner_approved(&ctx.accounts.global_config, &ctx.accounts.owner, ctx.remaining_accounts))]
+    pub fn set_global_config(ctx: Context<SetGlobalConfig>, update: GlobalConfigUpdate) {
+        ctx.accounts.process(&update)?
+    }
+
+    #[throws(ProgramError)]
+    #[access_control(global_owner_approved(&ctx.accounts.global_config, &ctx.accounts.owner, ctx.remaining_accounts) approved(&ctx.accounts.new_owner, ctx.remaining_accounts))]
+    pub fn transfer_global_owner(ctx: Context<TransferGlobalOwner>) {
+        ctx.accounts.process()?
+    }
+
     // ========== Admin Instructions ==========
 
     #[throws(ProgramError)]
+    #[allow(clippy::too_many_arguments)]
     pub fn create_pool(
         ctx: Context<CreatePool>,
         seed: [u8; 32],
@@ -115,23 +548,171 @@ pub mod pool {
         lp_bump: u8,
         fees: Fees,
         swap_curve: SwapCurve,
+        config_delay: i64,
+        flash_fees: FlashFees,
+    ) {
+        ctx.accounts.process(
+            seed,
+            pool_bump,
+            lp_bump,
+            fees,
+            swap_curve,
+            config_delay,
+            flash_fees,
+        )?
+    }
+
+    // Pools created by the first version of the program have to be migrated into a pool of the
+    // current layout before any other instruction can use them. The pool moves to the address of
+    // `Pool` with new vaults, keeping its seed and LP mint. Anyone can do it and pay for the new
+    // accounts, the migration does not change how the pool behaves.
+    #[throws(ProgramError)]
+    pub fn migrate_pool(ctx: Context<MigratePool>, seed: [u8; 32], pool_bump: u8) {
+        ctx.accounts.process(seed, pool_bump)?
+    }
+
+    // Test fixture for `migrate_pool`, fails unless the program is built with the `ci` feature
+    #[throws(ProgramError)]
+    pub fn create_legacy_pool(
+        ctx: Context<CreateLegacyPool>,
+        seed: [u8; 32],
+        pool_bump: u8,
+        lp_bump: u8,
+        fees: Fees,
+        swap_curve: SwapCurve,
     ) {
         ctx.accounts
             .process(seed, pool_bump, lp_bump, fees, swap_curve)?
     }
 
     #[throws(ProgramError)]
+    #[access_control(approved(&ctx.accounts.admin, ctx.remaining_accounts) unlocked(&ctx.accounts.pool))]
     pub fn mint_lp_to(ctx: Context<MintLPTo>, n: u64) {
         ctx.accounts.process(n)?
     }
 
     #[throws(ProgramError)]
+    #[access_control(authorized(&ctx.accounts.pool, PoolRole::FeeManager, &ctx.accounts.authority, ctx.remaining_accounts) unlocked(&ctx.accounts.pool))]
     pub fn withdraw_fee(ctx: Context<WithdrawFee>) {
         ctx.accounts.process()?
     }
 
     #[throws(ProgramError)]
+    #[access_control(authorized_config(&ctx.accounts.pool, &ctx.accounts.authority, ctx.remaining_accounts, &config) unlocked(&ctx.accounts.pool))]
     pub fn config_pool(ctx: Context<ConfigPool>, config: PoolConfig) {
         ctx.accounts.process(&config)?
     }
+
+    // Changes other than pausing and resuming are timelocked: `queue_config` records the config,
+    // anyone can `execute_config` once the `config_delay` of the pool has passed,
+    // and the same authorities who can queue it can `cancel_config` before that.
+    #[throws(ProgramError)]
+    #[access_control(authorized_config(&ctx.accounts.pool, &ctx.accounts.authority, ctx.remaining_accounts, &config))]
+    pub fn queue_config(ctx: Context<QueueConfig>, config: PoolConfig, bump: u8) {
+        ctx.accounts.process(config, bump)?
+    }
+
+    #[throws(ProgramError)]
+    #[access_control(unlocked(&ctx.accounts.pool))]
+    pub fn execute_config(ctx: Context<ExecuteConfig>) {
+        ctx.accounts.process()?
+    }
+
+    #[throws(ProgramError)]
+    #[access_control(authorized_config(&ctx.accounts.pool, &ctx.accounts.authority, ctx.remaining_accounts, &ctx.accounts.queued_config.config))]
+    pub fn cancel_config(ctx: Context<CancelConfig>) {
+        ctx.accounts.process()?
+    }
+
+    #[throws(ProgramError)]
+    #[access_control(approved(&ctx.accounts.admin, ctx.remaining_accounts) unlocked(&ctx.accounts.pool))]
+    pub fn set_roles(ctx: Context<SetRoles>, roles: PoolRoles) {
+        ctx.accounts.process(&roles)?
+    }
+
+    // Handing over the admin is done in two steps so that a typo cannot lock the pool:
+    // the current admin proposes a new admin and the new admin accepts it by signing.
+    // To put a pool under an AdminMultisig, propose the multisig and let its signers accept.
+    #[throws(ProgramError)]
+    #[access_control(approved(&ctx.accounts.admin, ctx.remaining_accounts) unlocked(&ctx.accounts.pool))]
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) {
+        ctx.accounts.process(new_admin)?
+    }
+
+    #[throws(ProgramError)]
+    #[access_control(approved(&ctx.accounts.new_admin, ctx.remaining_accounts) unlocked(&ctx.accounts.pool))]
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) {
+        ctx.accounts.process()?
+    }
+
+    #[throws(ProgramError)]
+    #[access_control(approved(&ctx.accounts.admin, ctx.remaining_accounts) unlocked(&ctx.accounts.pool))]
+    pub fn cancel_admin_proposal(ctx: Context<CancelAdminProposal>) {
+        ctx.accounts.process()?
+    }
+
+    #[throws(ProgramError)]
+    #[access_control(approved(&ctx.accounts.admin, ctx.remaining_accounts))]
+    pub fn delist_pool(ctx: Context<DelistPool>) {
+        ctx.accounts.process()?
+    }
+
+    // A pool becomes permissioned by setting `access_mode` through `queue_config`.
+    // The admin then allows the wallets one by one, each with its own `AllowlistEntry`.
+    #[throws(ProgramError)]
+    #[access_control(approved(&ctx.accounts.admin, ctx.remaining_accounts))]
+    pub fn add_to_allowlist(ctx: Context<AddToAllowlist>, bump: u8) {
+        ctx.accounts.process(bump)?
+    }
+
+    #[throws(ProgramError)]
+    #[access_control(approved(&ctx.accounts.admin, ctx.remaining_accounts))]
+    pub fn remove_from_allowlist(ctx: Context<RemoveFromAllowlist>) {
+        ctx.accounts.process()?
+    }
+
+    // A pool has at most one `Farm`, which pays `emission_rate` reward tokens per second to the staked LP tokens.
+    // The rewards are funded upfront with `fund_farm`, by any wallet the admin approves.
+    #[throws(ProgramError)]
+    #[access_control(approved(&ctx.accounts.admin, ctx.remaining_accounts))]
+    pub fn create_farm(ctx: Context<CreateFarm>, bump: u8, emission_rate: u64) {
+        ctx.accounts.process(bump, emission_rate)?
+    }
+
+    #[throws(ProgramError)]
+    #[access_control(approved(&ctx.accounts.admin, ctx.remaining_accounts))]
+    pub fn fund_farm(ctx: Context<FundFarm>, amount: u64) {
+        ctx.accounts.process(amount)?
+    }
+
+    #[throws(ProgramError)]
+    #[access_control(approved(&ctx.accounts.admin, ctx.remaining_accounts))]
+    pub fn set_emission_rate(ctx: Context<SetEmissionRate>, emission_rate: u64) {
+        ctx.accounts.process(emission_rate)?
+    }
+
+    // ========== Multisig Instructions ==========
+
+    // Every admin (and role) instruction accepts an AdminMultisig in place of a signing key.
+    // Its signers approve by signing the transaction and being passed in as the remaining accounts.
+    #[throws(ProgramError)]
+    pub fn create_admin_multisig(
+        ctx: Context<CreateAdminMultisig>,
+        seed: [u8; 32],
+        bump: u8,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) {
+        ctx.accounts.process(seed, bump, signers, threshold)?
+    }
+
+    #[throws(ProgramError)]
+    #[access_control(multisig_approved(&ctx.accounts.multisig, ctx.remaining_accounts))]
+    pub fn update_admin_multisig(
+        ctx: Context<UpdateAdminMultisig>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) {
+        ctx.accounts.process(signers, threshold)?
+    }
 }
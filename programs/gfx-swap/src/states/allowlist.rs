@@ -0,0 +1,42 @@
+use super::DerivedAccountIdentifier;
+use anchor_lang::prelude::*;
+
+impl DerivedAccountIdentifier for AllowlistEntry {
+    const IDENT: &'static [u8] = b"GFXAllowlistEntry";
+}
+
+// Who can trade and provide liquidity in a pool. Withdrawals are open to every LP in both modes.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, AnchorDeserialize, AnchorSerialize)]
+pub enum AccessMode {
+    Open,
+    // only the wallets with an `AllowlistEntry` can swap and deposit
+    Permissioned,
+}
+
+impl Default for AccessMode {
+    fn default() -> Self {
+        AccessMode::Open
+    }
+}
+
+// The existence of this account allows `wallet` to use a permissioned pool.
+// It is derived from `[IDENT, pool, wallet]` and managed by the admin of the pool.
+#[account]
+#[derive(Default, Debug)]
+pub struct AllowlistEntry {
+    pub pool: Pubkey,
+    pub wallet: Pubkey,
+    pub bump: u8,
+    pub payer: Pubkey, // gets the rent back once the entry is removed
+}
+
+impl AllowlistEntry {
+    pub fn address(program_id: &Pubkey, pool: &Pubkey, wallet: &Pubkey) -> Pubkey {
+        Self::address_with_bump(program_id, pool, wallet).0
+    }
+
+    pub fn address_with_bump(program_id: &Pubkey, pool: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::IDENT, pool.as_ref(), wallet.as_ref()], program_id)
+    }
+}
@@ -0,0 +1,136 @@
+use super::BPS;
+use crate::errors::ErrorCode::*;
+use anchor_lang::prelude::*;
+use fehler::throws;
+use std::convert::TryFrom;
+
+// fixed point scale of the prices
+const PRICE_SCALE: u128 = 1_000_000_000_000;
+
+// Why the circuit breaker stopped the swaps of a pool.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, AnchorDeserialize, AnchorSerialize)]
+pub enum TripReason {
+    None,
+    // the price moved more than `max_window_move_bps` within `window_slots`
+    WindowMove,
+}
+
+impl Default for TripReason {
+    fn default() -> Self {
+        TripReason::None
+    }
+}
+
+// Protects an oracle-less pool from being drained at a bad price. The price is the reserve ratio
+// of the pool, i.e. token 2 per token 1. A single trade moving the price more than
+// `max_trade_move_bps` is rejected. A trade taking the price more than `max_window_move_bps` away
+// from the price at the start of the window is executed and trips the breaker, which stops the
+// trades of the pool until its swaps are resumed. Only executed trades count, so a trip is only
+// recorded if its transaction succeeds. A threshold of 0 disables the check.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct CircuitBreaker {
+    pub max_trade_move_bps: u16,
+    pub max_window_move_bps: u16,
+    pub window_slots: u64,
+    // the price at the start of the current window, 0 if no window is running
+    pub reference_price: u128,
+    pub window_start_slot: u64,
+    // the record of the last trip
+    pub tripped: TripReason,
+    pub tripped_move_bps: u64,
+    pub tripped_slot: u64,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct CircuitBreakerParams {
+    pub max_trade_move_bps: u16,
+    pub max_window_move_bps: u16,
+    pub window_slots: u64,
+}
+
+impl CircuitBreakerParams {
+    pub const LEN: usize = 2 + 2 + 8;
+
+    #[throws(ProgramError)]
+    pub fn validate(&self) {
+        require!(
+            self.max_window_move_bps == 0 || self.window_slots > 0,
+            InvalidCircuitBreaker
+        );
+    }
+}
+
+impl CircuitBreaker {
+    pub const LEN: usize = 2 + 2 + 8 + 16 + 8 + 1 + 8 + 8;
+
+    pub fn is_tripped(&self) -> bool {
+        self.tripped != TripReason::None
+    }
+
+    pub fn set_params(&mut self, params: &CircuitBreakerParams) {
+        self.max_trade_move_bps = params.max_trade_move_bps;
+        self.max_window_move_bps = params.max_window_move_bps;
+        self.window_slots = params.window_slots;
+        self.reference_price = 0;
+    }
+
+    // Clears the trip and starts a fresh window on the next trade.
+    pub fn reset(&mut self) {
+        self.tripped = TripReason::None;
+        self.reference_price = 0;
+    }
+
+    // Records a trade moving the pool from `price_before` to `price_after`, failing if the breaker
+    // is tripped or the trade moves the price too far on its own.
+    #[throws(ProgramError)]
+    pub fn observe(&mut self, price_before: u128, price_after: u128, slot: u64) {
+        require!(!self.is_tripped(), CircuitBreakerTripped);
+
+        if self.max_trade_move_bps > 0 {
+            require!(
+                move_bps(price_before, price_after)? <= self.max_trade_move_bps as u128,
+                PriceMoveTooLarge
+            );
+        }
+
+        if self.max_window_move_bps == 0 {
+            return;
+        }
+
+        if self.reference_price == 0 || slot >= self.window_start_slot + self.window_slots {
+            self.reference_price = price_before;
+            self.window_start_slot = slot;
+        }
+
+        let window_move = move_bps(self.reference_price, price_after)?;
+        if window_move > self.max_window_move_bps as u128 {
+            self.tripped = TripReason::WindowMove;
+            self.tripped_move_bps = u64::try_from(window_move).unwrap_or(u64::MAX);
+            self.tripped_slot = slot;
+            msg!(
+                "{}: the price moved {} bps within the window, the trades are stopped",
+                CircuitBreakerTripped,
+                window_move
+            );
+        }
+    }
+}
+
+// The price of the pool as token 2 per token 1
+#[throws(ProgramError)]
+pub fn price(amount_1: u128, amount_2: u128) -> u128 {
+    require!(amount_1 > 0, ZeroTradingTokens);
+    amount_2
+        .checked_mul(PRICE_SCALE)
+        .ok_or(CalculationFailure)?
+        / amount_1
+}
+
+// The relative move from `reference` to `price` in basis points
+#[throws(ProgramError)]
+fn move_bps(reference: u128, price: u128) -> u128 {
+    require!(reference > 0, ZeroTradingTokens);
+    let diff = price.max(reference) - price.min(reference);
+    diff.checked_mul(BPS).ok_or(CalculationFailure)? / reference
+}
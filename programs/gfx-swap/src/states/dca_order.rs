@@ -0,0 +1,104 @@
+use super::{DerivedAccountIdentifier, Escrow};
+use crate::errors::ErrorCode::*;
+use anchor_lang::prelude::*;
+use fehler::throws;
+use spl_math::checked_ceil_div::CheckedCeilDiv;
+
+impl DerivedAccountIdentifier for DcaOrder {
+    const IDENT: &'static [u8] = b"GFXDcaOrder";
+}
+
+// Sells `total_amount` of `in_mint` in `pool` in slices of `slice_amount`, one every `interval` seconds.
+// The input is escrowed in the associated token account of the order. Keepers execute the slices with
+// `execute_dca_slice` and the owner can take the remainder back with `cancel_dca_order` at any time.
+// It is derived from `[IDENT, pool, owner, id]`, so an owner can run several orders in a pool.
+#[account]
+#[derive(Default, Debug)]
+pub struct DcaOrder {
+    pub pool: Pubkey,
+    pub owner: Pubkey, // receives the output and gets the rent back
+    pub id: u64,
+    pub bump: u8,
+    pub in_mint: Pubkey,
+    pub out_mint: Pubkey,
+    pub total_amount: u64,
+    pub slice_amount: u64,
+    pub interval: i64,
+    // what the owner gets at least for a whole slice, after the keeper fee. A smaller last slice
+    // has to get the same price.
+    pub minimum_slice_amount_out: u64,
+    pub keeper_fee_bps: u16, // share of the swap output paid to the keeper of each slice
+    pub last_slice_at: i64,  // 0 until the first slice, which can be executed right away
+    pub executed_amount: u64,
+}
+
+// How a `DcaOrder` sells its input, see the fields of the same name in `DcaOrder`
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct DcaSchedule {
+    pub total_amount: u64,
+    pub slice_amount: u64,
+    pub interval: i64,
+    pub minimum_slice_amount_out: u64,
+    pub keeper_fee_bps: u16,
+}
+
+impl DcaSchedule {
+    #[throws(ProgramError)]
+    pub fn validate(&self) {
+        require!(
+            self.slice_amount > 0
+                && self.slice_amount <= self.total_amount
+                && self.interval >= 0
+                && self.minimum_slice_amount_out > 0
+                && self.keeper_fee_bps <= DcaOrder::MAX_KEEPER_FEE_BPS,
+            InvalidOrder
+        );
+    }
+}
+
+impl DcaOrder {
+    pub const MAX_KEEPER_FEE_BPS: u16 = 100;
+
+    pub fn address(program_id: &Pubkey, pool: &Pubkey, owner: &Pubkey, id: u64) -> Pubkey {
+        Self::address_with_bump(program_id, pool, owner, id).0
+    }
+
+    pub fn address_with_bump(
+        program_id: &Pubkey,
+        pool: &Pubkey,
+        owner: &Pubkey,
+        id: u64,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                Self::IDENT,
+                pool.as_ref(),
+                owner.as_ref(),
+                &id.to_le_bytes(),
+            ],
+            program_id,
+        )
+    }
+
+    pub fn is_due(&self, now: i64) -> bool {
+        self.last_slice_at == 0 || now >= self.last_slice_at.saturating_add(self.interval)
+    }
+
+    // The minimum output of a slice of `amount`, rounded up
+    #[throws(ProgramError)]
+    pub fn minimum_amount_out(&self, amount: u64) -> u64 {
+        let minimum = (self.minimum_slice_amount_out as u128)
+            .checked_mul(amount.into())
+            .ok_or(CalculationFailure)?;
+        let (minimum, _) = minimum
+            .checked_ceil_div(self.slice_amount.into())
+            .ok_or(CalculationFailure)?;
+        minimum as u64 // at most `minimum_slice_amount_out`, `amount` is at most a slice
+    }
+}
+
+impl Escrow for DcaOrder {
+    fn escrow_seeds(&self) -> (Pubkey, Pubkey, u64, u8) {
+        (self.pool, self.owner, self.id, self.bump)
+    }
+}
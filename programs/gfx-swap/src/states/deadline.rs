@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+// The last moment a user instruction can be executed at, so that a transaction landing late
+// does not trade at a stale price which is still within the slippage.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, PartialEq)]
+pub enum Deadline {
+    UnixTimestamp(i64),
+    Slot(u64),
+}
+
+impl Deadline {
+    pub fn has_passed(&self, clock: &Clock) -> bool {
+        match *self {
+            Deadline::UnixTimestamp(timestamp) => clock.unix_timestamp > timestamp,
+            Deadline::Slot(slot) => clock.slot > slot,
+        }
+    }
+
+    #[throws(ProgramError)]
+    pub fn check(&self) {
+        require!(!self.has_passed(&Clock::get()?), Expired);
+    }
+}
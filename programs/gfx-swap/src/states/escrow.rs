@@ -0,0 +1,111 @@
+use super::{DerivedAccountIdentifier, BPS};
+use crate::errors::ErrorCode::*;
+use crate::token_interface::{self, CpiAccount, InterfaceMint, InterfaceTokenAccount};
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+// An account escrowing tokens in its associated token accounts, like the orders and the locked positions.
+// It is derived from `[IDENT, pool, owner, id]` and signs for the escrow with these seeds.
+pub trait Escrow: DerivedAccountIdentifier {
+    // the pool, the owner, the id and the bump of the account
+    fn escrow_seeds(&self) -> (Pubkey, Pubkey, u64, u8);
+}
+
+pub trait EscrowExt<'info> {
+    fn transfer_from_escrow(
+        &self,
+        escrow: &CpiAccount<'info, InterfaceTokenAccount>,
+        to: &CpiAccount<'info, InterfaceTokenAccount>,
+        mint: &CpiAccount<'info, InterfaceMint>,
+        token_program: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<(), ProgramError>;
+
+    fn close_escrow(
+        &self,
+        escrow: &CpiAccount<'info, InterfaceTokenAccount>,
+        mint: &CpiAccount<'info, InterfaceMint>,
+        destination: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+    ) -> Result<(), ProgramError>;
+}
+
+impl<'info, T> EscrowExt<'info> for Account<'info, T>
+where
+    T: Escrow + AccountSerialize + AccountDeserialize + Owner + Clone,
+{
+    #[throws(ProgramError)]
+    fn transfer_from_escrow(
+        &self,
+        escrow: &CpiAccount<'info, InterfaceTokenAccount>,
+        to: &CpiAccount<'info, InterfaceTokenAccount>,
+        mint: &CpiAccount<'info, InterfaceMint>,
+        token_program: &AccountInfo<'info>,
+        amount: u64,
+    ) {
+        let (pool, owner, id, bump) = self.escrow_seeds();
+        token_interface::transfer_checked(
+            escrow,
+            to,
+            mint,
+            &self.to_account_info(),
+            token_program,
+            &[&[
+                T::IDENT,
+                pool.as_ref(),
+                owner.as_ref(),
+                &id.to_le_bytes(),
+                &[bump],
+            ]],
+            amount,
+        )?;
+    }
+
+    #[throws(ProgramError)]
+    fn close_escrow(
+        &self,
+        escrow: &CpiAccount<'info, InterfaceTokenAccount>,
+        mint: &CpiAccount<'info, InterfaceMint>,
+        destination: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+    ) {
+        let (pool, owner, id, bump) = self.escrow_seeds();
+        token_interface::close_account(
+            escrow,
+            mint,
+            destination,
+            &self.to_account_info(),
+            token_program,
+            &[&[
+                T::IDENT,
+                pool.as_ref(),
+                owner.as_ref(),
+                &id.to_le_bytes(),
+                &[bump],
+            ]],
+        )?;
+    }
+}
+
+// Splits the swap output of an order into the part of its owner and the fee of the keeper
+#[throws(ProgramError)]
+pub fn split_proceeds(amount_out: u64, fee_bps: u16) -> (u64, u64) {
+    let fee = (amount_out as u128)
+        .checked_mul(fee_bps.into())
+        .ok_or(CalculationFailure)?
+        / BPS;
+    let fee = fee as u64; // at most `amount_out`, the fee is at most 100%
+    (amount_out - fee, fee)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_is_rounded_down() {
+        assert_eq!(split_proceeds(1000, 100).unwrap(), (990, 10));
+        assert_eq!(split_proceeds(99, 100).unwrap(), (99, 0));
+        assert_eq!(split_proceeds(u64::MAX, 0).unwrap(), (u64::MAX, 0));
+    }
+}
@@ -0,0 +1,242 @@
+use super::DerivedAccountIdentifier;
+use crate::errors::ErrorCode::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, TokenAccount};
+use fehler::throws;
+
+impl DerivedAccountIdentifier for Farm {
+    const IDENT: &'static [u8] = b"GFXFarm";
+}
+
+impl DerivedAccountIdentifier for FarmStake {
+    const IDENT: &'static [u8] = b"GFXFarmStake";
+}
+
+// Scale of `acc_reward_per_share`, so that small rates over large stakes still accrue
+pub const REWARD_PER_SHARE_PRECISION: u128 = 1_000_000_000_000;
+
+// Pays `emission_rate` reward tokens per second to the LP tokens of `pool` staked in it, pro rata.
+// The rewards are funded upfront into `reward_vault` with `fund_farm` and only what has been funded
+// is emitted, the emissions stop while nothing is staked. Both vaults are associated token accounts
+// of the farm. It is derived from `[IDENT, pool]`, a pool has at most one farm.
+#[account]
+#[derive(Default, Debug)]
+pub struct Farm {
+    pub pool: Pubkey,
+    pub bump: u8,
+    pub reward_mint: Pubkey,
+    pub reward_vault: Pubkey,
+    pub lp_vault: Pubkey,
+    pub emission_rate: u64, // reward tokens per second
+    // the rewards of one staked LP token since the creation of the farm, times REWARD_PER_SHARE_PRECISION
+    pub acc_reward_per_share: u128,
+    pub last_update_at: i64,
+    pub total_staked: u64,
+    pub rewards_left: u64, // funded but not emitted yet
+}
+
+// The LP tokens staked by `owner` in `farm`. It is derived from `[IDENT, farm, owner]`.
+#[account]
+#[derive(Default, Debug)]
+pub struct FarmStake {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub bump: u8,
+    pub amount: u64,
+    // `amount * acc_reward_per_share` when the rewards were last settled, the usual per share accounting
+    pub reward_debt: u128,
+    pub unclaimed_rewards: u64, // settled but not claimed yet
+}
+
+impl Farm {
+    pub fn address(program_id: &Pubkey, pool: &Pubkey) -> Pubkey {
+        Self::address_with_bump(program_id, pool).0
+    }
+
+    pub fn address_with_bump(program_id: &Pubkey, pool: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::IDENT, pool.as_ref()], program_id)
+    }
+
+    // Emits the rewards since the last update. It has to be called before the stake or the rate changes.
+    #[throws(ProgramError)]
+    pub fn update(&mut self, now: i64) {
+        if now <= self.last_update_at {
+            return;
+        }
+
+        if self.total_staked > 0 {
+            let elapsed = (now - self.last_update_at) as u64;
+            let emitted = std::cmp::min(
+                self.emission_rate.saturating_mul(elapsed),
+                self.rewards_left,
+            );
+
+            let per_share = (emitted as u128)
+                .checked_mul(REWARD_PER_SHARE_PRECISION)
+                .ok_or(CalculationFailure)?
+                / self.total_staked as u128;
+            self.acc_reward_per_share = self
+                .acc_reward_per_share
+                .checked_add(per_share)
+                .ok_or(CalculationFailure)?;
+            self.rewards_left -= emitted;
+        }
+
+        self.last_update_at = now;
+    }
+
+    // The rewards of `amount` staked LP tokens since the creation of the farm, rounded down
+    #[throws(ProgramError)]
+    pub fn accrued(&self, amount: u64) -> u128 {
+        (amount as u128)
+            .checked_mul(self.acc_reward_per_share)
+            .ok_or(CalculationFailure)?
+            / REWARD_PER_SHARE_PRECISION
+    }
+}
+
+impl FarmStake {
+    pub fn address(program_id: &Pubkey, farm: &Pubkey, owner: &Pubkey) -> Pubkey {
+        Self::address_with_bump(program_id, farm, owner).0
+    }
+
+    pub fn address_with_bump(program_id: &Pubkey, farm: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[Self::IDENT, farm.as_ref(), owner.as_ref()], program_id)
+    }
+
+    // Moves the rewards accrued since the last settlement into `unclaimed_rewards`. The farm has to be updated.
+    #[throws(ProgramError)]
+    pub fn settle(&mut self, farm: &Farm) {
+        let accrued = farm.accrued(self.amount)?;
+        let pending = accrued
+            .checked_sub(self.reward_debt)
+            .ok_or(CalculationFailure)?;
+        self.unclaimed_rewards = self
+            .unclaimed_rewards
+            .checked_add(pending as u64)
+            .ok_or(CalculationFailure)?;
+        self.reward_debt = accrued;
+    }
+
+    // Changes the stake to `amount` after settling the rewards of the previous one
+    #[throws(ProgramError)]
+    pub fn restake(&mut self, farm: &mut Farm, amount: u64) {
+        self.settle(farm)?;
+        farm.total_staked = farm
+            .total_staked
+            .checked_sub(self.amount)
+            .and_then(|total| total.checked_add(amount))
+            .ok_or(CalculationFailure)?;
+        self.amount = amount;
+        self.reward_debt = farm.accrued(amount)?;
+    }
+}
+
+// The farm signs for its vaults
+pub trait FarmExt<'info> {
+    fn transfer_from_vault(
+        &self,
+        vault: &Account<'info, TokenAccount>,
+        to: &Account<'info, TokenAccount>,
+        token_program: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<(), ProgramError>;
+}
+
+impl<'info> FarmExt<'info> for Account<'info, Farm> {
+    #[throws(ProgramError)]
+    fn transfer_from_vault(
+        &self,
+        vault: &Account<'info, TokenAccount>,
+        to: &Account<'info, TokenAccount>,
+        token_program: &AccountInfo<'info>,
+        amount: u64,
+    ) {
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token::Transfer {
+                    from: vault.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: self.to_account_info(),
+                },
+                &[&[Farm::IDENT, self.pool.as_ref(), &[self.bump]]],
+            ),
+            amount,
+        )?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn restake(farm: &mut Farm, stake: &mut FarmStake, now: i64, amount: u64) {
+        farm.update(now).unwrap();
+        stake.restake(farm, amount).unwrap();
+    }
+
+    fn claim(farm: &mut Farm, stake: &mut FarmStake, now: i64) -> u64 {
+        farm.update(now).unwrap();
+        stake.settle(farm).unwrap();
+        std::mem::take(&mut stake.unclaimed_rewards)
+    }
+
+    fn farm(emission_rate: u64, rewards_left: u64) -> Farm {
+        Farm {
+            emission_rate,
+            rewards_left,
+            last_update_at: 1000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn stakers_joining_at_different_times() {
+        let mut farm = farm(100, 1_000_000);
+        let mut alice = FarmStake::default();
+        let mut bob = FarmStake::default();
+
+        // alice is alone for 10 seconds, then bob stakes four times as much
+        restake(&mut farm, &mut alice, 1000, 100);
+        restake(&mut farm, &mut bob, 1010, 400);
+
+        assert_eq!(claim(&mut farm, &mut alice, 1020), 1000 + 200);
+        assert_eq!(claim(&mut farm, &mut bob, 1020), 800);
+
+        // alice leaves, bob gets everything afterwards
+        restake(&mut farm, &mut alice, 1030, 0);
+        assert_eq!(claim(&mut farm, &mut alice, 1040), 200);
+        assert_eq!(claim(&mut farm, &mut bob, 1040), 800 + 1000);
+
+        assert_eq!(farm.rewards_left, 1_000_000 - 4000);
+    }
+
+    #[test]
+    fn nothing_is_emitted_without_stakers() {
+        let mut farm = farm(100, 1_000_000);
+        let mut alice = FarmStake::default();
+
+        restake(&mut farm, &mut alice, 1100, 50);
+        assert_eq!(farm.rewards_left, 1_000_000);
+        assert_eq!(claim(&mut farm, &mut alice, 1110), 1000);
+    }
+
+    #[test]
+    fn emissions_stop_when_the_rewards_run_out() {
+        let mut farm = farm(100, 1500);
+        let mut alice = FarmStake::default();
+        let mut bob = FarmStake::default();
+
+        restake(&mut farm, &mut alice, 1000, 1);
+        restake(&mut farm, &mut bob, 1010, 2);
+
+        // only 500 of the 1000 emitted between 1010 and 1020 are funded
+        assert_eq!(claim(&mut farm, &mut alice, 1020), 1000 + 166);
+        assert_eq!(claim(&mut farm, &mut bob, 1020), 333);
+        assert_eq!(farm.rewards_left, 0);
+
+        // the payouts are rounded down, they never exceed the funded rewards
+        assert_eq!(claim(&mut farm, &mut bob, 2000), 0);
+    }
+}
@@ -0,0 +1,89 @@
+use super::DerivedAccountIdentifier;
+use crate::curve::Fees;
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+impl DerivedAccountIdentifier for GlobalConfig {
+    const IDENT: &'static [u8] = b"GFXGlobalConfig";
+}
+
+// The program wide config. There is only one of it, derived from the IDENT without any seed.
+// The upgrade authority of the program has to sign its initialization and becomes the owner.
+#[account]
+#[derive(Default, Debug)]
+pub struct GlobalConfig {
+    pub bump: u8,
+    pub owner: Pubkey, // the protocol owner, a signer or an AdminMultisig
+    // kill switch of all the pools, like the default pause of a pool it keeps `withdraw2` open
+    pub suspended: bool,
+    pub withdrawals_suspended: bool,
+    // the fee manager new pools start with, Pubkey::default() for none
+    pub fee_recipient: Pubkey,
+    pub constraints: PoolConstraints,
+}
+
+// Bounds every pool has to respect when it is created or configured.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct PoolConstraints {
+    // the highest trade fee a pool can charge, 0/0 for no limit
+    pub max_trade_fee_numerator: u64,
+    pub max_trade_fee_denominator: u64,
+    // the shortest timelock a pool can have on its config changes
+    pub min_config_delay: i64,
+}
+
+impl PoolConstraints {
+    #[throws(ProgramError)]
+    pub fn validate_fees(&self, fees: &Fees) {
+        if self.max_trade_fee_denominator == 0 {
+            return;
+        }
+
+        // fee_numerator / fee_denominator <= max_numerator / max_denominator
+        require!(
+            fees.trade_fee_numerator as u128 * self.max_trade_fee_denominator as u128
+                <= self.max_trade_fee_numerator as u128 * fees.trade_fee_denominator as u128,
+            ConstraintViolated
+        );
+    }
+
+    #[throws(ProgramError)]
+    pub fn validate_config_delay(&self, config_delay: i64) {
+        require!(config_delay >= self.min_config_delay, ConstraintViolated);
+    }
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Default)]
+pub struct GlobalConfigUpdate {
+    pub suspended: Option<bool>,
+    pub withdrawals_suspended: Option<bool>,
+    pub fee_recipient: Option<Pubkey>,
+    pub constraints: Option<PoolConstraints>,
+}
+
+impl GlobalConfig {
+    pub fn update(&mut self, update: &GlobalConfigUpdate) {
+        let GlobalConfigUpdate {
+            suspended,
+            withdrawals_suspended,
+            fee_recipient,
+            constraints,
+        } = update;
+
+        if let Some(suspended) = suspended {
+            self.suspended = *suspended;
+        }
+
+        if let Some(withdrawals_suspended) = withdrawals_suspended {
+            self.withdrawals_suspended = *withdrawals_suspended;
+        }
+
+        if let Some(fee_recipient) = fee_recipient {
+            self.fee_recipient = *fee_recipient;
+        }
+
+        if let Some(constraints) = constraints {
+            self.constraints = *constraints;
+        }
+    }
+}
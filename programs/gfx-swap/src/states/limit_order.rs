@@ -0,0 +1,71 @@
+use super::{DerivedAccountIdentifier, Escrow};
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+impl DerivedAccountIdentifier for LimitOrder {
+    const IDENT: &'static [u8] = b"GFXLimitOrder";
+}
+
+// A resting order of `maker` selling `amount_in` of `in_mint` in `pool` for at least `minimum_amount_out`
+// of `out_mint`, i.e. at a price of at least `minimum_amount_out / amount_in`. The input is escrowed in the
+// associated token account of the order until a keeper fills it with `fill_orders` or the maker cancels it.
+// It is derived from `[IDENT, pool, maker, id]`, so a maker can place several orders in a pool.
+#[account]
+#[derive(Default, Debug)]
+pub struct LimitOrder {
+    pub pool: Pubkey,
+    pub maker: Pubkey, // receives the output and gets the rent back
+    pub id: u64,
+    pub bump: u8,
+    pub in_mint: Pubkey,
+    pub out_mint: Pubkey,
+    pub amount_in: u64,
+    pub minimum_amount_out: u64, // what the maker gets at least, after the keeper bounty
+    pub bounty_bps: u16,         // share of the swap output paid to the keeper that fills the order
+}
+
+impl LimitOrder {
+    pub const MAX_BOUNTY_BPS: u16 = 1000;
+
+    pub fn address(program_id: &Pubkey, pool: &Pubkey, maker: &Pubkey, id: u64) -> Pubkey {
+        Self::address_with_bump(program_id, pool, maker, id).0
+    }
+
+    pub fn address_with_bump(
+        program_id: &Pubkey,
+        pool: &Pubkey,
+        maker: &Pubkey,
+        id: u64,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                Self::IDENT,
+                pool.as_ref(),
+                maker.as_ref(),
+                &id.to_le_bytes(),
+            ],
+            program_id,
+        )
+    }
+
+    #[throws(ProgramError)]
+    pub fn verify_address(&self, address: &Pubkey) {
+        let expected = Pubkey::create_program_address(
+            &[
+                Self::IDENT,
+                self.pool.as_ref(),
+                self.maker.as_ref(),
+                &self.id.to_le_bytes(),
+                &[self.bump],
+            ],
+            &crate::ID,
+        )?;
+        require!(&expected == address, ContractAddressNotCorrect);
+    }
+}
+
+impl Escrow for LimitOrder {
+    fn escrow_seeds(&self) -> (Pubkey, Pubkey, u64, u8) {
+        (self.pool, self.maker, self.id, self.bump)
+    }
+}
@@ -0,0 +1,85 @@
+use crate::curve::TradeDirection;
+use crate::errors::ErrorCode::*;
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+// Risk caps for guarded launches, 0 for no limit.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct PoolLimits {
+    pub token_1: TokenLimits,
+    pub token_2: TokenLimits,
+    // deposits cannot take the LP token supply above this
+    pub max_deposit_lp_supply: u64,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct TokenLimits {
+    // the most of the token a single swap or single sided or imbalanced deposit can put in, and a
+    // single sided or imbalanced withdrawal can take out
+    pub max_amount_in_per_swap: u64,
+    // the same for all of them within a slot
+    pub max_volume_per_slot: u64,
+}
+
+// The amounts moved within `slot` by swaps and single sided or imbalanced liquidity
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct SlotVolume {
+    pub slot: u64,
+    pub token_1: u64,
+    pub token_2: u64,
+}
+
+impl PoolLimits {
+    pub const LEN: usize = 2 * (8 + 8) + 8;
+
+    pub fn token(&self, direction: TradeDirection) -> &TokenLimits {
+        match direction {
+            TradeDirection::AtoB => &self.token_1,
+            TradeDirection::BtoA => &self.token_2,
+        }
+    }
+
+    #[throws(ProgramError)]
+    pub fn check_amount_in(&self, direction: TradeDirection, amount_in: u64) {
+        let max = self.token(direction).max_amount_in_per_swap;
+        require!(max == 0 || amount_in <= max, TradeTooLarge);
+    }
+
+    #[throws(ProgramError)]
+    pub fn check_lp_supply(&self, lp_supply_after: u64) {
+        require!(
+            self.max_deposit_lp_supply == 0 || lp_supply_after <= self.max_deposit_lp_supply,
+            DepositCapExceeded
+        );
+    }
+}
+
+impl SlotVolume {
+    pub const LEN: usize = 3 * 8;
+
+    // Adds a swap to the counter of its token, which restarts at every slot.
+    #[throws(ProgramError)]
+    pub fn record(
+        &mut self,
+        limits: &PoolLimits,
+        direction: TradeDirection,
+        amount_in: u64,
+        slot: u64,
+    ) {
+        if slot != self.slot {
+            *self = SlotVolume {
+                slot,
+                ..Default::default()
+            };
+        }
+
+        let volume = match direction {
+            TradeDirection::AtoB => &mut self.token_1,
+            TradeDirection::BtoA => &mut self.token_2,
+        };
+        *volume = volume.checked_add(amount_in).ok_or(CalculationFailure)?;
+
+        let max = limits.token(direction).max_volume_per_slot;
+        require!(max == 0 || *volume <= max, SlotVolumeExceeded);
+    }
+}
@@ -0,0 +1,169 @@
+use super::{DerivedAccountIdentifier, Escrow};
+use crate::errors::ErrorCode::*;
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+impl DerivedAccountIdentifier for LockedPosition {
+    const IDENT: &'static [u8] = b"GFXLockedPosition";
+}
+
+// `total_amount` LP tokens of `pool` locked by `owner`. Nothing vests before `cliff_at`, then the
+// position vests linearly from `start_at` to `end_at`, i.e. the part accrued until the cliff is released
+// at once. The LP tokens are escrowed in the associated token account of the position and only `unlock_lp`
+// releases the vested ones to the owner. It is derived from `[IDENT, pool, owner, id]`.
+#[account]
+#[derive(Default, Debug)]
+pub struct LockedPosition {
+    pub pool: Pubkey,
+    pub owner: Pubkey, // receives the LP tokens and gets the rent back
+    pub id: u64,
+    pub bump: u8,
+    pub lp_mint: Pubkey,
+    pub total_amount: u64,
+    pub released_amount: u64,
+    pub start_at: i64,
+    pub cliff_at: i64,
+    pub end_at: i64,
+}
+
+// The LP tokens still escrowed by the positions of a pool at some moment, for anyone to verify the
+// liquidity locked in the pool. The positions of a pool can be listed with `getProgramAccounts`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LockSummary {
+    pub positions: u64,
+    pub locked: u64,   // not vested yet
+    pub unlocked: u64, // vested but not released yet
+}
+
+impl LockedPosition {
+    pub fn address(program_id: &Pubkey, pool: &Pubkey, owner: &Pubkey, id: u64) -> Pubkey {
+        Self::address_with_bump(program_id, pool, owner, id).0
+    }
+
+    pub fn address_with_bump(
+        program_id: &Pubkey,
+        pool: &Pubkey,
+        owner: &Pubkey,
+        id: u64,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                Self::IDENT,
+                pool.as_ref(),
+                owner.as_ref(),
+                &id.to_le_bytes(),
+            ],
+            program_id,
+        )
+    }
+
+    // The LP tokens vested at `now`, released or not, rounded down
+    #[throws(ProgramError)]
+    pub fn vested_amount(&self, now: i64) -> u64 {
+        if now < self.cliff_at {
+            0
+        } else if now >= self.end_at {
+            self.total_amount
+        } else {
+            let elapsed = (now - self.start_at) as u128; // the cliff is not before the start
+            let duration = (self.end_at - self.start_at) as u128;
+            let vested = (self.total_amount as u128)
+                .checked_mul(elapsed)
+                .ok_or(CalculationFailure)?
+                / duration;
+            vested as u64 // less than `total_amount`
+        }
+    }
+
+    // The LP tokens `unlock_lp` releases at `now`
+    #[throws(ProgramError)]
+    pub fn unlockable_amount(&self, now: i64) -> u64 {
+        self.vested_amount(now)?
+            .checked_sub(self.released_amount)
+            .ok_or(CalculationFailure)?
+    }
+}
+
+impl LockSummary {
+    // Sums up the positions of `pool` among `positions` at `now`, the other ones are skipped
+    #[throws(ProgramError)]
+    pub fn new<'a>(
+        pool: &Pubkey,
+        positions: impl IntoIterator<Item = &'a LockedPosition>,
+        now: i64,
+    ) -> Self {
+        let mut summary = LockSummary::default();
+
+        for position in positions.into_iter().filter(|p| &p.pool == pool) {
+            let vested = position.vested_amount(now)?;
+            summary.positions += 1;
+            summary.locked = summary
+                .locked
+                .checked_add(position.total_amount - vested)
+                .ok_or(CalculationFailure)?;
+            summary.unlocked = summary
+                .unlocked
+                .checked_add(position.unlockable_amount(now)?)
+                .ok_or(CalculationFailure)?;
+        }
+
+        summary
+    }
+}
+
+impl Escrow for LockedPosition {
+    fn escrow_seeds(&self) -> (Pubkey, Pubkey, u64, u8) {
+        (self.pool, self.owner, self.id, self.bump)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(pool: Pubkey, total_amount: u64) -> LockedPosition {
+        LockedPosition {
+            pool,
+            total_amount,
+            start_at: 1000,
+            cliff_at: 1100,
+            end_at: 1400,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn vesting_with_a_cliff() {
+        let position = position(Pubkey::new_unique(), 4000);
+
+        assert_eq!(position.vested_amount(0).unwrap(), 0);
+        assert_eq!(position.vested_amount(1099).unwrap(), 0);
+        // what accrued during the cliff vests at once
+        assert_eq!(position.vested_amount(1100).unwrap(), 1000);
+        assert_eq!(position.vested_amount(1301).unwrap(), 3010);
+        assert_eq!(position.vested_amount(1400).unwrap(), 4000);
+        assert_eq!(position.vested_amount(i64::MAX).unwrap(), 4000);
+    }
+
+    #[test]
+    fn summary_of_a_pool() {
+        let pool = Pubkey::new_unique();
+        let mut released = position(pool, 4000);
+        released.released_amount = 1000;
+        let positions = [
+            position(pool, 4000),
+            released,
+            position(Pubkey::new_unique(), 4000),
+        ];
+
+        let summary = LockSummary::new(&pool, &positions, 1200).unwrap();
+        assert_eq!(
+            summary,
+            LockSummary {
+                positions: 2,
+                locked: 2000 + 2000,
+                unlocked: 2000 + 1000,
+            }
+        );
+    }
+}
@@ -1,13 +1,42 @@
+mod allowlist;
+mod circuit_breaker;
+mod dca_order;
+mod deadline;
+mod escrow;
+mod farm;
+mod global_config;
+mod limit_order;
+mod limits;
+mod locked_position;
 mod lp_mint;
+mod multisig;
 mod pool;
+mod queued_config;
+mod registry;
 
+pub use allowlist::{AccessMode, AllowlistEntry};
+pub use circuit_breaker::{price, CircuitBreaker, CircuitBreakerParams, TripReason};
+pub use dca_order::{DcaOrder, DcaSchedule};
+pub use deadline::Deadline;
+pub use escrow::{split_proceeds, Escrow, EscrowExt};
+pub use farm::{Farm, FarmExt, FarmStake};
+pub use global_config::{GlobalConfig, GlobalConfigUpdate, PoolConstraints};
+pub use limit_order::LimitOrder;
+pub use limits::{PoolLimits, SlotVolume, TokenLimits};
+pub use locked_position::{LockSummary, LockedPosition};
 pub use lp_mint::LPMint;
-pub use pool::{Pool, PoolConfig, PoolExt};
+pub use multisig::{AdminMultisig, MAX_MULTISIG_SIGNERS};
+pub use pool::{LegacyPool, Pool, PoolConfig, PoolExt, PoolRole, PoolRoles, SwapQuote};
+pub use queued_config::QueuedConfig;
+pub use registry::{PoolRegistry, PoolRegistryPage, RegistryEntry, REGISTRY_PAGE_CAPACITY};
 
 use crate::errors::ErrorCode::*;
 use anchor_lang::prelude::*;
 use fehler::{throw, throws};
 
+// basis points, the unit of the fee and price move parameters
+pub(crate) const BPS: u128 = 10000;
+
 // All the PDA account of this program are derived from a same seed with `find_program_address(IDENT, seed)`.
 pub trait DerivedAccountIdentifier {
     const IDENT: &'static [u8];
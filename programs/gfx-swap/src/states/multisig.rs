@@ -0,0 +1,66 @@
+use super::DerivedAccountIdentifier;
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+// How many signers a multisig can hold.
+pub const MAX_MULTISIG_SIGNERS: usize = 10;
+
+impl DerivedAccountIdentifier for AdminMultisig {
+    const IDENT: &'static [u8] = b"GFXAdminMultisig";
+}
+
+// A multisig which can be set as the admin (or a role) of pools.
+// It approves an instruction when at least `threshold` of the `signers`
+// sign the transaction and are passed in as remaining accounts.
+#[account]
+#[derive(Default, Debug)]
+pub struct AdminMultisig {
+    pub seed: [u8; 32],
+    pub bump: u8,
+    pub threshold: u8,
+    pub signers: Vec<Pubkey>,
+}
+
+impl AdminMultisig {
+    // discriminator + seed + bump + threshold + vec length + signers
+    pub const LEN: usize = 8 + 32 + 1 + 1 + 4 + MAX_MULTISIG_SIGNERS * 32;
+
+    #[throws(ProgramError)]
+    pub fn set_signers(&mut self, signers: Vec<Pubkey>, threshold: u8) {
+        require!(
+            !signers.is_empty() && signers.len() <= MAX_MULTISIG_SIGNERS,
+            InvalidMultisig
+        );
+        require!(
+            threshold > 0 && threshold as usize <= signers.len(),
+            InvalidMultisig
+        );
+
+        for (i, signer) in signers.iter().enumerate() {
+            require!(!signers[..i].contains(signer), InvalidMultisig);
+        }
+
+        self.signers = signers;
+        self.threshold = threshold;
+    }
+
+    // Number of distinct multisig signers which signed the transaction.
+    pub fn approvals(&self, accounts: &[AccountInfo]) -> usize {
+        self.signers
+            .iter()
+            .filter(|signer| {
+                accounts
+                    .iter()
+                    .any(|account| account.is_signer && account.key == *signer)
+            })
+            .count()
+    }
+
+    #[throws(ProgramError)]
+    pub fn verify(&self, accounts: &[AccountInfo]) {
+        require!(
+            self.approvals(accounts) >= self.threshold as usize,
+            NotEnoughApprovals
+        );
+    }
+}
@@ -1,13 +1,22 @@
-use super::DerivedAccountIdentifier;
-use crate::curve::{Fees, SwapCurve};
+use super::{
+    price, AccessMode, CircuitBreaker, CircuitBreakerParams, DerivedAccountIdentifier, PoolLimits,
+    SlotVolume,
+};
+use crate::curve::{CurveCalculator, Fees, FlashFees, SwapCurve, TradeDirection};
+use crate::errors::ErrorCode::*;
+use crate::token_interface::{self, CpiAccount, InterfaceMint, InterfaceTokenAccount};
+use crate::utils;
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Mint, TokenAccount};
 use fehler::throws;
 use solana_program::program_error::ProgramError;
 
 impl DerivedAccountIdentifier for Pool {
-    const IDENT: &'static [u8] = b"GFXPool";
+    const IDENT: &'static [u8] = b"GFXPoolV2";
 }
+// The fields up to `swaps_suspended` are the layout of the first version of the program, the
+// others are appended in the order they were added. Pools of the first version are moved into a
+// pool of the current layout with `migrate_pool`, see `LegacyPool`.
 #[account]
 #[derive(Default, Debug)]
 pub struct Pool {
@@ -24,10 +33,121 @@ pub struct Pool {
     pub fee_vault: Pubkey,
     pub fees: Fees,
     pub curve: SwapCurve,
-    pub suspended: bool,
+    // pause flags, one per kind of user operation. `swaps_suspended` is the former `suspended`.
+    pub swaps_suspended: bool,
+    // the admin proposed by `propose_admin`, Pubkey::default() if there is no proposal
+    pub pending_admin: Pubkey,
+    // role keys, Pubkey::default() if unassigned. The admin can always act as any of the roles.
+    pub fee_manager: Pubkey,
+    pub pause_guardian: Pubkey,
+    pub curve_manager: Pubkey,
+    pub deposits_suspended: bool,
+    pub single_sided_suspended: bool, // deposit1 and withdraw1
+    pub withdrawals_suspended: bool,
+    pub circuit_breaker: CircuitBreaker,
+    pub limits: PoolLimits,
+    pub slot_volume: SlotVolume,
+    // seconds a queued config has to wait before it can be executed
+    pub config_delay: i64,
+    pub access_mode: AccessMode,
+    // set while a flash loan of the pool is out, so the borrower cannot reenter the pool
+    pub locked: bool,
+    pub flash_fees: FlashFees,
+}
+
+// A pool as created by the first version of the program. Its account is too small for the current
+// layout, so it lives at its own address and keeps the discriminator of `Pool`.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, Default)]
+pub struct LegacyPool {
+    pub seed: [u8; 32],
+    pub bump: u8,
+    pub lp_bump: u8,
+    pub admin: Pubkey,
+    pub token_mint_1: Pubkey,
+    pub token_mint_2: Pubkey,
+    pub token_vault_1: Pubkey,
+    pub token_vault_2: Pubkey,
+    pub mint: Pubkey,
+    pub fee_vault: Pubkey,
+    pub fees: Fees,
+    pub curve: SwapCurve,
+    pub suspended: bool, // suspended all the user operations
+}
+
+impl DerivedAccountIdentifier for LegacyPool {
+    const IDENT: &'static [u8] = b"GFXPool";
+}
+
+impl From<LegacyPool> for Pool {
+    // The appended fields start unset, except for the pause flags which keep a suspended pool suspended.
+    fn from(legacy: LegacyPool) -> Self {
+        let LegacyPool {
+            seed,
+            bump,
+            lp_bump,
+            admin,
+            token_mint_1,
+            token_mint_2,
+            token_vault_1,
+            token_vault_2,
+            mint,
+            fee_vault,
+            fees,
+            curve,
+            suspended,
+        } = legacy;
+
+        Pool {
+            seed,
+            bump,
+            lp_bump,
+            admin,
+            token_mint_1,
+            token_mint_2,
+            token_vault_1,
+            token_vault_2,
+            mint,
+            fee_vault,
+            fees,
+            curve,
+            swaps_suspended: suspended,
+            deposits_suspended: suspended,
+            single_sided_suspended: suspended,
+            withdrawals_suspended: suspended,
+            ..Default::default()
+        }
+    }
+}
+
+// A trade against a pool, priced before any token moves.
+#[derive(Clone, Copy, Debug)]
+pub struct SwapQuote {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub owner_fee: u64, // LP tokens minted to the fee vault
 }
 
 impl Pool {
+    // discriminator + the fields, with the largest curve
+    pub const LEN: usize = 8
+        + 32
+        + 1
+        + 1
+        + 7 * 32
+        + Fees::LEN
+        + SwapCurve::MAX_LEN
+        + 1
+        + 4 * 32
+        + 3
+        + CircuitBreaker::LEN
+        + PoolLimits::LEN
+        + SlotVolume::LEN
+        + 8
+        + 1
+        + 1
+        + FlashFees::LEN;
+    pub const MAX_CONFIG_DELAY: i64 = 30 * 24 * 60 * 60; // 30 days
+
     pub fn swaps<A>(&self, acc: &A) -> bool
     where
         A: Key,
@@ -35,25 +155,246 @@ impl Pool {
         acc.key() == self.token_mint_1 || acc.key() == self.token_mint_2
     }
 
+    // Prices a trade of `amount_in` against the reserves of the pool and records it in the circuit breaker
+    // and the volume limits, failing if the trade exceeds either. A trade tripping the circuit breaker
+    // goes through and stops the trades after it.
+    #[throws(ProgramError)]
+    pub fn quote_swap(
+        &mut self,
+        trade_direction: TradeDirection,
+        amount_in: u64,
+        source_reserve: u64,
+        destination_reserve: u64,
+        lp_supply: u64,
+        slot: u64,
+    ) -> SwapQuote {
+        let result = self
+            .curve
+            .swap(
+                utils::to_u128(amount_in)?,
+                utils::to_u128(source_reserve)?,
+                utils::to_u128(destination_reserve)?,
+                trade_direction,
+                &self.fees,
+            )
+            .ok_or(ZeroTradingTokens)?;
+
+        let (swap_token_a_amount, swap_token_b_amount) = match trade_direction {
+            TradeDirection::AtoB => (
+                result.new_swap_source_amount,
+                result.new_swap_destination_amount,
+            ),
+            TradeDirection::BtoA => (
+                result.new_swap_destination_amount,
+                result.new_swap_source_amount,
+            ),
+        };
+
+        let price_before = match trade_direction {
+            TradeDirection::AtoB => price(source_reserve.into(), destination_reserve.into())?,
+            TradeDirection::BtoA => price(destination_reserve.into(), source_reserve.into())?,
+        };
+        let price_after = price(swap_token_a_amount, swap_token_b_amount)?;
+        self.circuit_breaker
+            .observe(price_before, price_after, slot)?;
+
+        let amount_in = utils::to_u64(result.source_amount_swapped)?;
+        self.limits.check_amount_in(trade_direction, amount_in)?;
+        let limits = self.limits;
+        self.slot_volume
+            .record(&limits, trade_direction, amount_in, slot)?;
+
+        // trading fees
+        let owner_fee = self
+            .curve
+            .withdraw_single_token_type_exact_out(
+                result.owner_fee,
+                swap_token_a_amount,
+                swap_token_b_amount,
+                utils::to_u128(lp_supply)?,
+                trade_direction,
+                &self.fees,
+            )
+            .ok_or(FeeCalculationFailure)?;
+
+        SwapQuote {
+            amount_in,
+            amount_out: utils::to_u64(result.destination_amount_swapped)?,
+            owner_fee: utils::to_u64(owner_fee)?,
+        }
+    }
+
+    // Records a deposit or withdrawal moving the reserves of the pool from `reserves_before` to
+    // `reserves_after` in the circuit breaker and the volume limits, so that single sided and
+    // imbalanced liquidity cannot move the price around them.
+    #[throws(ProgramError)]
+    pub fn record_reserve_change(
+        &mut self,
+        reserves_before: (u64, u64),
+        reserves_after: (u64, u64),
+        slot: u64,
+    ) {
+        let limits = self.limits;
+        let moves = [
+            (
+                TradeDirection::AtoB,
+                reserves_before.0.abs_diff(reserves_after.0),
+            ),
+            (
+                TradeDirection::BtoA,
+                reserves_before.1.abs_diff(reserves_after.1),
+            ),
+        ];
+        for &(direction, amount) in moves.iter().filter(|(_, amount)| *amount > 0) {
+            limits.check_amount_in(direction, amount)?;
+            self.slot_volume.record(&limits, direction, amount, slot)?;
+        }
+
+        // an empty side has no price, like before the first deposit
+        if reserves_before.0 > 0 && reserves_before.1 > 0 && reserves_after.0 > 0 {
+            self.circuit_breaker.observe(
+                price(reserves_before.0.into(), reserves_before.1.into())?,
+                price(reserves_after.0.into(), reserves_after.1.into())?,
+                slot,
+            )?;
+        }
+    }
+
+    #[throws(ProgramError)]
     pub fn config(&mut self, config: &PoolConfig) {
         let PoolConfig {
-            admin,
             fees,
+            flash_fees,
+            curve,
             suspended,
+            swaps_suspended,
+            deposits_suspended,
+            single_sided_suspended,
+            withdrawals_suspended,
+            circuit_breaker,
+            limits,
+            config_delay,
+            access_mode,
         } = config;
 
-        if let Some(admin) = admin {
-            self.admin = *admin;
-        }
-
         if let Some(fees) = fees {
+            fees.validate()?;
             self.fees = *fees;
         }
 
+        if let Some(flash_fees) = flash_fees {
+            flash_fees.validate()?;
+            self.flash_fees = *flash_fees;
+        }
+
+        if let Some(curve) = curve {
+            // only the parameters can be changed, not the type of the curve
+            require!(curve.curve_type() == self.curve.curve_type(), InvalidCurve);
+            curve.validate()?;
+            self.curve = curve.clone();
+        }
+
         if let Some(suspended) = suspended {
-            self.suspended = *suspended;
+            self.swaps_suspended = *suspended;
+            self.deposits_suspended = *suspended;
+            self.single_sided_suspended = *suspended;
+        }
+
+        // the individual flags take precedence over `suspended`
+        if let Some(swaps_suspended) = swaps_suspended {
+            self.swaps_suspended = *swaps_suspended;
+        }
+
+        if let Some(deposits_suspended) = deposits_suspended {
+            self.deposits_suspended = *deposits_suspended;
+        }
+
+        if let Some(single_sided_suspended) = single_sided_suspended {
+            self.single_sided_suspended = *single_sided_suspended;
+        }
+
+        if let Some(withdrawals_suspended) = withdrawals_suspended {
+            self.withdrawals_suspended = *withdrawals_suspended;
+        }
+
+        // resuming the swaps also clears a tripped circuit breaker
+        if suspended == &Some(false) || swaps_suspended == &Some(false) {
+            self.circuit_breaker.reset();
+        }
+
+        if let Some(circuit_breaker) = circuit_breaker {
+            circuit_breaker.validate()?;
+            self.circuit_breaker.set_params(circuit_breaker);
+        }
+
+        if let Some(limits) = limits {
+            self.limits = *limits;
+        }
+
+        if let Some(config_delay) = config_delay {
+            self.set_config_delay(*config_delay)?;
+        }
+
+        if let Some(access_mode) = access_mode {
+            self.access_mode = *access_mode;
         }
     }
+
+    #[throws(ProgramError)]
+    pub fn set_config_delay(&mut self, config_delay: i64) {
+        require!(
+            (0..=Self::MAX_CONFIG_DELAY).contains(&config_delay),
+            InvalidConfigDelay
+        );
+        self.config_delay = config_delay;
+    }
+
+    pub fn authorizes(&self, role: PoolRole, key: &Pubkey) -> bool {
+        let role_key = match role {
+            PoolRole::FeeManager => &self.fee_manager,
+            PoolRole::PauseGuardian => &self.pause_guardian,
+            PoolRole::CurveManager => &self.curve_manager,
+        };
+
+        key == &self.admin || key == role_key
+    }
+
+    pub fn set_roles(&mut self, roles: &PoolRoles) {
+        let PoolRoles {
+            fee_manager,
+            pause_guardian,
+            curve_manager,
+        } = roles;
+
+        if let Some(fee_manager) = fee_manager {
+            self.fee_manager = *fee_manager;
+        }
+
+        if let Some(pause_guardian) = pause_guardian {
+            self.pause_guardian = *pause_guardian;
+        }
+
+        if let Some(curve_manager) = curve_manager {
+            self.curve_manager = *curve_manager;
+        }
+    }
+
+    pub fn has_pending_admin(&self) -> bool {
+        self.pending_admin != Pubkey::default()
+    }
+
+    pub fn propose_admin(&mut self, new_admin: Pubkey) {
+        self.pending_admin = new_admin;
+    }
+
+    pub fn accept_admin(&mut self) {
+        self.admin = self.pending_admin;
+        self.pending_admin = Pubkey::default();
+    }
+
+    pub fn cancel_admin_proposal(&mut self) {
+        self.pending_admin = Pubkey::default();
+    }
 }
 
 pub trait PoolExt<'info> {
@@ -68,16 +409,18 @@ pub trait PoolExt<'info> {
     fn transfer_to_pool(
         &self,
         user_authority: &AccountInfo<'info>,
-        user_ata: &Account<'info, TokenAccount>,
-        pool_ata: &Account<'info, TokenAccount>,
+        user_ata: &CpiAccount<'info, InterfaceTokenAccount>,
+        pool_ata: &CpiAccount<'info, InterfaceTokenAccount>,
+        mint: &CpiAccount<'info, InterfaceMint>,
         token_program: &AccountInfo<'info>,
         amount: u64,
     ) -> Result<(), ProgramError>;
 
     fn transfer_to_user(
         &self,
-        pool_ata: &Account<'info, TokenAccount>,
-        user_ata: &Account<'info, TokenAccount>,
+        pool_ata: &CpiAccount<'info, InterfaceTokenAccount>,
+        user_ata: &CpiAccount<'info, InterfaceTokenAccount>,
+        mint: &CpiAccount<'info, InterfaceMint>,
         token_program: &AccountInfo<'info>,
         amount: u64,
     ) -> Result<(), ProgramError>;
@@ -93,7 +436,7 @@ pub trait PoolExt<'info> {
 
     fn transfer_lp_from_fee_vault(
         &self,
-        admin_ata: &Account<'info, TokenAccount>,
+        recipient_ata: &Account<'info, TokenAccount>,
         fee_vault: &Account<'info, TokenAccount>,
         token_program: &AccountInfo<'info>,
         amount: u64,
@@ -144,6 +487,7 @@ impl<'info> PoolExt<'info> for &'_ mut Account<'info, Pool> {
         amount: u64,
     ) {
         require!(mint.key() == self.mint, WrongLPMint);
+        utils::check_user_authority(user_ata, user_authority.key, amount)?;
 
         token::burn(
             CpiContext::new(
@@ -167,6 +511,8 @@ impl<'info> PoolExt<'info> for &'_ mut Account<'info, Pool> {
         token_program: &AccountInfo<'info>,
         amount: u64,
     ) {
+        utils::check_user_authority(user_ata, user_authority.key, amount)?;
+
         token::transfer(
             CpiContext::new(
                 token_program.clone(),
@@ -180,46 +526,47 @@ impl<'info> PoolExt<'info> for &'_ mut Account<'info, Pool> {
         )?;
     }
 
+    // The pool receives `mint.amount_received(amount)` of a mint with a transfer fee
     #[throws(ProgramError)]
     fn transfer_to_pool(
         &self,
         user_authority: &AccountInfo<'info>,
-        user_ata: &Account<'info, TokenAccount>,
-        pool_ata: &Account<'info, TokenAccount>,
+        user_ata: &CpiAccount<'info, InterfaceTokenAccount>,
+        pool_ata: &CpiAccount<'info, InterfaceTokenAccount>,
+        mint: &CpiAccount<'info, InterfaceMint>,
         token_program: &AccountInfo<'info>,
         amount: u64,
     ) {
-        token::transfer(
-            CpiContext::new(
-                token_program.to_account_info(),
-                token::Transfer {
-                    from: user_ata.to_account_info(),
-                    to: pool_ata.to_account_info(),
-                    authority: user_authority.to_account_info(),
-                },
-            ),
+        utils::check_user_authority(user_ata, user_authority.key, amount)?;
+
+        token_interface::transfer_checked(
+            user_ata,
+            pool_ata,
+            mint,
+            user_authority,
+            token_program,
+            &[],
             amount,
         )?;
     }
 
+    // The user receives `mint.amount_received(amount)` of a mint with a transfer fee
     #[throws(ProgramError)]
     fn transfer_to_user(
         &self,
-        pool_ata: &Account<'info, TokenAccount>,
-        user_ata: &Account<'info, TokenAccount>,
+        pool_ata: &CpiAccount<'info, InterfaceTokenAccount>,
+        user_ata: &CpiAccount<'info, InterfaceTokenAccount>,
+        mint: &CpiAccount<'info, InterfaceMint>,
         token_program: &AccountInfo<'info>,
         amount: u64,
     ) {
-        token::transfer(
-            CpiContext::new_with_signer(
-                token_program.to_account_info(),
-                token::Transfer {
-                    from: pool_ata.to_account_info(),
-                    to: user_ata.to_account_info(),
-                    authority: self.to_account_info(),
-                },
-                &[&[Pool::IDENT, &self.seed, &[self.bump]]],
-            ),
+        token_interface::transfer_checked(
+            pool_ata,
+            user_ata,
+            mint,
+            &self.to_account_info(),
+            token_program,
+            &[&[Pool::IDENT, &self.seed, &[self.bump]]],
             amount,
         )?;
     }
@@ -227,7 +574,7 @@ impl<'info> PoolExt<'info> for &'_ mut Account<'info, Pool> {
     #[throws(ProgramError)]
     fn transfer_lp_from_fee_vault(
         &self,
-        admin_ata: &Account<'info, TokenAccount>,
+        recipient_ata: &Account<'info, TokenAccount>,
         fee_vault: &Account<'info, TokenAccount>,
         token_program: &AccountInfo<'info>,
         amount: u64,
@@ -237,7 +584,7 @@ impl<'info> PoolExt<'info> for &'_ mut Account<'info, Pool> {
                 token_program.to_account_info(),
                 token::Transfer {
                     from: fee_vault.to_account_info(),
-                    to: admin_ata.to_account_info(),
+                    to: recipient_ata.to_account_info(),
                     authority: self.to_account_info(),
                 },
                 &[&[Pool::IDENT, &self.seed, &[self.bump]]],
@@ -247,9 +594,141 @@ impl<'info> PoolExt<'info> for &'_ mut Account<'info, Pool> {
     }
 }
 
-#[derive(AnchorDeserialize, AnchorSerialize, Debug)]
+// The admin is not configurable here. Use `propose_admin` and `accept_admin` to hand it over.
+// Except for `suspended`, the changes have to wait for the `config_delay` of the pool,
+// see `queue_config` and `execute_config`.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, Default)]
 pub struct PoolConfig {
-    pub admin: Option<Pubkey>,
     pub fees: Option<Fees>,
+    pub flash_fees: Option<FlashFees>,
+    pub curve: Option<SwapCurve>,
+    // The default pause: suspends swaps, deposits and the single sided operations,
+    // but keeps the proportional withdrawal (`withdraw2`) open so LPs can always exit.
     pub suspended: Option<bool>,
+    pub swaps_suspended: Option<bool>,
+    pub deposits_suspended: Option<bool>,
+    pub single_sided_suspended: Option<bool>,
+    pub withdrawals_suspended: Option<bool>,
+    pub circuit_breaker: Option<CircuitBreakerParams>,
+    pub limits: Option<PoolLimits>,
+    pub config_delay: Option<i64>, // only the admin can change the delay
+    pub access_mode: Option<AccessMode>, // only the admin can change the access mode
+}
+
+impl PoolConfig {
+    // The serialized size with all the fields set and the largest curve
+    pub const MAX_LEN: usize = (1 + Fees::LEN)
+        + (1 + FlashFees::LEN)
+        + (1 + SwapCurve::MAX_LEN)
+        + 5 * (1 + 1)
+        + (1 + CircuitBreakerParams::LEN)
+        + (1 + PoolLimits::LEN)
+        + (1 + 8)
+        + (1 + 1);
+
+    // A config without any field needs no role, so it must not occupy the queue of the pool
+    pub fn is_empty(&self) -> bool {
+        self.is_instant() && !self.touches_pause_flags()
+    }
+
+    // Pausing and resuming are applied instantly, everything else is timelocked.
+    pub fn is_instant(&self) -> bool {
+        self.fees.is_none()
+            && self.flash_fees.is_none()
+            && self.curve.is_none()
+            && self.circuit_breaker.is_none()
+            && self.limits.is_none()
+            && self.config_delay.is_none()
+            && self.access_mode.is_none()
+    }
+
+    // The roles which are allowed to apply this config
+    pub fn required_roles(&self) -> Vec<PoolRole> {
+        let mut roles = vec![];
+
+        if self.fees.is_some()
+            || self.flash_fees.is_some()
+            || self.curve.is_some()
+            || self.limits.is_some()
+        {
+            roles.push(PoolRole::CurveManager);
+        }
+
+        if self.touches_pause_flags() || self.circuit_breaker.is_some() {
+            roles.push(PoolRole::PauseGuardian);
+        }
+
+        roles
+    }
+
+    pub fn touches_pause_flags(&self) -> bool {
+        self.suspended.is_some()
+            || self.swaps_suspended.is_some()
+            || self.deposits_suspended.is_some()
+            || self.single_sided_suspended.is_some()
+            || self.withdrawals_suspended.is_some()
+    }
+}
+
+// The privileged operations besides the admin, which can be delegated to other keys.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, PartialEq)]
+pub enum PoolRole {
+    FeeManager,    // withdraws the owner fees
+    PauseGuardian, // suspends and resumes the operations of the pool
+    CurveManager,  // changes the fees, the curve parameters and the limits
+}
+
+// Only the admin can reassign the roles. Pubkey::default() revokes a role.
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Default)]
+pub struct PoolRoles {
+    pub fee_manager: Option<Pubkey>,
+    pub pause_guardian: Option<Pubkey>,
+    pub curve_manager: Option<Pubkey>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::StableCurve;
+
+    #[test]
+    fn legacy_layout_is_a_prefix() {
+        for curve in [
+            SwapCurve::default(),
+            SwapCurve::StableCurve(StableCurve { amp: 100 }),
+        ] {
+            let legacy = LegacyPool {
+                seed: [7; 32],
+                bump: 254,
+                admin: Pubkey::new_unique(),
+                mint: Pubkey::new_unique(),
+                fees: Fees {
+                    trade_fee_numerator: 1,
+                    trade_fee_denominator: 1000,
+                    ..Default::default()
+                },
+                curve,
+                suspended: true,
+                ..Default::default()
+            };
+            let legacy_data = legacy.try_to_vec().unwrap();
+
+            let pool = Pool::from(legacy);
+            assert!(pool.swaps_suspended && pool.deposits_suspended);
+            assert!(pool.single_sided_suspended && pool.withdrawals_suspended);
+            assert_eq!(pool.pending_admin, Pubkey::default());
+
+            let data = pool.try_to_vec().unwrap();
+            assert_eq!(data[..legacy_data.len()], legacy_data[..]);
+        }
+    }
+
+    #[test]
+    fn len_fits_the_largest_pool() {
+        let pool = Pool {
+            curve: SwapCurve::StableCurve(StableCurve { amp: 100 }),
+            ..Default::default()
+        };
+        assert_eq!(pool.try_to_vec().unwrap().len(), Pool::LEN - 8);
+    }
 }
@@ -0,0 +1,27 @@
+use super::{DerivedAccountIdentifier, PoolConfig};
+use anchor_lang::prelude::*;
+
+impl DerivedAccountIdentifier for QueuedConfig {
+    const IDENT: &'static [u8] = b"GFXQueuedConfig";
+}
+
+// A config change waiting for the `config_delay` of the pool.
+// It is derived from the pool address, so a pool has at most one queued config at a time.
+#[account]
+#[derive(Default, Debug)]
+pub struct QueuedConfig {
+    pub pool: Pubkey,
+    pub bump: u8,
+    pub payer: Pubkey, // gets the rent back once the config is executed or cancelled
+    pub eta: i64,      // unix timestamp after which the config can be executed
+    pub config: PoolConfig,
+}
+
+impl QueuedConfig {
+    // discriminator + pool + bump + payer + eta + config
+    pub const LEN: usize = 8 + 32 + 1 + 32 + 8 + PoolConfig::MAX_LEN;
+
+    pub fn is_ready(&self, now: i64) -> bool {
+        now >= self.eta
+    }
+}
@@ -0,0 +1,84 @@
+use super::DerivedAccountIdentifier;
+use crate::curve::CurveType;
+use crate::errors::ErrorCode::*;
+use anchor_lang::prelude::*;
+use fehler::throws;
+
+// How many pools a single registry page can hold.
+pub const REGISTRY_PAGE_CAPACITY: usize = 32;
+
+impl DerivedAccountIdentifier for PoolRegistry {
+    const IDENT: &'static [u8] = b"GFXPoolRegistry";
+}
+
+// The registry header. There is only one of it, derived from the IDENT without any seed.
+#[account]
+#[derive(Default, Debug)]
+pub struct PoolRegistry {
+    pub bump: u8,
+    pub page_count: u32,
+}
+
+impl DerivedAccountIdentifier for PoolRegistryPage {
+    const IDENT: &'static [u8] = b"GFXPoolRegistryPage";
+}
+
+// Pages are derived from the little endian bytes of the page index.
+#[account]
+#[derive(Default, Debug)]
+pub struct PoolRegistryPage {
+    pub page: u32,
+    pub bump: u8,
+    pub entries: Vec<RegistryEntry>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct RegistryEntry {
+    pub pool: Pubkey,
+    // sorted by token mint addresses
+    pub token_mint_1: Pubkey,
+    pub token_mint_2: Pubkey,
+    pub curve_type: CurveType,
+    // the fee tier of the pool, i.e. the trade fee fraction
+    pub trade_fee_numerator: u64,
+    pub trade_fee_denominator: u64,
+}
+
+impl RegistryEntry {
+    pub const LEN: usize = 32 + 32 + 32 + 1 + 8 + 8;
+}
+
+impl PoolRegistryPage {
+    // discriminator + page + bump + vec length + entries
+    pub const LEN: usize = 8 + 4 + 1 + 4 + REGISTRY_PAGE_CAPACITY * RegistryEntry::LEN;
+
+    pub fn get_page_address(program_id: &Pubkey, page: u32) -> Pubkey {
+        Self::get_address(program_id, &page.to_le_bytes())
+    }
+
+    // Addresses of all the pages, for clients to iterate through the registry.
+    pub fn page_addresses(program_id: &Pubkey, page_count: u32) -> Vec<Pubkey> {
+        (0..page_count)
+            .map(|page| Self::get_page_address(program_id, page))
+            .collect()
+    }
+
+    #[throws(ProgramError)]
+    pub fn register(&mut self, entry: RegistryEntry) {
+        require!(
+            self.entries.len() < REGISTRY_PAGE_CAPACITY,
+            RegistryPageFull
+        );
+        self.entries.push(entry);
+    }
+
+    #[throws(ProgramError)]
+    pub fn delist(&mut self, pool: &Pubkey) {
+        let idx = self
+            .entries
+            .iter()
+            .position(|entry| &entry.pool == pool)
+            .ok_or(PoolNotRegistered)?;
+        self.entries.swap_remove(idx);
+    }
+}
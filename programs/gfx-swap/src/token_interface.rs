@@ -0,0 +1,553 @@
+// # Token Programs
+//
+// The pool tokens may belong to the legacy Token program or to Token-2022, each mint brings its own
+// program. Anchor 0.18 only knows the legacy one: `Account<TokenAccount>` rejects any other owner and
+// the anchor-spl CPI helpers build their instructions for it only. The pool tokens go through this
+// module instead:
+//
+// * the accounts are read as `CpiAccount<InterfaceTokenAccount>` and `CpiAccount<InterfaceMint>`,
+//   which skip the owner check, so `transfer_checked` verifies that the mint, both token accounts and
+//   the program passed along are of the same supported token program before moving anything.
+// * a Token-2022 mint may withhold a transfer fee from every transfer. The amount that arrives is
+//   `amount_received(amount)` and the amount to send for a given arrival is `amount_to_send(amount)`,
+//   the curve is always run on what the vault actually receives or pays.
+// * `check_extensions` rejects the mint extensions a pool can not work with at `create_pool`.
+//
+// The LP mint, the farms and the locks stay on the legacy Token program.
+
+use crate::errors::ErrorCode::*;
+use anchor_lang::prelude::*;
+use fehler::{throw, throws};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+    program_pack::Pack,
+};
+use spl_token::instruction::TokenInstruction;
+use std::convert::TryInto;
+use std::ops::Deref;
+
+pub mod token_2022 {
+    use anchor_lang::prelude::*;
+    declare_id!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+}
+
+// Anchor 0.18 recognizes the account types of a context by their name, so the alias keeps it.
+#[allow(deprecated)]
+pub type CpiAccount<'info, T> = anchor_lang::CpiAccount<'info, T>;
+
+// The byte after the base account which tells a Token-2022 mint from a token account
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+// The Token-2022 extensions of a mint, by their type in the TLV data
+const EXTENSION_TRANSFER_FEE_CONFIG: u16 = 1;
+const EXTENSION_INTEREST_BEARING_CONFIG: u16 = 10;
+const EXTENSION_METADATA_POINTER: u16 = 18;
+const EXTENSION_TOKEN_METADATA: u16 = 19;
+const EXTENSION_GROUP_POINTER: u16 = 20;
+const EXTENSION_TOKEN_GROUP: u16 = 21;
+const EXTENSION_GROUP_MEMBER_POINTER: u16 = 22;
+const EXTENSION_TOKEN_GROUP_MEMBER: u16 = 23;
+
+// Everything else (non-transferable, permanent delegate, transfer hook, default frozen accounts,
+// mint close authority, confidential transfers, ...) could freeze, drain or block the vaults.
+const SUPPORTED_MINT_EXTENSIONS: [u16; 8] = [
+    EXTENSION_TRANSFER_FEE_CONFIG,
+    EXTENSION_INTEREST_BEARING_CONFIG, // only changes the displayed amount
+    EXTENSION_METADATA_POINTER,
+    EXTENSION_TOKEN_METADATA,
+    EXTENSION_GROUP_POINTER,
+    EXTENSION_TOKEN_GROUP,
+    EXTENSION_GROUP_MEMBER_POINTER,
+    EXTENSION_TOKEN_GROUP_MEMBER,
+];
+
+// The instructions of Token-2022 which do not exist in the legacy program
+const TRANSFER_FEE_EXTENSION: u8 = 26;
+const HARVEST_WITHHELD_TOKENS_TO_MINT: u8 = 4;
+
+const MAX_FEE_BASIS_POINTS: u128 = 10000;
+
+pub fn is_token_program(program: &Pubkey) -> bool {
+    program == &spl_token::ID || program == &token_2022::ID
+}
+
+// The associated token account of `wallet` for a mint of `token_program`
+pub fn associated_token_address(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &spl_associated_token_account::ID,
+    )
+    .0
+}
+
+// A token account of either token program, the extensions of Token-2022 are ignored.
+#[derive(Clone, Debug)]
+pub struct InterfaceTokenAccount(spl_token::state::Account);
+
+impl AccountDeserialize for InterfaceTokenAccount {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self, ProgramError> {
+        Self::try_deserialize_unchecked(buf)
+    }
+
+    #[throws(ProgramError)]
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Self {
+        let len = spl_token::state::Account::LEN;
+        // a multisig is as long as a token account with extensions but has no account type
+        require!(
+            buf.len() == len
+                || (buf.len() > len
+                    && buf.len() != spl_token::state::Multisig::LEN
+                    && buf[len] == ACCOUNT_TYPE_ACCOUNT),
+            ProgramError::InvalidAccountData
+        );
+        InterfaceTokenAccount(spl_token::state::Account::unpack(&buf[..len])?)
+    }
+}
+
+impl Deref for InterfaceTokenAccount {
+    type Target = spl_token::state::Account;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+// The fee Token-2022 withholds from the transfers of a mint during an epoch
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TransferFee {
+    pub epoch: u64,
+    pub maximum_fee: u64,
+    pub basis_points: u16,
+}
+
+impl TransferFee {
+    const LEN: usize = 8 + 8 + 2;
+
+    fn unpack(data: &[u8]) -> Self {
+        TransferFee {
+            epoch: u64::from_le_bytes(data[0..8].try_into().unwrap()),
+            maximum_fee: u64::from_le_bytes(data[8..16].try_into().unwrap()),
+            basis_points: u16::from_le_bytes(data[16..18].try_into().unwrap()),
+        }
+    }
+
+    // The fee withheld from a transfer of `amount`, rounded up like Token-2022 does
+    pub fn fee(&self, amount: u64) -> Option<u64> {
+        if self.basis_points == 0 || amount == 0 {
+            return Some(0);
+        }
+        let fee = (amount as u128)
+            .checked_mul(self.basis_points.into())?
+            .checked_add(MAX_FEE_BASIS_POINTS - 1)?
+            / MAX_FEE_BASIS_POINTS;
+        Some(std::cmp::min(fee.try_into().ok()?, self.maximum_fee))
+    }
+
+    // The smallest amount to transfer for `received` to arrive after the fee
+    pub fn pre_fee_amount(&self, received: u64) -> Option<u64> {
+        let basis_points = u128::from(self.basis_points);
+        if basis_points == 0 || received == 0 {
+            return Some(received);
+        }
+        if basis_points == MAX_FEE_BASIS_POINTS {
+            return received.checked_add(self.maximum_fee);
+        }
+
+        let received_u128 = u128::from(received);
+        let amount = received_u128
+            .checked_mul(MAX_FEE_BASIS_POINTS)?
+            .checked_add(MAX_FEE_BASIS_POINTS - basis_points - 1)?
+            / (MAX_FEE_BASIS_POINTS - basis_points);
+        if amount - received_u128 >= self.maximum_fee.into() {
+            received.checked_add(self.maximum_fee)
+        } else {
+            amount.try_into().ok()
+        }
+    }
+}
+
+// A mint of either token program with the Token-2022 extensions the pool has to know about
+#[derive(Clone, Debug)]
+pub struct InterfaceMint {
+    mint: spl_token::state::Mint,
+    extensions: Vec<u16>,
+    // the older and the newer fee of the `TransferFeeConfig` extension
+    transfer_fees: Option<(TransferFee, TransferFee)>,
+}
+
+impl AccountDeserialize for InterfaceMint {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self, ProgramError> {
+        Self::try_deserialize_unchecked(buf)
+    }
+
+    #[throws(ProgramError)]
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Self {
+        let len = spl_token::state::Mint::LEN;
+        // the extensions of a mint start after the same padding as the ones of a token account
+        let account_type = spl_token::state::Account::LEN;
+        require!(
+            buf.len() == len
+                || (buf.len() > account_type && buf[account_type] == ACCOUNT_TYPE_MINT),
+            ProgramError::InvalidAccountData
+        );
+        let mint = spl_token::state::Mint::unpack(&buf[..len])?;
+
+        let mut extensions = vec![];
+        let mut transfer_fees = None;
+        let mut tlv = buf.get(account_type + 1..).unwrap_or(&[]);
+        while tlv.len() >= 4 {
+            let extension = u16::from_le_bytes([tlv[0], tlv[1]]);
+            let length = u16::from_le_bytes([tlv[2], tlv[3]]) as usize;
+            if extension == 0 {
+                break; // the rest is unused space
+            }
+            let value = tlv
+                .get(4..4 + length)
+                .ok_or(ProgramError::InvalidAccountData)?;
+
+            if extension == EXTENSION_TRANSFER_FEE_CONFIG {
+                // the authorities and the withheld amount come before the two fees
+                let fees = value
+                    .get(32 + 32 + 8..)
+                    .filter(|fees| fees.len() == 2 * TransferFee::LEN)
+                    .ok_or(ProgramError::InvalidAccountData)?;
+                transfer_fees = Some((
+                    TransferFee::unpack(&fees[..TransferFee::LEN]),
+                    TransferFee::unpack(&fees[TransferFee::LEN..]),
+                ));
+            }
+            extensions.push(extension);
+            tlv = &tlv[4 + length..];
+        }
+
+        InterfaceMint {
+            mint,
+            extensions,
+            transfer_fees,
+        }
+    }
+}
+
+impl Deref for InterfaceMint {
+    type Target = spl_token::state::Mint;
+
+    fn deref(&self) -> &Self::Target {
+        &self.mint
+    }
+}
+
+impl InterfaceMint {
+    #[throws(ProgramError)]
+    pub fn check_extensions(&self) {
+        for extension in &self.extensions {
+            require!(
+                SUPPORTED_MINT_EXTENSIONS.contains(extension),
+                UnsupportedMintExtension
+            );
+        }
+    }
+
+    pub fn has_transfer_fee(&self) -> bool {
+        self.transfer_fees.is_some()
+    }
+
+    // The fee which applies in `epoch`, none for a mint without the extension
+    pub fn transfer_fee(&self, epoch: u64) -> TransferFee {
+        match self.transfer_fees {
+            Some((_, newer)) if epoch >= newer.epoch => newer,
+            Some((older, _)) => older,
+            None => TransferFee::default(),
+        }
+    }
+
+    // What arrives of a transfer of `amount` in the current epoch
+    #[throws(ProgramError)]
+    pub fn amount_received(&self, amount: u64) -> u64 {
+        if !self.has_transfer_fee() {
+            return amount;
+        }
+        let fee = self
+            .transfer_fee(Clock::get()?.epoch)
+            .fee(amount)
+            .ok_or(CalculationFailure)?;
+        amount - fee // the fee is never above the amount
+    }
+
+    // What to transfer in the current epoch for `received` to arrive
+    #[throws(ProgramError)]
+    pub fn amount_to_send(&self, received: u64) -> u64 {
+        if !self.has_transfer_fee() {
+            return received;
+        }
+        self.transfer_fee(Clock::get()?.epoch)
+            .pre_fee_amount(received)
+            .ok_or(CalculationFailure)?
+    }
+}
+
+// The token program of the mint, which has to own both token accounts as well
+#[throws(ProgramError)]
+fn check_token_program(
+    token_program: &AccountInfo,
+    mint: &AccountInfo,
+    token_accounts: &[&AccountInfo],
+) {
+    require!(
+        is_token_program(token_program.key)
+            && mint.owner == token_program.key
+            && token_accounts
+                .iter()
+                .all(|account| account.owner == token_program.key),
+        WrongTokenProgram
+    );
+}
+
+// Moves `amount` from `from` to `to` with the token program of the mint. The transfer fee of a
+// Token-2022 mint is withheld in `to`, see `InterfaceMint::amount_received`.
+#[throws(ProgramError)]
+pub fn transfer_checked<'info>(
+    from: &CpiAccount<'info, InterfaceTokenAccount>,
+    to: &CpiAccount<'info, InterfaceTokenAccount>,
+    mint: &CpiAccount<'info, InterfaceMint>,
+    authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) {
+    let decimals = mint.decimals;
+    let (from, to, mint) = (
+        from.to_account_info(),
+        to.to_account_info(),
+        mint.to_account_info(),
+    );
+    check_token_program(token_program, &mint, &[&from, &to])?;
+
+    let instruction = Instruction {
+        program_id: *token_program.key,
+        accounts: vec![
+            AccountMeta::new(*from.key, false),
+            AccountMeta::new_readonly(*mint.key, false),
+            AccountMeta::new(*to.key, false),
+            AccountMeta::new_readonly(*authority.key, true),
+        ],
+        data: TokenInstruction::TransferChecked { amount, decimals }.pack(),
+    };
+    invoke_signed(
+        &instruction,
+        &[from, mint, to, authority.clone(), token_program.clone()],
+        signer_seeds,
+    )?;
+}
+
+// Closes an empty token account. The transfer fees withheld in it are harvested into the mint first,
+// Token-2022 does not close an account which still withholds some.
+#[throws(ProgramError)]
+pub fn close_account<'info>(
+    account: &CpiAccount<'info, InterfaceTokenAccount>,
+    mint: &CpiAccount<'info, InterfaceMint>,
+    destination: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) {
+    let (account, mint_info) = (account.to_account_info(), mint.to_account_info());
+    check_token_program(token_program, &mint_info, &[&account])?;
+
+    if mint.has_transfer_fee() {
+        let harvest = Instruction {
+            program_id: *token_program.key,
+            accounts: vec![
+                AccountMeta::new(*mint_info.key, false),
+                AccountMeta::new(*account.key, false),
+            ],
+            data: vec![TRANSFER_FEE_EXTENSION, HARVEST_WITHHELD_TOKENS_TO_MINT],
+        };
+        invoke_signed(
+            &harvest,
+            &[mint_info, account.clone(), token_program.clone()],
+            &[],
+        )?;
+    }
+
+    let close = Instruction {
+        program_id: *token_program.key,
+        accounts: vec![
+            AccountMeta::new(*account.key, false),
+            AccountMeta::new(*destination.key, false),
+            AccountMeta::new_readonly(*authority.key, true),
+        ],
+        data: TokenInstruction::CloseAccount.pack(),
+    };
+    invoke_signed(
+        &close,
+        &[
+            account,
+            destination.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+}
+
+// Creates the associated token account of `wallet` for a mint of either token program. The associated
+// token program derives the address with the token program, see `associated_token_address`.
+#[throws(ProgramError)]
+pub fn create_associated_token_account<'info>(
+    payer: &AccountInfo<'info>,
+    associated_token: &AccountInfo<'info>,
+    wallet: &AccountInfo<'info>,
+    mint: &CpiAccount<'info, InterfaceMint>,
+    system_program: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    associated_token_program: &AccountInfo<'info>,
+) {
+    let mint = mint.to_account_info();
+    check_token_program(token_program, &mint, &[])?;
+    if associated_token_program.key != &spl_associated_token_account::ID {
+        throw!(ProgramError::IncorrectProgramId);
+    }
+
+    let instruction = Instruction {
+        program_id: spl_associated_token_account::ID,
+        accounts: vec![
+            AccountMeta::new(*payer.key, true),
+            AccountMeta::new(*associated_token.key, false),
+            AccountMeta::new_readonly(*wallet.key, false),
+            AccountMeta::new_readonly(*mint.key, false),
+            AccountMeta::new_readonly(*system_program.key, false),
+            AccountMeta::new_readonly(*token_program.key, false),
+        ],
+        data: vec![],
+    };
+    solana_program::program::invoke(
+        &instruction,
+        &[
+            payer.clone(),
+            associated_token.clone(),
+            wallet.clone(),
+            mint,
+            system_program.clone(),
+            token_program.clone(),
+            associated_token_program.clone(),
+        ],
+    )?;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mint_data(extensions: &[(u16, Vec<u8>)]) -> Vec<u8> {
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        if extensions.is_empty() {
+            return data;
+        }
+
+        data.resize(spl_token::state::Account::LEN, 0);
+        data.push(ACCOUNT_TYPE_MINT);
+        for (extension, value) in extensions {
+            data.extend_from_slice(&extension.to_le_bytes());
+            data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+            data.extend_from_slice(value);
+        }
+        data
+    }
+
+    fn transfer_fee_config(older: TransferFee, newer: TransferFee) -> Vec<u8> {
+        let mut value = vec![0; 32 + 32 + 8];
+        for fee in [older, newer] {
+            value.extend_from_slice(&fee.epoch.to_le_bytes());
+            value.extend_from_slice(&fee.maximum_fee.to_le_bytes());
+            value.extend_from_slice(&fee.basis_points.to_le_bytes());
+        }
+        value
+    }
+
+    #[test]
+    fn legacy_mint_has_no_fee() {
+        let mint = InterfaceMint::try_deserialize(&mut &mint_data(&[])[..]).unwrap();
+        assert_eq!(mint.decimals, 6);
+        assert!(!mint.has_transfer_fee());
+        assert_eq!(mint.transfer_fee(0).fee(1000), Some(0));
+        mint.check_extensions().unwrap();
+    }
+
+    #[test]
+    fn newer_fee_applies_from_its_epoch() {
+        let older = TransferFee {
+            epoch: 0,
+            maximum_fee: 100,
+            basis_points: 50,
+        };
+        let newer = TransferFee {
+            epoch: 10,
+            maximum_fee: 100,
+            basis_points: 200,
+        };
+        let data = mint_data(&[
+            (
+                EXTENSION_TRANSFER_FEE_CONFIG,
+                transfer_fee_config(older, newer),
+            ),
+            (EXTENSION_METADATA_POINTER, vec![0; 64]),
+        ]);
+        let mint = InterfaceMint::try_deserialize(&mut &data[..]).unwrap();
+        assert!(mint.has_transfer_fee());
+        assert_eq!(mint.transfer_fee(9), older);
+        assert_eq!(mint.transfer_fee(10), newer);
+        mint.check_extensions().unwrap();
+    }
+
+    #[test]
+    fn unsupported_extensions_are_rejected() {
+        const PERMANENT_DELEGATE: u16 = 12;
+        const NON_TRANSFERABLE: u16 = 9;
+        for extension in [PERMANENT_DELEGATE, NON_TRANSFERABLE] {
+            let data = mint_data(&[(extension, vec![0; 32])]);
+            let mint = InterfaceMint::try_deserialize(&mut &data[..]).unwrap();
+            assert!(mint.check_extensions().is_err());
+        }
+    }
+
+    #[test]
+    fn fee_is_rounded_up_and_capped() {
+        let fee = TransferFee {
+            epoch: 0,
+            maximum_fee: 5,
+            basis_points: 100,
+        };
+        assert_eq!(fee.fee(0), Some(0));
+        assert_eq!(fee.fee(1), Some(1));
+        assert_eq!(fee.fee(100), Some(1));
+        assert_eq!(fee.fee(101), Some(2));
+        assert_eq!(fee.fee(10000), Some(5));
+    }
+
+    #[test]
+    fn pre_fee_amount_receives_at_least_the_amount() {
+        for (maximum_fee, basis_points) in [(u64::MAX, 1), (u64::MAX, 250), (40, 250), (7, 10000)] {
+            let fee = TransferFee {
+                epoch: 0,
+                maximum_fee,
+                basis_points,
+            };
+            for received in [0, 1, 99, 1000, 123_456_789] {
+                let amount = fee.pre_fee_amount(received).unwrap();
+                assert!(amount - fee.fee(amount).unwrap() >= received);
+                if amount > 0 {
+                    let less = amount - 1;
+                    assert!(less - fee.fee(less).unwrap() < received);
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::errors::ErrorCode::{self, *};
 use anchor_lang::prelude::*;
 use fehler::{throw, throws};
+use solana_program::program_option::COption;
 use std::cmp::Ordering;
 use std::convert::TryInto;
 
@@ -12,6 +13,25 @@ pub fn to_u64(val: u128) -> Result<u64, ErrorCode> {
     val.try_into().map_err(|_| ConversionFailure)
 }
 
+// The user authority may move `amount` out of the token account either as its owner
+// or as an SPL delegate approved for at least `amount`.
+#[throws(ErrorCode)]
+pub fn check_user_authority(
+    user_ata: &spl_token::state::Account,
+    user_authority: &Pubkey,
+    amount: u64,
+) {
+    if &user_ata.owner == user_authority {
+        return;
+    }
+
+    require!(
+        user_ata.delegate == COption::Some(*user_authority),
+        WrongATAOwner
+    );
+    require!(user_ata.delegated_amount >= amount, InsufficientDelegation);
+}
+
 pub trait PubkeyPairExt: Sized {
     fn sort<T>(&self, v1: T, v2: T) -> Result<(T, T), ErrorCode>;
     fn sort_self(self) -> Result<Self, ErrorCode>;
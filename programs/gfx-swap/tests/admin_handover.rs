@@ -0,0 +1,87 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, LPMint, Pool};
+use serial_test::serial;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signature::Signer};
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn admin_handover() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+
+    let client = Client::new(Cluster::Devnet, admin.clone());
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        false,
+    )?;
+
+    let new_admin = Keypair::new();
+    let stranger = Keypair::new();
+
+    // only the admin can propose
+    assert!(utils::propose_admin_impl(&program, pool, &stranger, new_admin.pubkey()).is_err());
+
+    // nothing to accept or cancel before a proposal
+    assert!(utils::accept_admin_impl(&program, pool, &new_admin).is_err());
+    assert!(utils::cancel_admin_proposal_impl(&program, pool, &admin).is_err());
+
+    utils::propose_admin_impl(&program, pool, &admin, new_admin.pubkey())?;
+    let pool_account: Pool = program.account(pool)?;
+    assert_eq!(pool_account.admin, admin.pubkey());
+    assert_eq!(pool_account.pending_admin, new_admin.pubkey());
+
+    // only the pending admin can accept
+    assert!(utils::accept_admin_impl(&program, pool, &stranger).is_err());
+
+    // only the admin can cancel
+    assert!(utils::cancel_admin_proposal_impl(&program, pool, &new_admin).is_err());
+
+    utils::cancel_admin_proposal_impl(&program, pool, &admin)?;
+    let pool_account: Pool = program.account(pool)?;
+    assert_eq!(pool_account.admin, admin.pubkey());
+    assert_eq!(pool_account.pending_admin, Pubkey::default());
+
+    // a cancelled proposal cannot be accepted
+    assert!(utils::accept_admin_impl(&program, pool, &new_admin).is_err());
+
+    utils::propose_admin_impl(&program, pool, &admin, new_admin.pubkey())?;
+    utils::accept_admin_impl(&program, pool, &new_admin)?;
+    let pool_account: Pool = program.account(pool)?;
+    assert_eq!(pool_account.admin, new_admin.pubkey());
+    assert_eq!(pool_account.pending_admin, Pubkey::default());
+
+    // the previous admin lost the privilege
+    assert!(utils::propose_admin_impl(&program, pool, &admin, admin.pubkey()).is_err());
+
+    // and the new admin can hand it back
+    utils::propose_admin_impl(&program, pool, &new_admin, admin.pubkey())?;
+    utils::accept_admin_impl(&program, pool, &admin)?;
+    let pool_account: Pool = program.account(pool)?;
+    assert_eq!(pool_account.admin, admin.pubkey());
+}
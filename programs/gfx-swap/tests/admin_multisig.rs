@@ -0,0 +1,137 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, Duplicate};
+use gfx_swap::{AdminMultisig, DerivedAccountIdentifier, LPMint, Pool, PoolConfig};
+use serial_test::serial;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signature::Signer};
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn admin_multisig() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+
+    let client = Client::new(Cluster::Devnet, admin.clone());
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        false,
+    )?;
+
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    let carol = Keypair::new();
+    let stranger = Keypair::new();
+
+    // invalid signer sets are rejected
+    assert!(utils::create_admin_multisig_impl(&program, &admin, vec![], 1).is_err());
+    assert!(utils::create_admin_multisig_impl(
+        &program,
+        &admin,
+        vec![alice.pubkey(), bob.pubkey()],
+        3
+    )
+    .is_err());
+    assert!(utils::create_admin_multisig_impl(
+        &program,
+        &admin,
+        vec![alice.pubkey(), alice.pubkey()],
+        2
+    )
+    .is_err());
+
+    // 2 of 3
+    let multisig = utils::create_admin_multisig_impl(
+        &program,
+        &admin,
+        vec![alice.pubkey(), bob.pubkey(), carol.pubkey()],
+        2,
+    )?;
+    let multisig_account: AdminMultisig = program.account(multisig)?;
+    assert_eq!(multisig_account.threshold, 2);
+    assert_eq!(multisig_account.signers.len(), 3);
+
+    // hand the pool over to the multisig
+    utils::propose_admin_impl(&program, pool, &admin, multisig)?;
+    assert!(utils::accept_admin_multisig_impl(&program, pool, multisig, &[&alice]).is_err());
+    assert!(
+        utils::accept_admin_multisig_impl(&program, pool, multisig, &[&alice, &stranger]).is_err()
+    );
+    utils::accept_admin_multisig_impl(&program, pool, multisig, &[&alice, &bob])?;
+    let pool_account: Pool = program.account(pool)?;
+    assert_eq!(pool_account.admin, multisig);
+    assert_eq!(pool_account.pending_admin, Pubkey::default());
+
+    // the previous admin key lost the privilege
+    let pause = || PoolConfig {
+        suspended: Some(true),
+        ..Default::default()
+    };
+    assert!(utils::config_pool_impl(&program, pool, &admin, pause()).is_err());
+
+    // a single signer is not enough
+    assert!(
+        utils::config_pool_multisig_impl(&program, pool, multisig, &[&carol], pause()).is_err()
+    );
+    utils::config_pool_multisig_impl(&program, pool, multisig, &[&bob, &carol], pause())?;
+    let pool_account: Pool = program.account(pool)?;
+    assert!(pool_account.swaps_suspended);
+
+    // rotate carol out, the new set needs all the signers
+    assert!(utils::update_admin_multisig_impl(
+        &program,
+        multisig,
+        &[&alice],
+        vec![alice.pubkey(), bob.pubkey()],
+        2
+    )
+    .is_err());
+    utils::update_admin_multisig_impl(
+        &program,
+        multisig,
+        &[&alice, &carol],
+        vec![alice.pubkey(), bob.pubkey()],
+        2,
+    )?;
+    let multisig_account: AdminMultisig = program.account(multisig)?;
+    assert_eq!(multisig_account.signers, vec![alice.pubkey(), bob.pubkey()]);
+
+    let resume = || PoolConfig {
+        suspended: Some(false),
+        ..Default::default()
+    };
+    assert!(utils::config_pool_multisig_impl(
+        &program,
+        pool,
+        multisig,
+        &[&alice, &carol],
+        resume()
+    )
+    .is_err());
+    utils::config_pool_multisig_impl(&program, pool, multisig, &[&alice, &bob], resume())?;
+    let pool_account: Pool = program.account(pool)?;
+    assert!(!pool_account.swaps_suspended);
+}
@@ -0,0 +1,109 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, Duplicate};
+use gfx_swap::{AccessMode, AllowlistEntry, DerivedAccountIdentifier, LPMint, Pool, PoolConfig};
+use serial_test::serial;
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Keypair, signature::Signer};
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn allowlist() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    mint_to(token_a, admin, user.pubkey(), 1000)?;
+    mint_to(token_b, admin, user.pubkey(), 1000)?;
+
+    // used for bootstrap
+    mint_to(token_a, admin, admin.pubkey(), 1)?;
+    mint_to(token_b, admin, admin.pubkey(), 1)?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        true,
+    )?;
+
+    // pools are open by default
+    let pool_account: Pool = program.account(pool)?;
+    assert_eq!(pool_account.access_mode, AccessMode::Open);
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 10.)?;
+
+    let permissioned = PoolConfig {
+        access_mode: Some(AccessMode::Permissioned),
+        ..Default::default()
+    };
+
+    // only the admin can change the access mode, and not instantly
+    assert!(utils::config_pool_impl(&program, pool, &admin, permissioned.clone()).is_err());
+    let stranger = Keypair::new();
+    assert!(utils::queue_config_impl(&program, pool, &stranger, permissioned.clone()).is_err());
+    // the admin has to approve, a transaction paid by the user cannot pass the admin key alone
+    let user_program = Client::new(Cluster::Devnet, user.clone()).program(gfx_swap::ID);
+    assert!(utils::queue_config_unsigned_impl(
+        &user_program,
+        pool,
+        admin.pubkey(),
+        permissioned.clone()
+    )
+    .is_err());
+
+    utils::queue_config_impl(&program, pool, &admin, permissioned)?;
+    utils::execute_config_impl(&program, pool)?;
+    let pool_account: Pool = program.account(pool)?;
+    assert_eq!(pool_account.access_mode, AccessMode::Permissioned);
+
+    assert!(utils::swap_impl(&program, pool, lp_mint, token_a, token_b, user, 1.).is_err());
+    assert!(utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 1.).is_err());
+
+    // existing LPs can always exit
+    utils::withdraw2_impl(&program, pool, lp_mint, token_a, token_b, user, 1.)?;
+
+    // only the admin can allow wallets
+    assert!(utils::add_to_allowlist_impl(&program, pool, &stranger, user.pubkey()).is_err());
+
+    utils::add_to_allowlist_impl(&program, pool, &admin, user.pubkey())?;
+    let entry: AllowlistEntry = program.account(AllowlistEntry::address(
+        &gfx_swap::ID,
+        &pool,
+        &user.pubkey(),
+    ))?;
+    assert_eq!(entry.pool, pool);
+    assert_eq!(entry.wallet, user.pubkey());
+
+    utils::swap_impl(&program, pool, lp_mint, token_a, token_b, user, 1.)?;
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 1.)?;
+
+    utils::remove_from_allowlist_impl(&program, pool, &admin, user.pubkey())?;
+    assert!(utils::swap_impl(&program, pool, lp_mint, token_a, token_b, user, 1.).is_err());
+    utils::withdraw2_impl(&program, pool, lp_mint, token_a, token_b, user, 1.)?;
+}
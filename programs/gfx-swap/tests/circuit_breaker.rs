@@ -0,0 +1,115 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anchor_spl::token::TokenAccount;
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, Duplicate};
+use gfx_swap::{
+    CircuitBreakerParams, DerivedAccountIdentifier, LPMint, Pool, PoolConfig, TripReason,
+};
+use serial_test::serial;
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Keypair, signature::Signer};
+use spl_associated_token_account::get_associated_token_address;
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn circuit_breaker() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    mint_to(token_a, admin, user.pubkey(), 1000)?;
+    mint_to(token_b, admin, user.pubkey(), 1000)?;
+
+    // used for bootstrap
+    mint_to(token_a, admin, admin.pubkey(), 1)?;
+    mint_to(token_b, admin, admin.pubkey(), 1)?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        true,
+    )?;
+
+    // 100 of each token in the pool
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 10.)?;
+
+    // at most 5% per trade and 10% within 1000 slots
+    utils::queue_config_impl(
+        &program,
+        pool,
+        &admin,
+        PoolConfig {
+            circuit_breaker: Some(CircuitBreakerParams {
+                max_trade_move_bps: 500,
+                max_window_move_bps: 1000,
+                window_slots: 1000,
+            }),
+            ..Default::default()
+        },
+    )?;
+    utils::execute_config_impl(&program, pool)?;
+
+    // a single large trade is rejected
+    assert!(utils::swap_impl(&program, pool, lp_mint, token_a, token_b, user, 13.).is_err());
+
+    // small trades go through until they add up
+    utils::swap_impl(&program, pool, lp_mint, token_a, token_b, user, 2.)?;
+    utils::swap_impl(&program, pool, lp_mint, token_a, token_b, user, 2.)?;
+
+    let vault_address = get_associated_token_address(&pool, &token_a);
+    let vault_before: TokenAccount = program.account(vault_address)?;
+
+    // this one trips the breaker, the trade goes through and the trip is recorded
+    utils::swap_impl(&program, pool, lp_mint, token_a, token_b, user, 2.)?;
+    let vault_after: TokenAccount = program.account(vault_address)?;
+    assert!(vault_after.amount > vault_before.amount);
+
+    let pool_account: Pool = program.account(pool)?;
+    assert_eq!(pool_account.circuit_breaker.tripped, TripReason::WindowMove);
+    assert!(pool_account.circuit_breaker.tripped_move_bps > 1000);
+    assert!(pool_account.circuit_breaker.tripped_slot > 0);
+
+    // the swaps stay stopped, even in the other direction
+    assert!(utils::swap_impl(&program, pool, lp_mint, token_b, token_a, user, 1.).is_err());
+
+    // resuming the swaps clears the trip
+    utils::config_pool_impl(
+        &program,
+        pool,
+        &admin,
+        PoolConfig {
+            swaps_suspended: Some(false),
+            ..Default::default()
+        },
+    )?;
+    let pool_account: Pool = program.account(pool)?;
+    assert_eq!(pool_account.circuit_breaker.tripped, TripReason::None);
+    utils::swap_impl(&program, pool, lp_mint, token_b, token_a, user, 1.)?;
+}
@@ -4,7 +4,7 @@ use anchor_client::{Client, Cluster};
 use anyhow::Error;
 use fehler::throws;
 use gfx_solana_utils::{admin_wallet, create_token, Duplicate};
-use gfx_swap::{DerivedAccountIdentifier, LPMint, Pool};
+use gfx_swap::{DerivedAccountIdentifier, LPMint, Pool, PoolRegistryPage};
 use serial_test::serial;
 use solana_sdk::{signature::Keypair, signature::Signer};
 
@@ -29,7 +29,7 @@ fn create_swap_pool() {
     let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
     let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
 
-    utils::create_pool_impl(
+    let registry_page = utils::create_pool_impl(
         &program,
         pool,
         lp_mint,
@@ -41,4 +41,22 @@ fn create_swap_pool() {
         lp_bump,
         false,
     )?;
+
+    let page: PoolRegistryPage = program.account(registry_page)?;
+    let entry = page
+        .entries
+        .iter()
+        .find(|entry| entry.pool == pool)
+        .unwrap();
+    let sorted = if token_a < token_b {
+        (token_a, token_b)
+    } else {
+        (token_b, token_a)
+    };
+    assert_eq!((entry.token_mint_1, entry.token_mint_2), sorted);
+
+    utils::delist_pool_impl(&program, pool, registry_page, &admin)?;
+
+    let page: PoolRegistryPage = program.account(registry_page)?;
+    assert!(page.entries.iter().all(|entry| entry.pool != pool));
 }
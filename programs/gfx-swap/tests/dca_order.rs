@@ -0,0 +1,104 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anchor_spl::token::{Mint, TokenAccount};
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, Duplicate};
+use gfx_swap::{DcaOrder, DerivedAccountIdentifier, LPMint, Pool};
+use serial_test::serial;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Keypair, signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn dca_order() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    mint_to(token_a, admin, user.pubkey(), 1000)?;
+    mint_to(token_b, admin, user.pubkey(), 1000)?;
+
+    // used for bootstrap, the admin also keeps the keeper fees
+    mint_to(token_a, admin, admin.pubkey(), 1)?;
+    mint_to(token_b, admin, admin.pubkey(), 1)?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        true,
+    )?;
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 100.)?;
+
+    let balance = |owner: Pubkey, token: Pubkey| -> Result<u64, Error> {
+        let ata: TokenAccount = program.account(get_associated_token_address(&owner, &token))?;
+        Ok(ata.amount)
+    };
+    let a_decimals = program.account::<Mint>(token_a)?.decimals;
+    let slice = 2 * 10u64.pow(a_decimals as u32);
+
+    // sell 5 A in slices of 2 A, one per hour, for at least 1 B per slice
+    let user_a = balance(user.pubkey(), token_a)?;
+    let order = utils::create_dca_order_impl(
+        &program, pool, token_a, token_b, user, 0, 5., 2., 3600, 1., 50,
+    )?;
+    assert!(balance(user.pubkey(), token_a)? < user_a);
+
+    // the first slice is due right away
+    let user_b = balance(user.pubkey(), token_b)?;
+    let keeper_b = balance(admin.pubkey(), token_b)?;
+    utils::execute_dca_slice_impl(&program, order, admin)?;
+
+    let owner_amount = balance(user.pubkey(), token_b)? - user_b;
+    let keeper_fee = balance(admin.pubkey(), token_b)? - keeper_b;
+    assert!(owner_amount > 0);
+    assert!(keeper_fee > 0);
+    // 0.5% of the swap output
+    assert_eq!(keeper_fee, (owner_amount + keeper_fee) / 200);
+
+    let order_account: DcaOrder = program.account(order)?;
+    assert_eq!(order_account.executed_amount, slice);
+    assert!(order_account.last_slice_at > 0);
+
+    // the next one is not due for an hour
+    assert!(utils::execute_dca_slice_impl(&program, order, admin).is_err());
+
+    // only the owner gets the remainder back
+    assert!(utils::cancel_dca_order_impl(&program, order, admin).is_err());
+    utils::cancel_dca_order_impl(&program, order, user)?;
+    assert_eq!(balance(user.pubkey(), token_a)?, user_a - slice);
+
+    // both accounts of the order are closed
+    assert!(program.account::<DcaOrder>(order).is_err());
+    assert!(program
+        .account::<TokenAccount>(get_associated_token_address(&order, &token_a))
+        .is_err());
+}
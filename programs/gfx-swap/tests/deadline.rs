@@ -0,0 +1,82 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, Duplicate};
+use gfx_swap::{Deadline, DerivedAccountIdentifier, LPMint, Pool};
+use serial_test::serial;
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Keypair, signature::Signer};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn deadline() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    mint_to(token_a, admin, user.pubkey(), 1000)?;
+    mint_to(token_b, admin, user.pubkey(), 1000)?;
+
+    // used for bootstrap
+    mint_to(token_a, admin, admin.pubkey(), 1)?;
+    mint_to(token_b, admin, admin.pubkey(), 1)?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        true,
+    )?;
+
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 10.)?;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    let slot = program.rpc().get_slot()?;
+
+    // expired
+    for deadline in [Deadline::UnixTimestamp(now - 60), Deadline::Slot(slot - 1)] {
+        assert!(utils::swap_with_deadline_impl(
+            &program, pool, lp_mint, token_a, token_b, user, 1., deadline
+        )
+        .is_err());
+    }
+
+    // still valid
+    for deadline in [
+        Deadline::UnixTimestamp(now + 60),
+        Deadline::Slot(slot + 100),
+    ] {
+        utils::swap_with_deadline_impl(
+            &program, pool, lp_mint, token_a, token_b, user, 1., deadline,
+        )?;
+    }
+
+    // the variant without a deadline still works
+    utils::swap_impl(&program, pool, lp_mint, token_a, token_b, user, 1.)?;
+}
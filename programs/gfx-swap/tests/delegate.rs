@@ -0,0 +1,120 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anchor_spl::token::TokenAccount;
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, LPMint, Pool};
+use serial_test::serial;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Keypair, signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn delegate_and_pda() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+    let delegate = Keypair::new();
+    let (vault, _) = mock_pda_swapper::vault_address_with_bump();
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+    let swapper = client.program(mock_pda_swapper::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    for token in [token_a, token_b] {
+        mint_to(token, admin, user.pubkey(), 1000)?;
+        // the token accounts of the PDA are associated token accounts as well
+        mint_to(token, admin, vault, 1000)?;
+        // used for bootstrap
+        mint_to(token, admin, admin.pubkey(), 1)?;
+    }
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        true,
+    )?;
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 100.)?;
+
+    let balance = |owner: Pubkey, token: Pubkey| -> Result<u64, Error> {
+        let ata: TokenAccount = program.account(get_associated_token_address(&owner, &token))?;
+        Ok(ata.amount)
+    };
+
+    // a delegate swaps up to its approval and sends the output back to the owner
+    utils::approve_impl(&program, user, token_a, delegate.pubkey(), 1_000_000)?;
+
+    assert!(utils::delegated_swap_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        user.pubkey(),
+        &delegate,
+        1_000_001,
+    )
+    .is_err());
+
+    let (user_a, user_b) = (
+        balance(user.pubkey(), token_a)?,
+        balance(user.pubkey(), token_b)?,
+    );
+    utils::delegated_swap_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        user.pubkey(),
+        &delegate,
+        1_000_000,
+    )?;
+    assert_eq!(balance(user.pubkey(), token_a)?, user_a - 1_000_000);
+    assert!(balance(user.pubkey(), token_b)? > user_b);
+
+    // the approval is used up
+    assert!(utils::delegated_swap_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        user.pubkey(),
+        &delegate,
+        1,
+    )
+    .is_err());
+
+    // a program swaps the tokens of its PDA, which signs the CPI with its seeds
+    let (vault_a, vault_b) = (balance(vault, token_a)?, balance(vault, token_b)?);
+    utils::pda_swap_impl(&swapper, pool, lp_mint, token_a, token_b, 1_000_000)?;
+    assert_eq!(balance(vault, token_a)?, vault_a - 1_000_000);
+    assert!(balance(vault, token_b)? > vault_b);
+}
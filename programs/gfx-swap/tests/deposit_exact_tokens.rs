@@ -0,0 +1,109 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anchor_spl::token::{Mint, TokenAccount};
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, LPMint, Pool};
+use serial_test::serial;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Keypair, signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn deposit_exact_tokens() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    mint_to(token_a, admin, user.pubkey(), 1000)?;
+    mint_to(token_b, admin, user.pubkey(), 1000)?;
+
+    // used for bootstrap
+    mint_to(token_a, admin, admin.pubkey(), 1)?;
+    mint_to(token_b, admin, admin.pubkey(), 1)?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        true,
+    )?;
+
+    let balance = |owner: Pubkey, token: Pubkey| -> Result<u64, Error> {
+        let ata: TokenAccount = program.account(get_associated_token_address(&owner, &token))?;
+        Ok(ata.amount)
+    };
+    let reserves =
+        || -> Result<(u64, u64), Error> { Ok((balance(pool, token_a)?, balance(pool, token_b)?)) };
+
+    // asking for more LP tokens than the amounts cover
+    assert!(utils::deposit_exact_tokens_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        user,
+        100.,
+        250.,
+        u64::MAX,
+    )
+    .is_err());
+
+    let (user_a, user_b) = (
+        balance(user.pubkey(), token_a)?,
+        balance(user.pubkey(), token_b)?,
+    );
+    let (reserve_a, reserve_b) = reserves()?;
+    let lp_supply = program.account::<Mint>(lp_mint)?.supply;
+
+    utils::deposit_exact_tokens_impl(
+        &program, pool, lp_mint, token_a, token_b, user, 100., 250., 1,
+    )?;
+
+    let (paid_a, paid_b) = (
+        user_a - balance(user.pubkey(), token_a)?,
+        user_b - balance(user.pubkey(), token_b)?,
+    );
+    let (new_reserve_a, new_reserve_b) = reserves()?;
+    let minted = program.account::<Mint>(lp_mint)?.supply - lp_supply;
+
+    // the pool takes the amounts at its ratio and leaves the rest of B to the user
+    assert_eq!(new_reserve_a - reserve_a, paid_a);
+    assert_eq!(new_reserve_b - reserve_b, paid_b);
+    let b_decimals = program.account::<Mint>(token_b)?.decimals;
+    assert!(paid_a > 0 && paid_b < 250 * 10u64.pow(b_decimals as u32));
+    assert!((paid_a as u128) * (reserve_b as u128) / (reserve_a as u128) <= paid_b as u128 + 1);
+
+    // the LP share is rounded down, the paid amounts are rounded up
+    assert!((minted as u128) * (reserve_a as u128) <= (paid_a as u128) * (lp_supply as u128));
+    assert!((minted as u128) * (reserve_b as u128) <= (paid_b as u128) * (lp_supply as u128));
+}
@@ -0,0 +1,105 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anchor_spl::token::{Mint, TokenAccount};
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, LPMint, Pool};
+use serial_test::serial;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Keypair, signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn deposit_imbalanced() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    mint_to(token_a, admin, user.pubkey(), 1000)?;
+    mint_to(token_b, admin, user.pubkey(), 1000)?;
+
+    // used for bootstrap
+    mint_to(token_a, admin, admin.pubkey(), 1)?;
+    mint_to(token_b, admin, admin.pubkey(), 1)?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        true,
+    )?;
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 100.)?;
+
+    let balance = |owner: Pubkey, token: Pubkey| -> Result<u64, Error> {
+        let ata: TokenAccount = program.account(get_associated_token_address(&owner, &token))?;
+        Ok(ata.amount)
+    };
+    let lp_supply = || -> Result<u64, Error> { Ok(program.account::<Mint>(lp_mint)?.supply) };
+
+    // asking for more LP tokens than the deposit is worth
+    assert!(utils::deposit_imbalanced_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        user,
+        10.,
+        0.,
+        u64::MAX,
+    )
+    .is_err());
+
+    // a single sided deposit
+    let (initial_reserve_a, initial_reserve_b) = (balance(pool, token_a)?, balance(pool, token_b)?);
+    let initial_supply = lp_supply()?;
+    utils::deposit_imbalanced_impl(&program, pool, lp_mint, token_a, token_b, user, 10., 0., 1)?;
+    let single_sided_a = balance(pool, token_a)? - initial_reserve_a;
+    let single_sided_minted = lp_supply()? - initial_supply;
+    assert_eq!(balance(pool, token_b)?, initial_reserve_b);
+
+    // the pool takes all of both amounts
+    let (reserve_a, reserve_b) = (balance(pool, token_a)?, balance(pool, token_b)?);
+    let supply = lp_supply()?;
+    utils::deposit_imbalanced_impl(&program, pool, lp_mint, token_a, token_b, user, 5., 5., 1)?;
+    let balanced_a = balance(pool, token_a)? - reserve_a;
+    let balanced_b = balance(pool, token_b)? - reserve_b;
+    let balanced_minted = lp_supply()? - supply;
+    assert_eq!(balanced_a * 2, single_sided_a);
+    assert!(balanced_b > 0);
+
+    // the single sided deposit pays the imbalance fee, its LP share is below its share of the reserves
+    assert!(
+        (single_sided_minted as u128) * (initial_reserve_a as u128)
+            < (single_sided_a as u128) * (initial_supply as u128)
+    );
+    assert!(balanced_minted > 0);
+}
@@ -0,0 +1,114 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anchor_spl::token::TokenAccount;
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, FarmStake, LPMint, Pool};
+use serial_test::serial;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Keypair, signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+use std::{thread, time::Duration};
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn farm() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+    let reward = create_token(&admin)?;
+
+    // both the admin and the user provide liquidity and stake it
+    mint_to(token_a, admin, user.pubkey(), 1000)?;
+    mint_to(token_b, admin, user.pubkey(), 1000)?;
+    mint_to(token_a, admin, admin.pubkey(), 1000)?;
+    mint_to(token_b, admin, admin.pubkey(), 1000)?;
+
+    // the admin funds the farm, the user needs a reward account to claim
+    mint_to(reward, admin, admin.pubkey(), 1000)?;
+    mint_to(reward, admin, user.pubkey(), 1)?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        true,
+    )?;
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 100.)?;
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, admin, 100.)?;
+
+    let balance = |owner: Pubkey, token: Pubkey| -> Result<u64, Error> {
+        let ata: TokenAccount = program.account(get_associated_token_address(&owner, &token))?;
+        Ok(ata.amount)
+    };
+
+    // only the admin can create and fund the farm
+    assert!(utils::create_farm_impl(&program, pool, reward, user, 1000).is_err());
+    let farm = utils::create_farm_impl(&program, pool, reward, admin, 1000)?;
+    assert!(utils::fund_farm_impl(&program, pool, user, 1.).is_err());
+    utils::fund_farm_impl(&program, pool, admin, 100.)?;
+
+    let user_stake = utils::create_farm_stake_impl(&program, farm, user)?;
+    let admin_stake = utils::create_farm_stake_impl(&program, farm, admin)?;
+    let staked = balance(user.pubkey(), lp_mint)?;
+
+    // the user is alone in the farm for a while, then the admin stakes as much
+    utils::stake_lp_impl(&program, farm, user, staked)?;
+    assert_eq!(balance(user.pubkey(), lp_mint)?, 0);
+    thread::sleep(Duration::from_secs(10));
+    utils::stake_lp_impl(&program, farm, admin, staked)?;
+    thread::sleep(Duration::from_secs(10));
+
+    let user_reward = balance(user.pubkey(), reward)?;
+    let admin_reward = balance(admin.pubkey(), reward)?;
+    utils::claim_rewards_impl(&program, farm, user)?;
+    utils::claim_rewards_impl(&program, farm, admin)?;
+    let user_claimed = balance(user.pubkey(), reward)? - user_reward;
+    let admin_claimed = balance(admin.pubkey(), reward)? - admin_reward;
+
+    assert!(admin_claimed > 0);
+    assert!(user_claimed > admin_claimed);
+
+    // nobody can unstake more than the stake
+    assert!(utils::unstake_lp_impl(&program, farm, user, staked + 1).is_err());
+    utils::unstake_lp_impl(&program, farm, user, staked)?;
+    assert_eq!(balance(user.pubkey(), lp_mint)?, staked);
+    assert_eq!(program.account::<FarmStake>(user_stake)?.amount, 0);
+
+    // stopping the emissions leaves the stake of the admin in place
+    assert!(utils::set_emission_rate_impl(&program, pool, user, 0).is_err());
+    utils::set_emission_rate_impl(&program, pool, admin, 0)?;
+    utils::claim_rewards_impl(&program, farm, admin)?;
+    let admin_reward = balance(admin.pubkey(), reward)?;
+    thread::sleep(Duration::from_secs(2));
+    utils::claim_rewards_impl(&program, farm, admin)?;
+    assert_eq!(balance(admin.pubkey(), reward)?, admin_reward);
+    assert_eq!(program.account::<FarmStake>(admin_stake)?.amount, staked);
+}
@@ -0,0 +1,203 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::token::TokenAccount;
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, Duplicate};
+use gfx_swap::{
+    AllowlistEntry, DerivedAccountIdentifier, Fees, GlobalConfig, LPMint, Pool, PoolConfig,
+    QueuedConfig,
+};
+use mock_flash_receiver::{REENTER, REPAY, REPAY_PRINCIPAL};
+use serial_test::serial;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, instruction::AccountMeta, pubkey::Pubkey,
+    signature::Keypair, signature::Signer, system_program, sysvar,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn flash_loan() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    for token in [token_a, token_b] {
+        // the borrower pays the fees from its own tokens
+        mint_to(token, admin, user.pubkey(), 1000)?;
+        // used for bootstrap
+        mint_to(token, admin, admin.pubkey(), 1)?;
+    }
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        true,
+    )?;
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 100.)?;
+
+    let pool_account: Pool = program.account(pool)?;
+    let vault_balance = |vault: Pubkey| -> Result<u64, Error> {
+        let vault: TokenAccount = program.account(vault)?;
+        Ok(vault.amount)
+    };
+    let fee_vault_balance = || vault_balance(get_associated_token_address(&pool, &lp_mint));
+
+    // the receiver keeps the fees
+    assert!(utils::flash_loan_impl(
+        &program,
+        pool,
+        lp_mint,
+        user,
+        1_000_000,
+        1_000_000,
+        vec![REPAY_PRINCIPAL],
+        vec![],
+    )
+    .is_err());
+
+    // the receiver swaps in the same pool while the loan is out, which hits the pool lock
+    // unless the runtime already refuses the reentrance
+    let swap = gfx_swap::accounts::Swap {
+        pool,
+        global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+        in_token_vault: pool_account.token_vault_1,
+        out_token_vault: pool_account.token_vault_2,
+        in_token_mint: pool_account.token_mint_1,
+        out_token_mint: pool_account.token_mint_2,
+        lp_token_mint: lp_mint,
+        fee_vault: get_associated_token_address(&pool, &lp_mint),
+        user_wallet: user.pubkey(),
+        allowlist_entry: AllowlistEntry::address(&gfx_swap::ID, &pool, &user.pubkey()),
+        in_token_ata_user: get_associated_token_address(&user.pubkey(), &pool_account.token_mint_1),
+        out_token_ata_user: get_associated_token_address(
+            &user.pubkey(),
+            &pool_account.token_mint_2,
+        ),
+        token_program: spl_token::id(),
+        in_token_program: spl_token::id(),
+        out_token_program: spl_token::id(),
+        system_program: system_program::id(),
+    };
+    let mut reenter = vec![REENTER];
+    reenter.extend(
+        gfx_swap::instruction::Swap {
+            amount_in: 1_000,
+            minimum_amount_out: 0,
+        }
+        .data(),
+    );
+    let mut swap_accounts = vec![AccountMeta::new_readonly(gfx_swap::ID, false)];
+    swap_accounts.extend(swap.to_account_metas(None));
+    assert!(utils::flash_loan_impl(
+        &program,
+        pool,
+        lp_mint,
+        user,
+        1_000_000,
+        1_000_000,
+        reenter,
+        swap_accounts,
+    )
+    .is_err());
+
+    // the receiver executes a ready queued config while the loan is out, which would be lost
+    // when the loan saves the pool
+    let fee_hike = PoolConfig {
+        fees: Some(Fees {
+            trade_fee_numerator: 2,
+            ..pool_account.fees
+        }),
+        ..Default::default()
+    };
+    let queued_config = utils::queue_config_impl(&program, pool, &admin, fee_hike.clone())?;
+    let execute_config = gfx_swap::accounts::ExecuteConfig {
+        pool,
+        queued_config,
+        payer: program.payer(),
+        clock: sysvar::clock::id(),
+    };
+    let mut reenter = vec![REENTER];
+    reenter.extend(gfx_swap::instruction::ExecuteConfig {}.data());
+    let mut execute_config_accounts = vec![AccountMeta::new_readonly(gfx_swap::ID, false)];
+    execute_config_accounts.extend(execute_config.to_account_metas(None));
+    assert!(utils::flash_loan_impl(
+        &program,
+        pool,
+        lp_mint,
+        user,
+        1_000_000,
+        1_000_000,
+        reenter,
+        execute_config_accounts,
+    )
+    .is_err());
+
+    // the config is still queued and applies once the pool is unlocked
+    let queued_config_account: QueuedConfig = program.account(queued_config)?;
+    assert_eq!(queued_config_account.config.fees, fee_hike.fees);
+    utils::execute_config_impl(&program, pool)?;
+    let pool_account: Pool = program.account(pool)?;
+    assert_eq!(pool_account.fees.trade_fee_numerator, 2);
+
+    let vault_1_before = vault_balance(pool_account.token_vault_1)?;
+    let vault_2_before = vault_balance(pool_account.token_vault_2)?;
+    let fee_vault_before = fee_vault_balance()?;
+
+    utils::flash_loan_impl(
+        &program,
+        pool,
+        lp_mint,
+        user,
+        1_000_000,
+        1_000_000,
+        vec![REPAY],
+        vec![],
+    )?;
+
+    // the vaults earned the flash fees and the owner got its share in LP tokens
+    assert!(vault_balance(pool_account.token_vault_1)? > vault_1_before);
+    assert!(vault_balance(pool_account.token_vault_2)? > vault_2_before);
+    assert!(fee_vault_balance()? > fee_vault_before);
+
+    // the pool is unlocked again
+    let pool_account: Pool = program.account(pool)?;
+    assert!(!pool_account.locked);
+    utils::swap_impl(
+        &program,
+        pool,
+        lp_mint,
+        pool_account.token_mint_1,
+        pool_account.token_mint_2,
+        user,
+        1.,
+    )?;
+}
@@ -0,0 +1,156 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, Duplicate};
+use gfx_swap::{
+    DerivedAccountIdentifier, GlobalConfig, GlobalConfigUpdate, LPMint, Pool, PoolConfig,
+    PoolConstraints,
+};
+use serial_test::serial;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Keypair, signature::Signer,
+};
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn global_config() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+
+    let global_config = utils::global_config_impl(&program, &admin)?;
+    let global_config_account: GlobalConfig = program.account(global_config)?;
+    assert_eq!(global_config_account.owner, admin.pubkey());
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    mint_to(token_a, admin, user.pubkey(), 1000)?;
+    mint_to(token_b, admin, user.pubkey(), 1000)?;
+
+    // used for bootstrap
+    mint_to(token_a, admin, admin.pubkey(), 1)?;
+    mint_to(token_b, admin, admin.pubkey(), 1)?;
+
+    // new pools start with the default fee recipient as their fee manager
+    let treasury = Keypair::new();
+    utils::set_global_config_impl(
+        &program,
+        &admin,
+        GlobalConfigUpdate {
+            fee_recipient: Some(treasury.pubkey()),
+            ..Default::default()
+        },
+    )?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        true,
+    )?;
+    let pool_account: Pool = program.account(pool)?;
+    assert_eq!(pool_account.fee_manager, treasury.pubkey());
+
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 10.)?;
+
+    // only the owner can pull the kill switch
+    let kill = |suspended| GlobalConfigUpdate {
+        suspended: Some(suspended),
+        ..Default::default()
+    };
+    let stranger = Keypair::new();
+    assert!(utils::set_global_config_impl(&program, &stranger, kill(true)).is_err());
+
+    utils::set_global_config_impl(&program, &admin, kill(true))?;
+    assert!(utils::swap_impl(&program, pool, lp_mint, token_a, token_b, user, 1.).is_err());
+    assert!(utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 1.).is_err());
+    // LPs can still exit
+    utils::withdraw2_impl(&program, pool, lp_mint, token_a, token_b, user, 1.)?;
+
+    utils::set_global_config_impl(
+        &program,
+        &admin,
+        GlobalConfigUpdate {
+            withdrawals_suspended: Some(true),
+            ..Default::default()
+        },
+    )?;
+    assert!(utils::withdraw2_impl(&program, pool, lp_mint, token_a, token_b, user, 1.).is_err());
+
+    utils::set_global_config_impl(
+        &program,
+        &admin,
+        GlobalConfigUpdate {
+            suspended: Some(false),
+            withdrawals_suspended: Some(false),
+            ..Default::default()
+        },
+    )?;
+    utils::swap_impl(&program, pool, lp_mint, token_a, token_b, user, 1.)?;
+
+    // the constraints bound the fees of the pools
+    utils::set_global_config_impl(
+        &program,
+        &admin,
+        GlobalConfigUpdate {
+            constraints: Some(PoolConstraints {
+                max_trade_fee_numerator: 1,
+                max_trade_fee_denominator: 100,
+                min_config_delay: 0,
+            }),
+            ..Default::default()
+        },
+    )?;
+    let mut fees = pool_account.fees;
+    fees.trade_fee_numerator = 99;
+    fees.trade_fee_denominator = 100;
+    let fee_hike = PoolConfig {
+        fees: Some(fees),
+        ..Default::default()
+    };
+    assert!(utils::queue_config_impl(&program, pool, &admin, fee_hike).is_err());
+
+    // hand the ownership over and back, both keys have to approve
+    let new_owner = Keypair::new();
+    assert!(utils::transfer_global_owner_impl(&program, &stranger, &new_owner).is_err());
+    utils::transfer_global_owner_impl(&program, &admin, &new_owner)?;
+    let global_config_account: GlobalConfig = program.account(global_config)?;
+    assert_eq!(global_config_account.owner, new_owner.pubkey());
+    assert!(utils::set_global_config_impl(&program, &admin, kill(true)).is_err());
+    utils::transfer_global_owner_impl(&program, &new_owner, &admin)?;
+
+    // restore the defaults for the other tests
+    utils::set_global_config_impl(
+        &program,
+        &admin,
+        GlobalConfigUpdate {
+            fee_recipient: Some(Pubkey::default()),
+            constraints: Some(PoolConstraints::default()),
+            ..Default::default()
+        },
+    )?;
+}
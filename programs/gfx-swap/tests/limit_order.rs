@@ -0,0 +1,99 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anchor_spl::token::{Mint, TokenAccount};
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, LPMint, LimitOrder, Pool};
+use serial_test::serial;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Keypair, signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn limit_order() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    mint_to(token_a, admin, user.pubkey(), 1000)?;
+    mint_to(token_b, admin, user.pubkey(), 1000)?;
+
+    // used for bootstrap, the admin also moves the price and keeps the bounties
+    mint_to(token_a, admin, admin.pubkey(), 1)?;
+    mint_to(token_b, admin, admin.pubkey(), 100)?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        true,
+    )?;
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 100.)?;
+
+    let balance = |owner: Pubkey, token: Pubkey| -> Result<u64, Error> {
+        let ata: TokenAccount = program.account(get_associated_token_address(&owner, &token))?;
+        Ok(ata.amount)
+    };
+    let b_decimals = program.account::<Mint>(token_b)?.decimals;
+
+    // a cancelled order gives the escrow back
+    let user_a = balance(user.pubkey(), token_a)?;
+    let order = utils::place_order_impl(&program, pool, token_a, token_b, user, 0, 10., 10., 100)?;
+    assert!(balance(user.pubkey(), token_a)? < user_a);
+    assert!(utils::cancel_order_impl(&program, order, admin).is_err());
+    utils::cancel_order_impl(&program, order, user)?;
+    assert_eq!(balance(user.pubkey(), token_a)?, user_a);
+    assert!(program.account::<LimitOrder>(order).is_err());
+
+    // sell 10 A for at least 10 B, which the pool does not pay yet
+    let order = utils::place_order_impl(&program, pool, token_a, token_b, user, 1, 10., 10., 100)?;
+    assert!(utils::fill_orders_impl(&program, pool, admin, &[order]).is_err());
+
+    // buying A raises its price above the limit
+    utils::swap_impl(&program, pool, lp_mint, token_b, token_a, admin, 50.)?;
+
+    let user_b = balance(user.pubkey(), token_b)?;
+    let keeper_b = balance(admin.pubkey(), token_b)?;
+    utils::fill_orders_impl(&program, pool, admin, &[order])?;
+
+    let maker_amount = balance(user.pubkey(), token_b)? - user_b;
+    let bounty = balance(admin.pubkey(), token_b)? - keeper_b;
+    assert!(maker_amount >= 10 * 10u64.pow(b_decimals as u32));
+    assert!(bounty > 0);
+    // 1% of the swap output
+    assert_eq!(bounty, (maker_amount + bounty) / 100);
+
+    // both accounts of the order are closed
+    assert!(program.account::<LimitOrder>(order).is_err());
+    assert!(program
+        .account::<TokenAccount>(get_associated_token_address(&order, &token_a))
+        .is_err());
+}
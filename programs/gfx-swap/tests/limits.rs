@@ -0,0 +1,109 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anchor_spl::token::Mint;
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, ApplyDecimal, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, LPMint, Pool, PoolConfig, PoolLimits, TokenLimits};
+use serial_test::serial;
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Keypair, signature::Signer};
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn limits() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    mint_to(token_a, admin, user.pubkey(), 1000)?;
+    mint_to(token_b, admin, user.pubkey(), 1000)?;
+
+    // used for bootstrap
+    mint_to(token_a, admin, admin.pubkey(), 1)?;
+    mint_to(token_b, admin, admin.pubkey(), 1)?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        true,
+    )?;
+
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 10.)?;
+
+    let pool_account: Pool = program.account(pool)?;
+    let (token_1, token_2) = (pool_account.token_mint_1, pool_account.token_mint_2);
+    let token_1_mint: Mint = program.account(token_1)?;
+    let lp_mint_account: Mint = program.account(lp_mint)?;
+
+    // only token 1 is capped: 5 per swap, 3 per slot. Deposits can add 1 more LP token.
+    utils::queue_config_impl(
+        &program,
+        pool,
+        &admin,
+        PoolConfig {
+            limits: Some(PoolLimits {
+                token_1: TokenLimits {
+                    max_amount_in_per_swap: token_1_mint.decimals.apply(5),
+                    max_volume_per_slot: token_1_mint.decimals.apply(3),
+                },
+                token_2: TokenLimits::default(),
+                max_deposit_lp_supply: lp_mint_account.supply + lp_mint_account.decimals.apply(1),
+            }),
+            ..Default::default()
+        },
+    )?;
+    utils::execute_config_impl(&program, pool)?;
+
+    // over the size cap
+    assert!(utils::swap_impl(&program, pool, lp_mint, token_1, token_2, user, 6.).is_err());
+    // within the size cap, but over the slot volume
+    assert!(utils::swap_impl(&program, pool, lp_mint, token_1, token_2, user, 4.).is_err());
+    utils::swap_impl(&program, pool, lp_mint, token_1, token_2, user, 2.)?;
+
+    // token 2 is not capped
+    utils::swap_impl(&program, pool, lp_mint, token_2, token_1, user, 6.)?;
+
+    // the LP supply cap
+    assert!(utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 2.).is_err());
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 0.5)?;
+
+    // lifting the limits
+    utils::queue_config_impl(
+        &program,
+        pool,
+        &admin,
+        PoolConfig {
+            limits: Some(PoolLimits::default()),
+            ..Default::default()
+        },
+    )?;
+    utils::execute_config_impl(&program, pool)?;
+    utils::swap_impl(&program, pool, lp_mint, token_1, token_2, user, 6.)?;
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 2.)?;
+}
@@ -0,0 +1,106 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anchor_spl::token::TokenAccount;
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, LPMint, LockSummary, LockedPosition, Pool};
+use serial_test::serial;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Keypair, signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+use std::{thread, time::Duration};
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn locked_position() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    mint_to(token_a, admin, user.pubkey(), 1000)?;
+    mint_to(token_b, admin, user.pubkey(), 1000)?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        true,
+    )?;
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 100.)?;
+
+    let balance = |owner: Pubkey, token: Pubkey| -> Result<u64, Error> {
+        let ata: TokenAccount = program.account(get_associated_token_address(&owner, &token))?;
+        Ok(ata.amount)
+    };
+
+    assert_eq!(
+        utils::lock_summary_impl(&program, pool)?,
+        LockSummary::default()
+    );
+
+    // the cliff cannot be after the end
+    assert!(utils::lock_lp_impl(&program, pool, user, 0, 1000, 20, 10).is_err());
+
+    // lock half of the LP tokens, vesting over 20 seconds after a 5 seconds cliff
+    let user_lp = balance(user.pubkey(), lp_mint)?;
+    let locked = user_lp / 2;
+    let position = utils::lock_lp_impl(&program, pool, user, 0, locked, 5, 20)?;
+    assert_eq!(balance(user.pubkey(), lp_mint)?, user_lp - locked);
+
+    let summary = utils::lock_summary_impl(&program, pool)?;
+    assert_eq!(summary.positions, 1);
+    assert_eq!(summary.locked + summary.unlocked, locked);
+
+    // nothing vests before the cliff, and only the owner can unlock
+    assert!(utils::unlock_lp_impl(&program, position, user).is_err());
+    thread::sleep(Duration::from_secs(10));
+    assert!(utils::unlock_lp_impl(&program, position, admin).is_err());
+
+    utils::unlock_lp_impl(&program, position, user)?;
+    let released = program.account::<LockedPosition>(position)?.released_amount;
+    assert!(released > 0 && released < locked);
+    assert_eq!(
+        balance(user.pubkey(), lp_mint)?,
+        user_lp - locked + released
+    );
+
+    let summary = utils::lock_summary_impl(&program, pool)?;
+    assert_eq!(summary.locked + summary.unlocked, locked - released);
+
+    // everything is released after the end, and the position is closed
+    thread::sleep(Duration::from_secs(15));
+    utils::unlock_lp_impl(&program, position, user)?;
+    assert_eq!(balance(user.pubkey(), lp_mint)?, user_lp);
+    assert!(program.account::<LockedPosition>(position).is_err());
+    assert_eq!(
+        utils::lock_summary_impl(&program, pool)?,
+        LockSummary::default()
+    );
+}
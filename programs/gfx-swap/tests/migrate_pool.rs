@@ -0,0 +1,162 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anchor_lang::AnchorSerialize;
+use anchor_spl::token::{Mint, TokenAccount};
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, ApplyDecimal, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, LPMint, LegacyPool, Pool, PoolRegistryPage};
+use serial_test::serial;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, signature::Keypair, signature::Signer,
+    transaction::Transaction,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn migrate_legacy_pool_and_swap() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+    let token_a_mint: Mint = program.account(token_a)?;
+
+    mint_to(token_a, admin, user.pubkey(), 1000)?;
+    mint_to(token_b, admin, user.pubkey(), 1000)?;
+
+    // the reserves of the legacy pool
+    mint_to(token_a, admin, admin.pubkey(), 1)?;
+    mint_to(token_b, admin, admin.pubkey(), 1)?;
+
+    let seed = Keypair::new().pubkey();
+
+    let (legacy_pool, legacy_bump) =
+        LegacyPool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_legacy_pool_impl(
+        &program,
+        legacy_pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        legacy_bump,
+        lp_bump,
+    )?;
+
+    // the account has the size of the first version
+    let rpc_client = program.rpc();
+    let legacy_len = 8 + LegacyPool::default().try_to_vec()?.len();
+    assert_eq!(rpc_client.get_account_data(&legacy_pool)?.len(), legacy_len);
+
+    rpc_client.send_and_confirm_transaction(&Transaction::new_signed_with_payer(
+        &[
+            spl_token::instruction::transfer(
+                &spl_token::id(),
+                &get_associated_token_address(&admin.pubkey(), &token_a),
+                &get_associated_token_address(&legacy_pool, &token_a),
+                &admin.pubkey(),
+                &[],
+                1,
+            )?,
+            spl_token::instruction::transfer(
+                &spl_token::id(),
+                &get_associated_token_address(&admin.pubkey(), &token_b),
+                &get_associated_token_address(&legacy_pool, &token_b),
+                &admin.pubkey(),
+                &[],
+                1,
+            )?,
+        ],
+        Some(&admin.pubkey()),
+        &[admin],
+        rpc_client.get_recent_blockhash()?.0,
+    ))?;
+
+    let registry_page = utils::migrate_pool_impl(
+        &program,
+        legacy_pool,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+    )?;
+
+    // the legacy pool and its vaults are closed
+    for closed in [
+        legacy_pool,
+        get_associated_token_address(&legacy_pool, &token_a),
+        get_associated_token_address(&legacy_pool, &token_b),
+        get_associated_token_address(&legacy_pool, &lp_mint),
+    ] {
+        assert!(rpc_client.get_account(&closed).is_err());
+    }
+
+    let pool_account: Pool = program.account(pool)?;
+    assert_eq!(pool_account.seed, seed.to_bytes());
+    assert_eq!(pool_account.bump, pool_bump);
+    assert_eq!(pool_account.admin, admin.pubkey());
+    assert_eq!(pool_account.mint, lp_mint);
+    assert_eq!(
+        pool_account.fee_vault,
+        get_associated_token_address(&pool, &lp_mint)
+    );
+    assert!(!pool_account.swaps_suspended);
+
+    let token_a_vault: TokenAccount =
+        program.account(get_associated_token_address(&pool, &token_a))?;
+    assert_eq!(token_a_vault.amount, 1);
+    let token_b_vault: TokenAccount =
+        program.account(get_associated_token_address(&pool, &token_b))?;
+    assert_eq!(token_b_vault.amount, 1);
+
+    let page: PoolRegistryPage = program.account(registry_page)?;
+    assert!(page.entries.iter().any(|entry| entry.pool == pool));
+
+    // a migrated pool cannot be migrated again
+    assert!(utils::migrate_pool_impl(
+        &program,
+        legacy_pool,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+    )
+    .is_err());
+
+    // the new pool holds the authority of the LP mint
+    utils::mint_lp_to_impl(&program, pool, lp_mint, &admin, 1)?;
+    let lp_mint_account: Mint = program.account(lp_mint)?;
+    assert_eq!(lp_mint_account.supply, 1);
+
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 10.)?;
+    utils::swap_impl(&program, pool, lp_mint, token_a, token_b, user, 13.)?;
+
+    let token_a_vault: TokenAccount =
+        program.account(get_associated_token_address(&pool, &token_a))?;
+    assert!(token_a_vault.amount > token_a_mint.decimals.apply(100) + 1);
+    let fee_vault: TokenAccount = program.account(get_associated_token_address(&pool, &lp_mint))?;
+    assert!(fee_vault.amount != 0);
+}
@@ -0,0 +1,131 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anchor_spl::token::TokenAccount;
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, LPMint, Pool};
+use serial_test::serial;
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Keypair, signature::Signer};
+use spl_associated_token_account::get_associated_token_address;
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn native_sol() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+    let rpc_client = program.rpc();
+
+    let sol = spl_token::native_mint::id();
+    let token = create_token(&admin)?;
+
+    mint_to(token, admin, user.pubkey(), 1000)?;
+
+    // used for bootstrap
+    utils::wrap_sol_impl(&program, admin, 1)?;
+    mint_to(token, admin, admin.pubkey(), 1)?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        sol,
+        token,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        true,
+    )?;
+
+    let sol_vault = get_associated_token_address(&pool, &sol);
+    let vault_amount = || -> Result<u64, Error> {
+        let vault: TokenAccount = program.account(sol_vault)?;
+        Ok(vault.amount)
+    };
+
+    // The WSOL the user holds is left alone by the native instructions,
+    // which go through a temporary account closed back into the wallet.
+    let wsol_ata = utils::wrap_sol_impl(&program, user, 1000)?;
+    let temp_wsol = utils::temp_wsol_address(&user.pubkey());
+
+    // deposit SOL, the unused part of the maximum is unwrapped
+    let lamports_before = rpc_client.get_balance(&user.pubkey())?;
+    let vault_before = vault_amount()?;
+    utils::deposit2_native_impl(
+        &program,
+        pool,
+        lp_mint,
+        sol,
+        token,
+        user,
+        0.1,
+        200_000_000,
+        u64::MAX,
+    )?;
+    let deposited = vault_amount()? - vault_before;
+    assert!(deposited > 0 && deposited < 200_000_000);
+    assert_eq!(
+        rpc_client.get_balance(&user.pubkey())?,
+        lamports_before - deposited
+    );
+    assert!(rpc_client.get_account(&temp_wsol).is_err());
+
+    // SOL in
+    let lamports_before = rpc_client.get_balance(&user.pubkey())?;
+    let token_before: TokenAccount =
+        program.account(get_associated_token_address(&user.pubkey(), &token))?;
+    utils::swap_native_impl(&program, pool, lp_mint, sol, token, user, 1_000_000)?;
+    let token_after: TokenAccount =
+        program.account(get_associated_token_address(&user.pubkey(), &token))?;
+    assert!(token_after.amount > token_before.amount);
+    assert_eq!(
+        rpc_client.get_balance(&user.pubkey())?,
+        lamports_before - 1_000_000
+    );
+    assert!(rpc_client.get_account(&temp_wsol).is_err());
+
+    // SOL out
+    let lamports_before = rpc_client.get_balance(&user.pubkey())?;
+    let vault_before = vault_amount()?;
+    utils::swap_native_impl(&program, pool, lp_mint, token, sol, user, 1_000_000)?;
+    let swapped_out = vault_before - vault_amount()?;
+    assert!(swapped_out > 0);
+    assert_eq!(
+        rpc_client.get_balance(&user.pubkey())?,
+        lamports_before + swapped_out
+    );
+    assert!(rpc_client.get_account(&temp_wsol).is_err());
+
+    // withdraw SOL
+    let lamports_before = rpc_client.get_balance(&user.pubkey())?;
+    let vault_before = vault_amount()?;
+    utils::withdraw2_native_impl(&program, pool, lp_mint, sol, token, user, 0.05)?;
+    let withdrawn = vault_before - vault_amount()?;
+    assert!(withdrawn > 0);
+    assert_eq!(
+        rpc_client.get_balance(&user.pubkey())?,
+        lamports_before + withdrawn
+    );
+    assert!(rpc_client.get_account(&temp_wsol).is_err());
+
+    let wsol: TokenAccount = program.account(wsol_ata)?;
+    assert_eq!(wsol.amount, 1000);
+}
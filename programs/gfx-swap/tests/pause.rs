@@ -0,0 +1,119 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, LPMint, Pool, PoolConfig};
+use serial_test::serial;
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Keypair, signature::Signer};
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn pause_keeps_withdrawals_open() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    mint_to(token_a, admin, user.pubkey(), 1000)?;
+    mint_to(token_b, admin, user.pubkey(), 1000)?;
+
+    // used for bootstrap
+    mint_to(token_a, admin, admin.pubkey(), 1)?;
+    mint_to(token_b, admin, admin.pubkey(), 1)?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        true,
+    )?;
+
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 10.)?;
+
+    // the default pause
+    utils::config_pool_impl(
+        &program,
+        pool,
+        &admin,
+        PoolConfig {
+            suspended: Some(true),
+            ..Default::default()
+        },
+    )?;
+    let pool_account: Pool = program.account(pool)?;
+    assert!(pool_account.swaps_suspended);
+    assert!(pool_account.deposits_suspended);
+    assert!(pool_account.single_sided_suspended);
+    assert!(!pool_account.withdrawals_suspended);
+
+    assert!(utils::swap_impl(&program, pool, lp_mint, token_a, token_b, user, 1.).is_err());
+    assert!(utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 1.).is_err());
+
+    // LPs can still exit
+    utils::withdraw2_impl(&program, pool, lp_mint, token_a, token_b, user, 1.)?;
+
+    // withdrawals are only suspended explicitly
+    utils::config_pool_impl(
+        &program,
+        pool,
+        &admin,
+        PoolConfig {
+            withdrawals_suspended: Some(true),
+            ..Default::default()
+        },
+    )?;
+    assert!(utils::withdraw2_impl(&program, pool, lp_mint, token_a, token_b, user, 1.).is_err());
+
+    // resume the swaps only
+    utils::config_pool_impl(
+        &program,
+        pool,
+        &admin,
+        PoolConfig {
+            swaps_suspended: Some(false),
+            ..Default::default()
+        },
+    )?;
+    utils::swap_impl(&program, pool, lp_mint, token_a, token_b, user, 1.)?;
+    assert!(utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 1.).is_err());
+
+    // resuming with `suspended` leaves the withdrawal flag alone
+    utils::config_pool_impl(
+        &program,
+        pool,
+        &admin,
+        PoolConfig {
+            suspended: Some(false),
+            ..Default::default()
+        },
+    )?;
+    let pool_account: Pool = program.account(pool)?;
+    assert!(!pool_account.swaps_suspended);
+    assert!(!pool_account.deposits_suspended);
+    assert!(pool_account.withdrawals_suspended);
+}
@@ -0,0 +1,97 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anchor_spl::token::TokenAccount;
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, LPMint, Pool};
+use serial_test::serial;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Keypair, signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn swap_to_recipient() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+    let merchant = Keypair::new().pubkey();
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    mint_to(token_a, admin, user.pubkey(), 1000)?;
+    mint_to(token_b, admin, user.pubkey(), 1000)?;
+
+    // creates the token accounts of the merchant
+    mint_to(token_a, admin, merchant, 1)?;
+    mint_to(token_b, admin, merchant, 1)?;
+
+    // used for bootstrap
+    mint_to(token_a, admin, admin.pubkey(), 1)?;
+    mint_to(token_b, admin, admin.pubkey(), 1)?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        true,
+    )?;
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 100.)?;
+
+    let balance = |owner: Pubkey, token: Pubkey| -> Result<u64, Error> {
+        let ata: TokenAccount = program.account(get_associated_token_address(&owner, &token))?;
+        Ok(ata.amount)
+    };
+
+    // the user pays in A and the merchant receives B
+    let (user_a, user_b) = (
+        balance(user.pubkey(), token_a)?,
+        balance(user.pubkey(), token_b)?,
+    );
+    let merchant_b = balance(merchant, token_b)?;
+
+    utils::swap_to_impl(
+        &program, pool, lp_mint, token_a, token_b, user, merchant, 1.,
+    )?;
+
+    assert!(balance(user.pubkey(), token_a)? < user_a);
+    assert_eq!(balance(user.pubkey(), token_b)?, user_b);
+    assert!(balance(merchant, token_b)? > merchant_b);
+
+    // the user burns LP tokens and the merchant receives both tokens
+    let user_lp = balance(user.pubkey(), lp_mint)?;
+    let (merchant_a, merchant_b) = (balance(merchant, token_a)?, balance(merchant, token_b)?);
+
+    utils::withdraw2_to_impl(
+        &program, pool, lp_mint, token_a, token_b, user, merchant, 1.,
+    )?;
+
+    assert!(balance(user.pubkey(), lp_mint)? < user_lp);
+    assert!(balance(merchant, token_a)? > merchant_a);
+    assert!(balance(merchant, token_b)? > merchant_b);
+}
@@ -0,0 +1,124 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, LPMint, Pool, PoolConfig, PoolRoles};
+use serial_test::serial;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signature::Signer};
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn roles() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+
+    let client = Client::new(Cluster::Devnet, admin.clone());
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        false,
+    )?;
+
+    let guardian = Keypair::new();
+    let treasury = Keypair::new();
+    let curve_manager = Keypair::new();
+
+    let pause = || PoolConfig {
+        suspended: Some(true),
+        ..Default::default()
+    };
+    let resume = || PoolConfig {
+        suspended: Some(false),
+        ..Default::default()
+    };
+
+    // only the admin can assign the roles
+    let roles = || PoolRoles {
+        fee_manager: Some(treasury.pubkey()),
+        pause_guardian: Some(guardian.pubkey()),
+        curve_manager: Some(curve_manager.pubkey()),
+    };
+    assert!(utils::set_roles_impl(&program, pool, &guardian, roles()).is_err());
+    assert!(utils::config_pool_impl(&program, pool, &guardian, pause()).is_err());
+
+    utils::set_roles_impl(&program, pool, &admin, roles())?;
+    let pool_account: Pool = program.account(pool)?;
+    assert_eq!(pool_account.fee_manager, treasury.pubkey());
+    assert_eq!(pool_account.pause_guardian, guardian.pubkey());
+    assert_eq!(pool_account.curve_manager, curve_manager.pubkey());
+
+    // the pause guardian can pause and resume, but cannot change the fees
+    utils::config_pool_impl(&program, pool, &guardian, pause())?;
+    let pool_account: Pool = program.account(pool)?;
+    assert!(pool_account.swaps_suspended);
+    utils::config_pool_impl(&program, pool, &guardian, resume())?;
+
+    let mut fees = pool_account.fees;
+    fees.trade_fee_numerator = 2;
+    let change_fees = || PoolConfig {
+        fees: Some(fees),
+        ..Default::default()
+    };
+    assert!(utils::queue_config_impl(&program, pool, &guardian, change_fees()).is_err());
+
+    // the fee manager cannot pause the pool
+    assert!(utils::config_pool_impl(&program, pool, &treasury, pause()).is_err());
+
+    // the curve manager can change the fees, but a config touching both needs both roles
+    assert!(utils::queue_config_impl(
+        &program,
+        pool,
+        &curve_manager,
+        PoolConfig {
+            fees: Some(fees),
+            suspended: Some(true),
+            ..Default::default()
+        }
+    )
+    .is_err());
+    utils::queue_config_impl(&program, pool, &curve_manager, change_fees())?;
+    utils::execute_config_impl(&program, pool)?;
+    let pool_account: Pool = program.account(pool)?;
+    assert_eq!(pool_account.fees.trade_fee_numerator, 2);
+    assert!(!pool_account.swaps_suspended);
+
+    // the admin can still act as any role
+    utils::config_pool_impl(&program, pool, &admin, pause())?;
+
+    // revoking a role
+    utils::set_roles_impl(
+        &program,
+        pool,
+        &admin,
+        PoolRoles {
+            pause_guardian: Some(Pubkey::default()),
+            ..Default::default()
+        },
+    )?;
+    assert!(utils::config_pool_impl(&program, pool, &guardian, resume()).is_err());
+    let pool_account: Pool = program.account(pool)?;
+    assert_eq!(pool_account.fee_manager, treasury.pubkey());
+    assert_eq!(pool_account.pause_guardian, Pubkey::default());
+}
@@ -0,0 +1,115 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anchor_spl::token::TokenAccount;
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, LPMint, Pool};
+use serial_test::serial;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Keypair, signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn route_swap() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+    let token_c = create_token(&admin)?;
+
+    for token in [token_a, token_b, token_c] {
+        mint_to(token, admin, user.pubkey(), 1000)?;
+        // used for bootstrap, token_b is in both pools
+        mint_to(token, admin, admin.pubkey(), 2)?;
+    }
+
+    let create_pool = |token_1: Pubkey, token_2: Pubkey| -> Result<(Pubkey, Pubkey), Error> {
+        // seed for creating the pool
+        let seed = Keypair::new().pubkey();
+
+        let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+        let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+        utils::create_pool_impl(
+            &program,
+            pool,
+            lp_mint,
+            token_1,
+            token_2,
+            &admin,
+            seed.to_bytes(),
+            pool_bump,
+            lp_bump,
+            true,
+        )?;
+        utils::deposit2_impl(&program, pool, lp_mint, token_1, token_2, user, 100.)?;
+
+        Ok((pool, lp_mint))
+    };
+    let (pool_ab, lp_mint_ab) = create_pool(token_a, token_b)?;
+    let (pool_bc, lp_mint_bc) = create_pool(token_b, token_c)?;
+
+    let balance = |token: Pubkey| -> Result<u64, Error> {
+        let ata: TokenAccount =
+            program.account(get_associated_token_address(&user.pubkey(), &token))?;
+        Ok(ata.amount)
+    };
+
+    let route = [
+        (pool_ab, lp_mint_ab, token_a, token_b),
+        (pool_bc, lp_mint_bc, token_b, token_c),
+    ];
+
+    // the final output is below the minimum
+    assert!(utils::route_swap_impl(&program, &route, user, 1_000_000, u64::MAX).is_err());
+
+    // the hops do not connect
+    let broken_route = [
+        (pool_ab, lp_mint_ab, token_a, token_b),
+        (pool_bc, lp_mint_bc, token_c, token_b),
+    ];
+    assert!(utils::route_swap_impl(&program, &broken_route, user, 1_000_000, 0).is_err());
+
+    // the same pool twice
+    let loop_route = [
+        (pool_ab, lp_mint_ab, token_a, token_b),
+        (pool_ab, lp_mint_ab, token_b, token_a),
+    ];
+    assert!(utils::route_swap_impl(&program, &loop_route, user, 1_000_000, 0).is_err());
+
+    let (a_before, b_before, c_before) = (balance(token_a)?, balance(token_b)?, balance(token_c)?);
+    let fee_vault_ab: TokenAccount =
+        program.account(get_associated_token_address(&pool_ab, &lp_mint_ab))?;
+    let fee_vault_bc: TokenAccount =
+        program.account(get_associated_token_address(&pool_bc, &lp_mint_bc))?;
+
+    utils::route_swap_impl(&program, &route, user, 1_000_000, 1)?;
+
+    assert_eq!(balance(token_a)?, a_before - 1_000_000);
+    // the intermediate token never reaches the user
+    assert_eq!(balance(token_b)?, b_before);
+    assert!(balance(token_c)? > c_before);
+
+    // both pools charged the owner fee
+    let fee_vault_ab_after: TokenAccount =
+        program.account(get_associated_token_address(&pool_ab, &lp_mint_ab))?;
+    let fee_vault_bc_after: TokenAccount =
+        program.account(get_associated_token_address(&pool_bc, &lp_mint_bc))?;
+    assert!(fee_vault_ab_after.amount > fee_vault_ab.amount);
+    assert!(fee_vault_bc_after.amount > fee_vault_bc.amount);
+}
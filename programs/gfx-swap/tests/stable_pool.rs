@@ -0,0 +1,160 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anchor_spl::token::{Mint, TokenAccount};
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, LPMint, Pool, StableCurve, SwapCurve};
+use serial_test::serial;
+use sim::StableSwapModel;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Keypair, signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+const AMP: u64 = 100;
+
+// The imbalanced deposits and withdrawals of a stable pool against the Curve model, on the reserves
+// and LP supply of the pool before each of them.
+#[throws(Error)]
+#[serial]
+#[test]
+fn stable_pool_matches_model() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    mint_to(token_a, admin, user.pubkey(), 1000)?;
+    mint_to(token_b, admin, user.pubkey(), 1000)?;
+
+    // used for bootstrap
+    mint_to(token_a, admin, admin.pubkey(), 1)?;
+    mint_to(token_b, admin, admin.pubkey(), 1)?;
+
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_with_curve_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        SwapCurve::StableCurve(StableCurve { amp: AMP }),
+        true,
+    )?;
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 10.)?;
+
+    let pool_account: Pool = program.account(pool)?;
+    let fees = pool_account.fees;
+    // the model takes the tokens in the order of the pool
+    let (token_1, token_2) = (pool_account.token_mint_1, pool_account.token_mint_2);
+
+    let balance = |owner: Pubkey, token: Pubkey| -> Result<u128, Error> {
+        let ata: TokenAccount = program.account(get_associated_token_address(&owner, &token))?;
+        Ok(ata.amount.into())
+    };
+    let lp_supply =
+        || -> Result<u128, Error> { Ok(program.account::<Mint>(lp_mint)?.supply.into()) };
+    let model = |fee: u128| -> Result<StableSwapModel, Error> {
+        let mut model = StableSwapModel::new_with_pool_tokens(
+            AMP.into(),
+            vec![balance(pool, token_1)?, balance(pool, token_2)?],
+            2,
+            lp_supply()?,
+        );
+        model.fee = fee;
+        Ok(model)
+    };
+    // the fee of the model is in 1e10, it charges half of it on the imbalance like the pool
+    let model_fee = u128::from(fees.trade_fee_numerator) * 10_000_000_000
+        / u128::from(fees.trade_fee_denominator);
+
+    // deposit_imbalanced mints the LP tokens of `add_liquidity`
+    let before = model(model_fee)?;
+    let (reserve_1, reserve_2) = (balance(pool, token_1)?, balance(pool, token_2)?);
+    let supply = lp_supply()?;
+    utils::deposit_imbalanced_impl(&program, pool, lp_mint, token_a, token_b, user, 10., 2., 1)?;
+    let amounts = vec![
+        balance(pool, token_1)? - reserve_1,
+        balance(pool, token_2)? - reserve_2,
+    ];
+    let minted = lp_supply()? - supply;
+    let sim_minted = before.sim_add_liquidity(amounts);
+    assert!(
+        minted.abs_diff(sim_minted) <= std::cmp::max(4, sim_minted / 1_000_000_000),
+        "minted={}, sim_minted={}",
+        minted,
+        sim_minted
+    );
+
+    // withdraw_imbalanced burns the LP tokens of `remove_liquidity_imbalance`, rounded up. The
+    // owner withdraw fee goes to the fee vault on top.
+    let before = model(model_fee)?;
+    let (reserve_1, reserve_2) = (balance(pool, token_1)?, balance(pool, token_2)?);
+    let supply = lp_supply()?;
+    utils::withdraw_imbalanced_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        user,
+        3.,
+        0.5,
+        u64::MAX,
+    )?;
+    let amounts = vec![
+        reserve_1 - balance(pool, token_1)?,
+        reserve_2 - balance(pool, token_2)?,
+    ];
+    let burnt = supply - lp_supply()?;
+    let sim_burnt = before.sim_remove_liquidity_imbalance(amounts);
+    assert!(
+        burnt + 1 >= sim_burnt && burnt <= sim_burnt + std::cmp::max(4, sim_burnt / 1_000_000_000),
+        "burnt={}, sim_burnt={}",
+        burnt,
+        sim_burnt
+    );
+
+    // withdraw_one_exact_lp pays out `calc_withdraw_one_coin` without fees, less the trading fee on
+    // half of it
+    let before = model(0)?;
+    let user_balance = balance(user.pubkey(), token_a)?;
+    let supply = lp_supply()?;
+    utils::withdraw_one_exact_lp_impl(
+        &program, pool, lp_mint, token_a, token_b, token_a, user, 1., 0,
+    )?;
+    let withdrawn = balance(user.pubkey(), token_a)? - user_balance;
+    let burnt = supply - lp_supply()?;
+    let index = if token_a == token_1 { 0 } else { 1 };
+    let sim_withdrawn = before.sim_calc_withdraw_one_coin(burnt, index);
+    let sim_withdrawn = sim_withdrawn
+        - fees
+            .trading_fee(std::cmp::max(1, sim_withdrawn / 2))
+            .unwrap();
+    assert!(
+        withdrawn.abs_diff(sim_withdrawn) <= std::cmp::max(4, sim_withdrawn / 1_000_000_000),
+        "withdrawn={}, sim_withdrawn={}",
+        withdrawn,
+        sim_withdrawn
+    );
+}
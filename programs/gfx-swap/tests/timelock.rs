@@ -0,0 +1,124 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, user_wallet, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, LPMint, Pool, PoolConfig, PoolRoles, QueuedConfig};
+use serial_test::serial;
+use solana_sdk::{signature::Keypair, signature::Signer};
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn timelock() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+
+    let client = Client::new(Cluster::Devnet, admin.clone());
+    let program = client.program(gfx_swap::ID);
+
+    // pays and signs instead of the admin, to check that the admin key alone is not enough
+    let user = user_wallet(1.)?;
+    let user_program = Client::new(Cluster::Devnet, user.clone()).program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        false,
+    )?;
+
+    let curve_manager = Keypair::new();
+    utils::set_roles_impl(
+        &program,
+        pool,
+        &admin,
+        PoolRoles {
+            curve_manager: Some(curve_manager.pubkey()),
+            ..Default::default()
+        },
+    )?;
+
+    let pool_account: Pool = program.account(pool)?;
+    assert_eq!(pool_account.config_delay, 0);
+    let mut fees = pool_account.fees;
+    fees.trade_fee_numerator = 999;
+    let fee_hike = || PoolConfig {
+        fees: Some(fees),
+        ..Default::default()
+    };
+
+    // fee changes cannot skip the queue
+    assert!(utils::config_pool_impl(&program, pool, &admin, fee_hike()).is_err());
+
+    // only the admin can change the delay
+    let delay = |config_delay| PoolConfig {
+        config_delay: Some(config_delay),
+        ..Default::default()
+    };
+    assert!(utils::queue_config_impl(&program, pool, &curve_manager, delay(3600)).is_err());
+    // and it has to approve, passing its key is not enough
+    assert!(
+        utils::queue_config_unsigned_impl(&user_program, pool, admin.pubkey(), delay(3600))
+            .is_err()
+    );
+    // nobody can occupy the queue with a config that changes nothing
+    assert!(
+        utils::queue_config_impl(&program, pool, &curve_manager, PoolConfig::default()).is_err()
+    );
+    assert!(utils::queue_config_impl(&program, pool, &admin, delay(-1)).is_err());
+    assert!(
+        utils::queue_config_impl(&program, pool, &admin, delay(Pool::MAX_CONFIG_DELAY + 1))
+            .is_err()
+    );
+
+    utils::queue_config_impl(&program, pool, &admin, delay(3600))?;
+    // one queued config at a time
+    assert!(utils::queue_config_impl(&program, pool, &curve_manager, fee_hike()).is_err());
+    utils::execute_config_impl(&program, pool)?;
+    let pool_account: Pool = program.account(pool)?;
+    assert_eq!(pool_account.config_delay, 3600);
+
+    // now a fee hike has to wait
+    let queued_config = utils::queue_config_impl(&program, pool, &curve_manager, fee_hike())?;
+    let queued_config_account: QueuedConfig = program.account(queued_config)?;
+    assert_eq!(queued_config_account.pool, pool);
+    assert_eq!(queued_config_account.config.fees, Some(fees));
+    assert!(utils::execute_config_impl(&program, pool).is_err());
+
+    // while pausing stays instant
+    let pause = |suspended| PoolConfig {
+        suspended: Some(suspended),
+        ..Default::default()
+    };
+    utils::config_pool_impl(&program, pool, &admin, pause(true))?;
+    utils::config_pool_impl(&program, pool, &admin, pause(false))?;
+
+    // strangers cannot cancel
+    let stranger = Keypair::new();
+    assert!(utils::cancel_config_impl(&program, pool, &stranger).is_err());
+
+    utils::cancel_config_impl(&program, pool, &admin)?;
+    assert!(program.account::<QueuedConfig>(queued_config).is_err());
+    assert!(utils::execute_config_impl(&program, pool).is_err());
+
+    let pool_account: Pool = program.account(pool)?;
+    assert_ne!(pool_account.fees.trade_fee_numerator, 999);
+}
@@ -6,13 +6,155 @@ use anchor_spl::token::Mint;
 use anyhow::Error;
 use fehler::throws;
 use gfx_solana_utils::{AnchorClientErrorExt, ApplyDecimal};
-use gfx_swap::{ConstantProductCurve, ErrorCode, Fees, SwapCurve};
+use gfx_swap::{
+    AdminMultisig, AllowlistEntry, ConstantProductCurve, DcaOrder, DcaSchedule, Deadline,
+    DerivedAccountIdentifier, ErrorCode, Farm, FarmStake, Fees, FlashFees, GlobalConfig,
+    GlobalConfigUpdate, LimitOrder, LockSummary, LockedPosition, Pool, PoolConfig, PoolRegistry,
+    PoolRegistryPage, PoolRoles, QueuedConfig, SwapCurve, TempWsol, REGISTRY_PAGE_CAPACITY,
+};
 use num_traits::AsPrimitive;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
+};
 use solana_sdk::{
-    signature::Keypair, signature::Signer, system_program, sysvar, transaction::Transaction,
+    bpf_loader_upgradeable, signature::Keypair, signature::Signer, system_instruction,
+    system_program, sysvar, transaction::Transaction,
 };
-use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::{create_associated_token_account, get_associated_token_address};
+
+// The authority of admin instructions is not a signer account, so that it can be an AdminMultisig.
+// The signing keys are passed in as remaining accounts instead.
+pub fn approvals(signers: &[&Keypair]) -> Vec<AccountMeta> {
+    signers
+        .iter()
+        .map(|signer| AccountMeta::new_readonly(signer.pubkey(), true))
+        .collect()
+}
+
+// Initialize the global config if nobody did it yet, `owner` has to be the upgrade authority.
+#[throws(Error)]
+pub fn global_config_impl(program: &Program, owner: &Keypair) -> Pubkey {
+    let (global_config, bump) = GlobalConfig::get_address_with_bump(&gfx_swap::ID, &[]);
+    if program.account::<GlobalConfig>(global_config).is_ok() {
+        return global_config;
+    }
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::InitGlobalConfig {
+            global_config,
+            owner: owner.pubkey(),
+            program_data: Pubkey::find_program_address(
+                &[gfx_swap::ID.as_ref()],
+                &bpf_loader_upgradeable::id(),
+            )
+            .0,
+            rent: sysvar::rent::id(),
+            system_program: system_program::id(),
+        })
+        .args(gfx_swap::instruction::InitGlobalConfig { bump })
+        .signer(owner)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "InitGlobalConfig: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+
+    global_config
+}
+
+#[throws(Error)]
+pub fn set_global_config_impl(program: &Program, owner: &Keypair, update: GlobalConfigUpdate) {
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::SetGlobalConfig {
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+            owner: owner.pubkey(),
+        })
+        .accounts(approvals(&[owner]))
+        .args(gfx_swap::instruction::SetGlobalConfig { update })
+        .signer(owner)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "SetGlobalConfig: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn transfer_global_owner_impl(program: &Program, owner: &Keypair, new_owner: &Keypair) {
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::TransferGlobalOwner {
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+            owner: owner.pubkey(),
+            new_owner: new_owner.pubkey(),
+        })
+        .accounts(approvals(&[owner, new_owner]))
+        .args(gfx_swap::instruction::TransferGlobalOwner {})
+        .signer(owner)
+        .signer(new_owner)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "TransferGlobalOwner: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+// Find the last registry page if it still has room, otherwise add a new page.
+#[throws(Error)]
+pub fn registry_page_impl(program: &Program, payer: &Keypair) -> Pubkey {
+    let (registry, registry_bump) = PoolRegistry::get_address_with_bump(&gfx_swap::ID, &[]);
+    let page_count = match program.account::<PoolRegistry>(registry) {
+        Ok(registry) => registry.page_count,
+        Err(_) => 0,
+    };
+
+    if page_count > 0 {
+        let page = PoolRegistryPage::get_page_address(&gfx_swap::ID, page_count - 1);
+        let page_account: PoolRegistryPage = program.account(page)?;
+        if page_account.entries.len() < REGISTRY_PAGE_CAPACITY {
+            return page;
+        }
+    }
+
+    let (page, page_bump) =
+        PoolRegistryPage::get_address_with_bump(&gfx_swap::ID, &page_count.to_le_bytes());
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::AddRegistryPage {
+            registry,
+            registry_page: page,
+            payer: payer.pubkey(),
+            rent: sysvar::rent::id(),
+            system_program: system_program::id(),
+        })
+        .args(gfx_swap::instruction::AddRegistryPage {
+            registry_bump,
+            page_bump,
+        })
+        .signer(payer)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "AddRegistryPage: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+
+    page
+}
 
+// Returns the registry page the pool is registered in
 #[throws(Error)]
 pub fn create_pool_impl(
     program: &Program,
@@ -26,20 +168,59 @@ pub fn create_pool_impl(
     lp_bump: u8,
 
     bootstrap: bool,
-) {
+) -> Pubkey {
+    create_pool_with_curve_impl(
+        program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        admin,
+        seed,
+        pool_bump,
+        lp_bump,
+        SwapCurve::ConstantProductCurve(ConstantProductCurve::new()),
+        bootstrap,
+    )?
+}
+
+// `create_pool_impl` with the given curve
+#[throws(Error)]
+pub fn create_pool_with_curve_impl(
+    program: &Program,
+    pool: Pubkey,
+    lp_mint: Pubkey,
+    token_a: Pubkey,
+    token_b: Pubkey,
+    admin: &Keypair,
+    seed: [u8; 32],
+    pool_bump: u8,
+    lp_bump: u8,
+    swap_curve: SwapCurve,
+
+    bootstrap: bool,
+) -> Pubkey {
+    let global_config = global_config_impl(program, admin)?;
+    let registry_page = registry_page_impl(program, admin)?;
+
     let tx = program
         .request()
         .accounts(gfx_swap::accounts::CreatePool {
             pool,
+            global_config,
 
             lp_token_mint: lp_mint,
             fee_vault: get_associated_token_address(&pool, &lp_mint),
 
             token_a_mint: token_a,
             token_a_vault: get_associated_token_address(&pool, &token_a),
+            token_a_program: spl_token::id(),
 
             token_b_mint: token_b,
             token_b_vault: get_associated_token_address(&pool, &token_b),
+            token_b_program: spl_token::id(),
+
+            registry_page,
 
             admin: admin.pubkey(),
 
@@ -62,7 +243,14 @@ pub fn create_pool_impl(
                 host_fee_numerator: 0,
                 host_fee_denominator: 0,
             },
-            swap_curve: SwapCurve::ConstantProductCurve(ConstantProductCurve::new()),
+            swap_curve,
+            config_delay: 0, // tests execute the queued configs right away
+            flash_fees: FlashFees {
+                flash_fee_numerator: 9,
+                flash_fee_denominator: 10000, // 0.09% flash loan fee
+                owner_flash_fee_numerator: 1,
+                owner_flash_fee_denominator: 10000, // 0.01% flash loan fee to us
+            },
         })
         .signer(admin)
         .send()
@@ -76,26 +264,7 @@ pub fn create_pool_impl(
     if bootstrap {
         // bootstrap the pool, set 1 token_a = 1 token_b = 1 lp token
 
-        let tx = program
-            .request()
-            .accounts(gfx_swap::accounts::MintLPTo {
-                admin: admin.pubkey(),
-                pool,
-                recipient_ata: get_associated_token_address(&admin.pubkey(), &lp_mint),
-                lp_token_mint: lp_mint,
-                token_program: spl_token::id(),
-                system_program: system_program::id(),
-                rent: sysvar::rent::id(),
-                associated_token_program: spl_associated_token_account::id(),
-            })
-            .args(gfx_swap::instruction::MintLpTo { n: 1 })
-            .signer(admin)
-            .send()
-            .map_err(|e| e.canonicalize::<ErrorCode>())?;
-        println!(
-            "MintLpTo: https://explorer.solana.com/tx/{}?cluster=devnet",
-            tx
-        );
+        mint_lp_to_impl(program, pool, lp_mint, admin, 1)?;
 
         let rpc_client = program.rpc();
         let tx = rpc_client.send_and_confirm_transaction(&Transaction::new_signed_with_payer(
@@ -127,6 +296,148 @@ pub fn create_pool_impl(
             tx
         );
     }
+
+    registry_page
+}
+
+#[throws(Error)]
+pub fn mint_lp_to_impl(program: &Program, pool: Pubkey, lp_mint: Pubkey, admin: &Keypair, n: u64) {
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::MintLPTo {
+            admin: admin.pubkey(),
+            payer: admin.pubkey(),
+            pool,
+            recipient: admin.pubkey(),
+            recipient_ata: get_associated_token_address(&admin.pubkey(), &lp_mint),
+            lp_token_mint: lp_mint,
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+            associated_token_program: spl_associated_token_account::id(),
+        })
+        .accounts(approvals(&[admin]))
+        .args(gfx_swap::instruction::MintLpTo { n })
+        .signer(admin)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+    println!(
+        "MintLpTo: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+// A pool of the first version of the program, with the constant product curve and the fees of
+// `create_pool_impl`. The program has to be built with the `ci` feature.
+#[throws(Error)]
+pub fn create_legacy_pool_impl(
+    program: &Program,
+    legacy_pool: Pubkey,
+    lp_mint: Pubkey,
+    token_a: Pubkey,
+    token_b: Pubkey,
+    admin: &Keypair,
+    seed: [u8; 32],
+    pool_bump: u8,
+    lp_bump: u8,
+) {
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::CreateLegacyPool {
+            legacy_pool,
+            lp_token_mint: lp_mint,
+            fee_vault: get_associated_token_address(&legacy_pool, &lp_mint),
+            token_a_mint: token_a,
+            token_a_vault: get_associated_token_address(&legacy_pool, &token_a),
+            token_b_mint: token_b,
+            token_b_vault: get_associated_token_address(&legacy_pool, &token_b),
+            admin: admin.pubkey(),
+            associated_token_program: spl_associated_token_account::id(),
+            token_program: spl_token::id(),
+            rent: sysvar::rent::id(),
+            system_program: system_program::id(),
+        })
+        .args(gfx_swap::instruction::CreateLegacyPool {
+            seed,
+            pool_bump,
+            lp_bump,
+            fees: Fees {
+                trade_fee_numerator: 1,
+                trade_fee_denominator: 1000,
+                owner_trade_fee_numerator: 1,
+                owner_trade_fee_denominator: 10000,
+                owner_withdraw_fee_numerator: 1,
+                owner_withdraw_fee_denominator: 10000,
+                host_fee_numerator: 0,
+                host_fee_denominator: 0,
+            },
+            swap_curve: SwapCurve::ConstantProductCurve(ConstantProductCurve::new()),
+        })
+        .signer(admin)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "CreateLegacyPool: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+// Returns the registry page the migrated pool is registered in
+#[throws(Error)]
+pub fn migrate_pool_impl(
+    program: &Program,
+    legacy_pool: Pubkey,
+    pool: Pubkey,
+    lp_mint: Pubkey,
+    token_a: Pubkey,
+    token_b: Pubkey,
+    payer: &Keypair,
+    seed: [u8; 32],
+    pool_bump: u8,
+) -> Pubkey {
+    let registry_page = registry_page_impl(program, payer)?;
+    let (token_mint_1, token_mint_2) = if token_a < token_b {
+        (token_a, token_b)
+    } else {
+        (token_b, token_a)
+    };
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::MigratePool {
+            legacy_pool,
+            legacy_token_vault_1: get_associated_token_address(&legacy_pool, &token_mint_1),
+            legacy_token_vault_2: get_associated_token_address(&legacy_pool, &token_mint_2),
+            legacy_fee_vault: get_associated_token_address(&legacy_pool, &lp_mint),
+
+            pool,
+            lp_token_mint: lp_mint,
+            token_mint_1,
+            token_mint_2,
+            token_vault_1: get_associated_token_address(&pool, &token_mint_1),
+            token_vault_2: get_associated_token_address(&pool, &token_mint_2),
+            fee_vault: get_associated_token_address(&pool, &lp_mint),
+
+            registry_page,
+            payer: payer.pubkey(),
+
+            associated_token_program: spl_associated_token_account::id(),
+            token_program: spl_token::id(),
+            rent: sysvar::rent::id(),
+            system_program: system_program::id(),
+        })
+        .args(gfx_swap::instruction::MigratePool { seed, pool_bump })
+        .signer(payer)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "MigratePool: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+
+    registry_page
 }
 
 #[throws(Error)]
@@ -147,16 +458,22 @@ pub fn deposit2_impl(
         .request()
         .accounts(gfx_swap::accounts::Deposit2 {
             pool: pool,
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
             token_a_vault: get_associated_token_address(&pool, &token_a),
             token_b_vault: get_associated_token_address(&pool, &token_b),
             lp_token_mint: lp_mint,
 
             user_wallet: user.pubkey(),
+            allowlist_entry: AllowlistEntry::address(&gfx_swap::ID, &pool, &user.pubkey()),
             user_token_a_ata: get_associated_token_address(&user.pubkey(), &token_a),
             user_token_b_ata: get_associated_token_address(&user.pubkey(), &token_b),
+            token_a_mint: token_a,
+            token_b_mint: token_b,
             user_lp_ata: get_associated_token_address(&user.pubkey(), &lp_mint),
 
             token_program: spl_token::id(),
+            token_a_program: spl_token::id(),
+            token_b_program: spl_token::id(),
             system_program: system_program::id(),
             rent: sysvar::rent::id(),
             associated_token_program: spl_associated_token_account::id(),
@@ -177,64 +494,1935 @@ pub fn deposit2_impl(
 }
 
 #[throws(Error)]
-pub fn swap_impl<N: AsPrimitive<f64>>(
+pub fn deposit_exact_tokens_impl(
     program: &Program,
     pool: Pubkey,
     lp_mint: Pubkey,
-    swap_in: Pubkey,
-    swap_out: Pubkey,
+    token_a: Pubkey,
+    token_b: Pubkey,
     user: &Keypair,
-    amount: N,
+    amount_a: f64,
+    amount_b: f64,
+    minimum_lp_token_amount: u64,
 ) {
-    let swap_in_mint: Mint = program.account(swap_in)?;
+    let amint: Mint = program.account(token_a)?;
+    let bmint: Mint = program.account(token_b)?;
+
     let tx = program
         .request()
-        .accounts(gfx_swap::accounts::Swap {
+        .accounts(gfx_swap::accounts::Deposit2 {
             pool: pool,
-
-            in_token_vault: get_associated_token_address(&pool, &swap_in),
-            out_token_vault: get_associated_token_address(&pool, &swap_out),
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+            token_a_vault: get_associated_token_address(&pool, &token_a),
+            token_b_vault: get_associated_token_address(&pool, &token_b),
             lp_token_mint: lp_mint,
-            fee_vault: get_associated_token_address(&pool, &lp_mint),
 
             user_wallet: user.pubkey(),
-            in_token_ata_user: get_associated_token_address(&user.pubkey(), &swap_in),
-            out_token_ata_user: get_associated_token_address(&user.pubkey(), &swap_out),
+            allowlist_entry: AllowlistEntry::address(&gfx_swap::ID, &pool, &user.pubkey()),
+            user_token_a_ata: get_associated_token_address(&user.pubkey(), &token_a),
+            user_token_b_ata: get_associated_token_address(&user.pubkey(), &token_b),
+            token_a_mint: token_a,
+            token_b_mint: token_b,
+            user_lp_ata: get_associated_token_address(&user.pubkey(), &lp_mint),
 
             token_program: spl_token::id(),
+            token_a_program: spl_token::id(),
+            token_b_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+            associated_token_program: spl_associated_token_account::id(),
         })
-        .args(gfx_swap::instruction::Swap {
-            amount_in: swap_in_mint.decimals.apply(amount),
-            minimum_amount_out: 0,
+        .args(gfx_swap::instruction::DepositExactTokens {
+            amount_a: amint.decimals.apply(amount_a),
+            amount_b: bmint.decimals.apply(amount_b),
+            minimum_lp_token_amount,
         })
         .signer(user)
         .send()
         .map_err(|e| e.canonicalize::<ErrorCode>())?;
 
-    println!("Swap: https://explorer.solana.com/tx/{}?cluster=devnet", tx);
+    println!(
+        "DepositExactTokens: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
 }
 
 #[throws(Error)]
-pub fn withdraw_fee_impl(program: &Program, pool: Pubkey, lp_mint: Pubkey, admin: &Keypair) {
+pub fn deposit_imbalanced_impl(
+    program: &Program,
+    pool: Pubkey,
+    lp_mint: Pubkey,
+    token_a: Pubkey,
+    token_b: Pubkey,
+    user: &Keypair,
+    amount_a: f64,
+    amount_b: f64,
+    minimum_lp_token_amount: u64,
+) {
+    let amint: Mint = program.account(token_a)?;
+    let bmint: Mint = program.account(token_b)?;
+
     let tx = program
         .request()
-        .accounts(gfx_swap::accounts::WithdrawFee {
+        .accounts(gfx_swap::accounts::Deposit2 {
             pool: pool,
-
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+            token_a_vault: get_associated_token_address(&pool, &token_a),
+            token_b_vault: get_associated_token_address(&pool, &token_b),
             lp_token_mint: lp_mint,
-            fee_vault: get_associated_token_address(&pool, &lp_mint),
-            admin_ata: get_associated_token_address(&admin.pubkey(), &lp_mint),
-            admin: admin.pubkey(),
+
+            user_wallet: user.pubkey(),
+            allowlist_entry: AllowlistEntry::address(&gfx_swap::ID, &pool, &user.pubkey()),
+            user_token_a_ata: get_associated_token_address(&user.pubkey(), &token_a),
+            user_token_b_ata: get_associated_token_address(&user.pubkey(), &token_b),
+            token_a_mint: token_a,
+            token_b_mint: token_b,
+            user_lp_ata: get_associated_token_address(&user.pubkey(), &lp_mint),
 
             token_program: spl_token::id(),
+            token_a_program: spl_token::id(),
+            token_b_program: spl_token::id(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+            associated_token_program: spl_associated_token_account::id(),
         })
-        .args(gfx_swap::instruction::WithdrawFee {})
-        .signer(admin)
+        .args(gfx_swap::instruction::DepositImbalanced {
+            amount_a: amint.decimals.apply(amount_a),
+            amount_b: bmint.decimals.apply(amount_b),
+            minimum_lp_token_amount,
+        })
+        .signer(user)
         .send()
         .map_err(|e| e.canonicalize::<ErrorCode>())?;
 
     println!(
-        "WithdrawFee: https://explorer.solana.com/tx/{}?cluster=devnet",
+        "DepositImbalanced: https://explorer.solana.com/tx/{}?cluster=devnet",
         tx
     );
 }
+
+#[throws(Error)]
+pub fn withdraw2_impl(
+    program: &Program,
+    pool: Pubkey,
+    lp_mint: Pubkey,
+    token_a: Pubkey,
+    token_b: Pubkey,
+    user: &Keypair,
+    lp_amount: f64,
+) {
+    let lpmint: Mint = program.account(lp_mint)?;
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::Withdraw2 {
+            pool: pool,
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+            token_a_vault: get_associated_token_address(&pool, &token_a),
+            token_b_vault: get_associated_token_address(&pool, &token_b),
+            lp_token_mint: lp_mint,
+            fee_vault: get_associated_token_address(&pool, &lp_mint),
+
+            user_wallet: user.pubkey(),
+            user_token_a_ata: get_associated_token_address(&user.pubkey(), &token_a),
+            user_token_b_ata: get_associated_token_address(&user.pubkey(), &token_b),
+            token_a_mint: token_a,
+            token_b_mint: token_b,
+            user_lp_ata: get_associated_token_address(&user.pubkey(), &lp_mint),
+
+            token_program: spl_token::id(),
+            token_a_program: spl_token::id(),
+            token_b_program: spl_token::id(),
+        })
+        .args(gfx_swap::instruction::Withdraw2 {
+            lp_token_amount: lpmint.decimals.apply(lp_amount),
+            minimum_token_a_amount: 0,
+            minimum_token_b_amount: 0,
+        })
+        .signer(user)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "Withdraw2: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn withdraw_imbalanced_impl(
+    program: &Program,
+    pool: Pubkey,
+    lp_mint: Pubkey,
+    token_a: Pubkey,
+    token_b: Pubkey,
+    user: &Keypair,
+    amount_a: f64,
+    amount_b: f64,
+    maximum_lp_token_amount: u64,
+) {
+    let amint: Mint = program.account(token_a)?;
+    let bmint: Mint = program.account(token_b)?;
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::Withdraw2 {
+            pool: pool,
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+            token_a_vault: get_associated_token_address(&pool, &token_a),
+            token_b_vault: get_associated_token_address(&pool, &token_b),
+            lp_token_mint: lp_mint,
+            fee_vault: get_associated_token_address(&pool, &lp_mint),
+
+            user_wallet: user.pubkey(),
+            user_token_a_ata: get_associated_token_address(&user.pubkey(), &token_a),
+            user_token_b_ata: get_associated_token_address(&user.pubkey(), &token_b),
+            token_a_mint: token_a,
+            token_b_mint: token_b,
+            user_lp_ata: get_associated_token_address(&user.pubkey(), &lp_mint),
+
+            token_program: spl_token::id(),
+            token_a_program: spl_token::id(),
+            token_b_program: spl_token::id(),
+        })
+        .args(gfx_swap::instruction::WithdrawImbalanced {
+            amount_a: amint.decimals.apply(amount_a),
+            amount_b: bmint.decimals.apply(amount_b),
+            maximum_lp_token_amount,
+        })
+        .signer(user)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "WithdrawImbalanced: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn withdraw_one_exact_lp_impl(
+    program: &Program,
+    pool: Pubkey,
+    lp_mint: Pubkey,
+    token_a: Pubkey,
+    token_b: Pubkey,
+    out_token: Pubkey,
+    user: &Keypair,
+    lp_amount: f64,
+    minimum_out_token_amount: u64,
+) {
+    let lpmint: Mint = program.account(lp_mint)?;
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::Withdraw1 {
+            pool: pool,
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+            token_a_vault: get_associated_token_address(&pool, &token_a),
+            token_b_vault: get_associated_token_address(&pool, &token_b),
+            lp_token_mint: lp_mint,
+            fee_vault: get_associated_token_address(&pool, &lp_mint),
+
+            user_wallet: user.pubkey(),
+            out_token_ata_user: get_associated_token_address(&user.pubkey(), &out_token),
+            out_token_mint: out_token,
+            user_lp_ata: get_associated_token_address(&user.pubkey(), &lp_mint),
+
+            token_program: spl_token::id(),
+            out_token_program: spl_token::id(),
+        })
+        .args(gfx_swap::instruction::WithdrawOneExactLp {
+            lp_token_amount: lpmint.decimals.apply(lp_amount),
+            minimum_out_token_amount,
+        })
+        .signer(user)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "WithdrawOneExactLp: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+// Pays the withdrawn tokens to the token accounts of `recipient`
+#[throws(Error)]
+pub fn withdraw2_to_impl(
+    program: &Program,
+    pool: Pubkey,
+    lp_mint: Pubkey,
+    token_a: Pubkey,
+    token_b: Pubkey,
+    user: &Keypair,
+    recipient: Pubkey,
+    lp_amount: f64,
+) {
+    let lpmint: Mint = program.account(lp_mint)?;
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::Withdraw2 {
+            pool: pool,
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+            token_a_vault: get_associated_token_address(&pool, &token_a),
+            token_b_vault: get_associated_token_address(&pool, &token_b),
+            lp_token_mint: lp_mint,
+            fee_vault: get_associated_token_address(&pool, &lp_mint),
+
+            user_wallet: user.pubkey(),
+            user_token_a_ata: get_associated_token_address(&recipient, &token_a),
+            user_token_b_ata: get_associated_token_address(&recipient, &token_b),
+            token_a_mint: token_a,
+            token_b_mint: token_b,
+            user_lp_ata: get_associated_token_address(&user.pubkey(), &lp_mint),
+
+            token_program: spl_token::id(),
+            token_a_program: spl_token::id(),
+            token_b_program: spl_token::id(),
+        })
+        .args(gfx_swap::instruction::Withdraw2To {
+            lp_token_amount: lpmint.decimals.apply(lp_amount),
+            minimum_token_a_amount: 0,
+            minimum_token_b_amount: 0,
+            recipient,
+        })
+        .signer(user)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "Withdraw2To: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn swap_impl<N: AsPrimitive<f64>>(
+    program: &Program,
+    pool: Pubkey,
+    lp_mint: Pubkey,
+    swap_in: Pubkey,
+    swap_out: Pubkey,
+    user: &Keypair,
+    amount: N,
+) {
+    let swap_in_mint: Mint = program.account(swap_in)?;
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::Swap {
+            pool: pool,
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+
+            in_token_vault: get_associated_token_address(&pool, &swap_in),
+            out_token_vault: get_associated_token_address(&pool, &swap_out),
+            in_token_mint: swap_in,
+            out_token_mint: swap_out,
+            lp_token_mint: lp_mint,
+            fee_vault: get_associated_token_address(&pool, &lp_mint),
+
+            user_wallet: user.pubkey(),
+            allowlist_entry: AllowlistEntry::address(&gfx_swap::ID, &pool, &user.pubkey()),
+            in_token_ata_user: get_associated_token_address(&user.pubkey(), &swap_in),
+            out_token_ata_user: get_associated_token_address(&user.pubkey(), &swap_out),
+
+            token_program: spl_token::id(),
+            in_token_program: spl_token::id(),
+            out_token_program: spl_token::id(),
+            system_program: system_program::id(),
+        })
+        .args(gfx_swap::instruction::Swap {
+            amount_in: swap_in_mint.decimals.apply(amount),
+            minimum_amount_out: 0,
+        })
+        .signer(user)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!("Swap: https://explorer.solana.com/tx/{}?cluster=devnet", tx);
+}
+
+// Pays the output of the swap to the token account of `recipient`
+#[throws(Error)]
+pub fn swap_to_impl<N: AsPrimitive<f64>>(
+    program: &Program,
+    pool: Pubkey,
+    lp_mint: Pubkey,
+    swap_in: Pubkey,
+    swap_out: Pubkey,
+    user: &Keypair,
+    recipient: Pubkey,
+    amount: N,
+) {
+    let swap_in_mint: Mint = program.account(swap_in)?;
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::Swap {
+            pool: pool,
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+
+            in_token_vault: get_associated_token_address(&pool, &swap_in),
+            out_token_vault: get_associated_token_address(&pool, &swap_out),
+            in_token_mint: swap_in,
+            out_token_mint: swap_out,
+            lp_token_mint: lp_mint,
+            fee_vault: get_associated_token_address(&pool, &lp_mint),
+
+            user_wallet: user.pubkey(),
+            allowlist_entry: AllowlistEntry::address(&gfx_swap::ID, &pool, &user.pubkey()),
+            in_token_ata_user: get_associated_token_address(&user.pubkey(), &swap_in),
+            out_token_ata_user: get_associated_token_address(&recipient, &swap_out),
+
+            token_program: spl_token::id(),
+            in_token_program: spl_token::id(),
+            out_token_program: spl_token::id(),
+            system_program: system_program::id(),
+        })
+        .args(gfx_swap::instruction::SwapTo {
+            amount_in: swap_in_mint.decimals.apply(amount),
+            minimum_amount_out: 0,
+            recipient,
+        })
+        .signer(user)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "SwapTo: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+// Approves `delegate` to move `amount` out of the token account of the owner
+#[throws(Error)]
+pub fn approve_impl(
+    program: &Program,
+    owner: &Keypair,
+    token: Pubkey,
+    delegate: Pubkey,
+    amount: u64,
+) {
+    let rpc_client = program.rpc();
+
+    let ix = spl_token::instruction::approve(
+        &spl_token::id(),
+        &get_associated_token_address(&owner.pubkey(), &token),
+        &delegate,
+        &owner.pubkey(),
+        &[],
+        amount,
+    )?;
+    let tx = rpc_client.send_and_confirm_transaction(&Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[owner],
+        rpc_client.get_recent_blockhash()?.0,
+    ))?;
+
+    println!(
+        "Approve: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+// The delegate swaps the tokens of the owner, the output goes back to the owner
+#[throws(Error)]
+pub fn delegated_swap_impl(
+    program: &Program,
+    pool: Pubkey,
+    lp_mint: Pubkey,
+    swap_in: Pubkey,
+    swap_out: Pubkey,
+    owner: Pubkey,
+    delegate: &Keypair,
+    amount_in: u64,
+) {
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::Swap {
+            pool: pool,
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+
+            in_token_vault: get_associated_token_address(&pool, &swap_in),
+            out_token_vault: get_associated_token_address(&pool, &swap_out),
+            in_token_mint: swap_in,
+            out_token_mint: swap_out,
+            lp_token_mint: lp_mint,
+            fee_vault: get_associated_token_address(&pool, &lp_mint),
+
+            user_wallet: delegate.pubkey(),
+            allowlist_entry: AllowlistEntry::address(&gfx_swap::ID, &pool, &delegate.pubkey()),
+            in_token_ata_user: get_associated_token_address(&owner, &swap_in),
+            out_token_ata_user: get_associated_token_address(&owner, &swap_out),
+
+            token_program: spl_token::id(),
+            in_token_program: spl_token::id(),
+            out_token_program: spl_token::id(),
+            system_program: system_program::id(),
+        })
+        .args(gfx_swap::instruction::SwapTo {
+            amount_in,
+            minimum_amount_out: 0,
+            recipient: owner,
+        })
+        .signer(delegate)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "DelegatedSwap: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+// Swaps the tokens of the vault PDA of `mock_pda_swapper`, which signs the CPI into gfx-swap
+#[throws(Error)]
+pub fn pda_swap_impl(
+    program: &Program,
+    pool: Pubkey,
+    lp_mint: Pubkey,
+    swap_in: Pubkey,
+    swap_out: Pubkey,
+    amount_in: u64,
+) {
+    let (vault, vault_bump) = mock_pda_swapper::vault_address_with_bump();
+
+    let tx = program
+        .request()
+        .accounts(mock_pda_swapper::accounts::SwapAsVault {
+            vault,
+            pool,
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+            in_token_vault: get_associated_token_address(&pool, &swap_in),
+            out_token_vault: get_associated_token_address(&pool, &swap_out),
+            lp_token_mint: lp_mint,
+            fee_vault: get_associated_token_address(&pool, &lp_mint),
+            in_token_ata_vault: get_associated_token_address(&vault, &swap_in),
+            out_token_ata_vault: get_associated_token_address(&vault, &swap_out),
+            allowlist_entry: AllowlistEntry::address(&gfx_swap::ID, &pool, &vault),
+            gfx_swap_program: gfx_swap::ID,
+            system_program: system_program::id(),
+            token_program: spl_token::id(),
+        })
+        .args(mock_pda_swapper::instruction::SwapAsVault {
+            vault_bump,
+            amount_in,
+            minimum_amount_out: 0,
+        })
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "PdaSwap: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn swap_with_deadline_impl<N: AsPrimitive<f64>>(
+    program: &Program,
+    pool: Pubkey,
+    lp_mint: Pubkey,
+    swap_in: Pubkey,
+    swap_out: Pubkey,
+    user: &Keypair,
+    amount: N,
+    deadline: Deadline,
+) {
+    let swap_in_mint: Mint = program.account(swap_in)?;
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::Swap {
+            pool: pool,
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+
+            in_token_vault: get_associated_token_address(&pool, &swap_in),
+            out_token_vault: get_associated_token_address(&pool, &swap_out),
+            in_token_mint: swap_in,
+            out_token_mint: swap_out,
+            lp_token_mint: lp_mint,
+            fee_vault: get_associated_token_address(&pool, &lp_mint),
+
+            user_wallet: user.pubkey(),
+            allowlist_entry: AllowlistEntry::address(&gfx_swap::ID, &pool, &user.pubkey()),
+            in_token_ata_user: get_associated_token_address(&user.pubkey(), &swap_in),
+            out_token_ata_user: get_associated_token_address(&user.pubkey(), &swap_out),
+
+            token_program: spl_token::id(),
+            in_token_program: spl_token::id(),
+            out_token_program: spl_token::id(),
+            system_program: system_program::id(),
+        })
+        .args(gfx_swap::instruction::SwapWithDeadline {
+            amount_in: swap_in_mint.decimals.apply(amount),
+            minimum_amount_out: 0,
+            deadline,
+        })
+        .signer(user)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "SwapWithDeadline: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+// Creates the WSOL account of the owner if it does not exist yet, and wraps `lamports` into it.
+#[throws(Error)]
+pub fn wrap_sol_impl(program: &Program, owner: &Keypair, lamports: u64) -> Pubkey {
+    let native_mint = spl_token::native_mint::id();
+    let wsol_ata = get_associated_token_address(&owner.pubkey(), &native_mint);
+    let rpc_client = program.rpc();
+
+    let mut instructions = vec![];
+    if rpc_client.get_account(&wsol_ata).is_err() {
+        instructions.push(create_associated_token_account(
+            &owner.pubkey(),
+            &owner.pubkey(),
+            &native_mint,
+        ));
+    }
+    if lamports > 0 {
+        instructions.push(system_instruction::transfer(
+            &owner.pubkey(),
+            &wsol_ata,
+            lamports,
+        ));
+        instructions.push(spl_token::instruction::sync_native(
+            &spl_token::id(),
+            &wsol_ata,
+        )?);
+    }
+
+    if !instructions.is_empty() {
+        let tx = rpc_client.send_and_confirm_transaction(&Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&owner.pubkey()),
+            &[owner],
+            rpc_client.get_recent_blockhash()?.0,
+        ))?;
+
+        println!(
+            "WrapSol: https://explorer.solana.com/tx/{}?cluster=devnet",
+            tx
+        );
+    }
+
+    wsol_ata
+}
+
+// The temporary WSOL account the native SOL instructions create for the user
+pub fn temp_wsol_address(user: &Pubkey) -> Pubkey {
+    TempWsol::get_address(&gfx_swap::ID, &user.to_bytes())
+}
+
+fn temp_wsol_accounts(user: &Pubkey) -> gfx_swap::accounts::TempWsol {
+    gfx_swap::accounts::TempWsol {
+        wsol_account: temp_wsol_address(user),
+        wsol_mint: spl_token::native_mint::id(),
+        user_wallet: *user,
+        system_program: system_program::id(),
+        token_program: spl_token::id(),
+        rent: sysvar::rent::id(),
+    }
+}
+
+// The token account of the user for `mint` in the native SOL instructions
+fn native_user_ata(user: &Pubkey, mint: &Pubkey) -> Pubkey {
+    if *mint == spl_token::native_mint::id() {
+        temp_wsol_address(user)
+    } else {
+        get_associated_token_address(user, mint)
+    }
+}
+
+#[throws(Error)]
+pub fn deposit2_native_impl(
+    program: &Program,
+    pool: Pubkey,
+    lp_mint: Pubkey,
+    token_a: Pubkey,
+    token_b: Pubkey,
+    user: &Keypair,
+    lp_amount: f64,
+    maximum_token_a_amount: u64,
+    maximum_token_b_amount: u64,
+) {
+    let lpmint: Mint = program.account(lp_mint)?;
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::Deposit2Native {
+            temp_wsol: temp_wsol_accounts(&user.pubkey()),
+            deposit: gfx_swap::accounts::Deposit2 {
+                pool: pool,
+                global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+                token_a_vault: get_associated_token_address(&pool, &token_a),
+                token_b_vault: get_associated_token_address(&pool, &token_b),
+                lp_token_mint: lp_mint,
+
+                user_wallet: user.pubkey(),
+                allowlist_entry: AllowlistEntry::address(&gfx_swap::ID, &pool, &user.pubkey()),
+                user_token_a_ata: native_user_ata(&user.pubkey(), &token_a),
+                user_token_b_ata: native_user_ata(&user.pubkey(), &token_b),
+                token_a_mint: token_a,
+                token_b_mint: token_b,
+                user_lp_ata: get_associated_token_address(&user.pubkey(), &lp_mint),
+
+                token_program: spl_token::id(),
+                token_a_program: spl_token::id(),
+                token_b_program: spl_token::id(),
+                system_program: system_program::id(),
+                rent: sysvar::rent::id(),
+                associated_token_program: spl_associated_token_account::id(),
+            },
+        })
+        .args(gfx_swap::instruction::Deposit2Native {
+            lp_token_amount: lpmint.decimals.apply(lp_amount),
+            maximum_token_a_amount,
+            maximum_token_b_amount,
+        })
+        .signer(user)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "Deposit2Native: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn withdraw2_native_impl(
+    program: &Program,
+    pool: Pubkey,
+    lp_mint: Pubkey,
+    token_a: Pubkey,
+    token_b: Pubkey,
+    user: &Keypair,
+    lp_amount: f64,
+) {
+    let lpmint: Mint = program.account(lp_mint)?;
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::Withdraw2Native {
+            temp_wsol: temp_wsol_accounts(&user.pubkey()),
+            withdraw: gfx_swap::accounts::Withdraw2 {
+                pool: pool,
+                global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+                token_a_vault: get_associated_token_address(&pool, &token_a),
+                token_b_vault: get_associated_token_address(&pool, &token_b),
+                lp_token_mint: lp_mint,
+                fee_vault: get_associated_token_address(&pool, &lp_mint),
+
+                user_wallet: user.pubkey(),
+                user_token_a_ata: native_user_ata(&user.pubkey(), &token_a),
+                user_token_b_ata: native_user_ata(&user.pubkey(), &token_b),
+                token_a_mint: token_a,
+                token_b_mint: token_b,
+                user_lp_ata: get_associated_token_address(&user.pubkey(), &lp_mint),
+
+                token_program: spl_token::id(),
+                token_a_program: spl_token::id(),
+                token_b_program: spl_token::id(),
+            },
+        })
+        .args(gfx_swap::instruction::Withdraw2Native {
+            lp_token_amount: lpmint.decimals.apply(lp_amount),
+            minimum_token_a_amount: 0,
+            minimum_token_b_amount: 0,
+        })
+        .signer(user)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "Withdraw2Native: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+// `amount_in` is in the smallest unit, lamports for SOL
+#[throws(Error)]
+pub fn swap_native_impl(
+    program: &Program,
+    pool: Pubkey,
+    lp_mint: Pubkey,
+    swap_in: Pubkey,
+    swap_out: Pubkey,
+    user: &Keypair,
+    amount_in: u64,
+) {
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::SwapNative {
+            temp_wsol: temp_wsol_accounts(&user.pubkey()),
+            swap: gfx_swap::accounts::Swap {
+                pool: pool,
+                global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+
+                in_token_vault: get_associated_token_address(&pool, &swap_in),
+                out_token_vault: get_associated_token_address(&pool, &swap_out),
+                in_token_mint: swap_in,
+                out_token_mint: swap_out,
+                lp_token_mint: lp_mint,
+                fee_vault: get_associated_token_address(&pool, &lp_mint),
+
+                user_wallet: user.pubkey(),
+                allowlist_entry: AllowlistEntry::address(&gfx_swap::ID, &pool, &user.pubkey()),
+                in_token_ata_user: native_user_ata(&user.pubkey(), &swap_in),
+                out_token_ata_user: native_user_ata(&user.pubkey(), &swap_out),
+
+                token_program: spl_token::id(),
+                in_token_program: spl_token::id(),
+                out_token_program: spl_token::id(),
+                system_program: system_program::id(),
+            },
+        })
+        .args(gfx_swap::instruction::SwapNative {
+            amount_in,
+            minimum_amount_out: 0,
+        })
+        .signer(user)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "SwapNative: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+// One hop of a route: (pool, lp mint, token in, token out)
+pub type RouteHop = (Pubkey, Pubkey, Pubkey, Pubkey);
+
+#[throws(Error)]
+pub fn route_swap_impl(
+    program: &Program,
+    route: &[RouteHop],
+    user: &Keypair,
+    amount_in: u64,
+    minimum_final_out: u64,
+) {
+    let (_, _, token_in, _) = route[0];
+    let (_, _, _, token_out) = route[route.len() - 1];
+
+    let mut hops = vec![];
+    for &(pool, lp_mint, hop_in, hop_out) in route {
+        hops.extend([
+            AccountMeta::new(pool, false),
+            AccountMeta::new(get_associated_token_address(&pool, &hop_in), false),
+            AccountMeta::new(get_associated_token_address(&pool, &hop_out), false),
+            AccountMeta::new(lp_mint, false),
+            AccountMeta::new(get_associated_token_address(&pool, &lp_mint), false),
+            AccountMeta::new_readonly(
+                AllowlistEntry::address(&gfx_swap::ID, &pool, &user.pubkey()),
+                false,
+            ),
+            AccountMeta::new_readonly(hop_out, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ]);
+    }
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::RouteSwap {
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+            in_token_ata_user: get_associated_token_address(&user.pubkey(), &token_in),
+            in_token_mint: token_in,
+            out_token_ata_user: get_associated_token_address(&user.pubkey(), &token_out),
+            user_wallet: user.pubkey(),
+            token_program: spl_token::id(),
+            in_token_program: spl_token::id(),
+        })
+        .accounts(hops)
+        .args(gfx_swap::instruction::RouteSwap {
+            amount_in,
+            minimum_final_out,
+            hops: route.len() as u8,
+        })
+        .signer(user)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "RouteSwap: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+// Borrows from the pool through the mock receiver, `data` is passed on to it, see `mock_flash_receiver`
+#[throws(Error)]
+pub fn flash_loan_impl(
+    program: &Program,
+    pool: Pubkey,
+    lp_mint: Pubkey,
+    borrower: &Keypair,
+    amount_1: u64,
+    amount_2: u64,
+    data: Vec<u8>,
+    remaining_accounts: Vec<AccountMeta>,
+) {
+    let pool_account: Pool = program.account(pool)?;
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::FlashLoan {
+            pool,
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+            token_vault_1: pool_account.token_vault_1,
+            token_vault_2: pool_account.token_vault_2,
+            token_mint_1: pool_account.token_mint_1,
+            token_mint_2: pool_account.token_mint_2,
+            lp_token_mint: lp_mint,
+            fee_vault: get_associated_token_address(&pool, &lp_mint),
+            borrower_token_1: get_associated_token_address(
+                &borrower.pubkey(),
+                &pool_account.token_mint_1,
+            ),
+            borrower_token_2: get_associated_token_address(
+                &borrower.pubkey(),
+                &pool_account.token_mint_2,
+            ),
+            receiver_program: mock_flash_receiver::ID,
+            borrower: borrower.pubkey(),
+            allowlist_entry: AllowlistEntry::address(&gfx_swap::ID, &pool, &borrower.pubkey()),
+            token_program: spl_token::id(),
+            token_program_1: spl_token::id(),
+            token_program_2: spl_token::id(),
+        })
+        .accounts(remaining_accounts)
+        .args(gfx_swap::instruction::FlashLoan {
+            amount_1,
+            amount_2,
+            data,
+        })
+        .signer(borrower)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "FlashLoan: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn place_order_impl(
+    program: &Program,
+    pool: Pubkey,
+    in_mint: Pubkey,
+    out_mint: Pubkey,
+    maker: &Keypair,
+    id: u64,
+    amount_in: f64,
+    minimum_amount_out: f64,
+    bounty_bps: u16,
+) -> Pubkey {
+    let in_decimals = program.account::<Mint>(in_mint)?.decimals;
+    let out_decimals = program.account::<Mint>(out_mint)?.decimals;
+    let (order, bump) = LimitOrder::address_with_bump(&gfx_swap::ID, &pool, &maker.pubkey(), id);
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::PlaceOrder {
+            pool,
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+            order,
+            in_mint,
+            escrow: get_associated_token_address(&order, &in_mint),
+            maker_in_token_ata: get_associated_token_address(&maker.pubkey(), &in_mint),
+            maker: maker.pubkey(),
+            allowlist_entry: AllowlistEntry::address(&gfx_swap::ID, &pool, &maker.pubkey()),
+            associated_token_program: spl_associated_token_account::id(),
+            token_program: spl_token::id(),
+            rent: sysvar::rent::id(),
+            system_program: system_program::id(),
+        })
+        .args(gfx_swap::instruction::PlaceOrder {
+            id,
+            bump,
+            amount_in: in_decimals.apply(amount_in),
+            minimum_amount_out: out_decimals.apply(minimum_amount_out),
+            bounty_bps,
+        })
+        .signer(maker)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "PlaceOrder: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+
+    order
+}
+
+#[throws(Error)]
+pub fn cancel_order_impl(program: &Program, order: Pubkey, maker: &Keypair) {
+    let order_account: LimitOrder = program.account(order)?;
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::CancelOrder {
+            order,
+            escrow: get_associated_token_address(&order, &order_account.in_mint),
+            in_mint: order_account.in_mint,
+            maker_in_token_ata: get_associated_token_address(
+                &maker.pubkey(),
+                &order_account.in_mint,
+            ),
+            maker: maker.pubkey(),
+            token_program: spl_token::id(),
+        })
+        .args(gfx_swap::instruction::CancelOrder {})
+        .signer(maker)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "CancelOrder: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+// The keeper receives the bounties in its own token accounts
+#[throws(Error)]
+pub fn fill_orders_impl(program: &Program, pool: Pubkey, keeper: &Keypair, orders: &[Pubkey]) {
+    let pool_account: Pool = program.account(pool)?;
+
+    let mut remaining_accounts = vec![];
+    for order in orders {
+        let order_account: LimitOrder = program.account(*order)?;
+        remaining_accounts.extend([
+            AccountMeta::new(*order, false),
+            AccountMeta::new(
+                get_associated_token_address(order, &order_account.in_mint),
+                false,
+            ),
+            AccountMeta::new(order_account.maker, false),
+            AccountMeta::new(
+                get_associated_token_address(&order_account.maker, &order_account.out_mint),
+                false,
+            ),
+        ]);
+    }
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::FillOrders {
+            pool,
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+            token_vault_1: pool_account.token_vault_1,
+            token_vault_2: pool_account.token_vault_2,
+            token_mint_1: pool_account.token_mint_1,
+            token_mint_2: pool_account.token_mint_2,
+            lp_token_mint: pool_account.mint,
+            fee_vault: pool_account.fee_vault,
+            keeper_token_1_ata: get_associated_token_address(
+                &keeper.pubkey(),
+                &pool_account.token_mint_1,
+            ),
+            keeper_token_2_ata: get_associated_token_address(
+                &keeper.pubkey(),
+                &pool_account.token_mint_2,
+            ),
+            keeper: keeper.pubkey(),
+            token_program: spl_token::id(),
+            token_program_1: spl_token::id(),
+            token_program_2: spl_token::id(),
+        })
+        .accounts(remaining_accounts)
+        .args(gfx_swap::instruction::FillOrders {})
+        .signer(keeper)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "FillOrders: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn create_dca_order_impl(
+    program: &Program,
+    pool: Pubkey,
+    in_mint: Pubkey,
+    out_mint: Pubkey,
+    owner: &Keypair,
+    id: u64,
+    total_amount: f64,
+    slice_amount: f64,
+    interval: i64,
+    minimum_slice_amount_out: f64,
+    keeper_fee_bps: u16,
+) -> Pubkey {
+    let in_decimals = program.account::<Mint>(in_mint)?.decimals;
+    let out_decimals = program.account::<Mint>(out_mint)?.decimals;
+    let (order, bump) = DcaOrder::address_with_bump(&gfx_swap::ID, &pool, &owner.pubkey(), id);
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::CreateDcaOrder {
+            pool,
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+            order,
+            in_mint,
+            escrow: get_associated_token_address(&order, &in_mint),
+            owner_in_token_ata: get_associated_token_address(&owner.pubkey(), &in_mint),
+            owner: owner.pubkey(),
+            allowlist_entry: AllowlistEntry::address(&gfx_swap::ID, &pool, &owner.pubkey()),
+            associated_token_program: spl_associated_token_account::id(),
+            token_program: spl_token::id(),
+            rent: sysvar::rent::id(),
+            system_program: system_program::id(),
+        })
+        .args(gfx_swap::instruction::CreateDcaOrder {
+            id,
+            bump,
+            schedule: DcaSchedule {
+                total_amount: in_decimals.apply(total_amount),
+                slice_amount: in_decimals.apply(slice_amount),
+                interval,
+                minimum_slice_amount_out: out_decimals.apply(minimum_slice_amount_out),
+                keeper_fee_bps,
+            },
+        })
+        .signer(owner)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "CreateDcaOrder: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+
+    order
+}
+
+// The keeper receives its fee in its own token account
+#[throws(Error)]
+pub fn execute_dca_slice_impl(program: &Program, order: Pubkey, keeper: &Keypair) {
+    let order_account: DcaOrder = program.account(order)?;
+    let pool_account: Pool = program.account(order_account.pool)?;
+    let (in_token_vault, out_token_vault) = if order_account.in_mint == pool_account.token_mint_1 {
+        (pool_account.token_vault_1, pool_account.token_vault_2)
+    } else {
+        (pool_account.token_vault_2, pool_account.token_vault_1)
+    };
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::ExecuteDcaSlice {
+            pool: order_account.pool,
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+            in_token_vault,
+            out_token_vault,
+            in_token_mint: order_account.in_mint,
+            out_token_mint: order_account.out_mint,
+            lp_token_mint: pool_account.mint,
+            fee_vault: pool_account.fee_vault,
+            order,
+            escrow: get_associated_token_address(&order, &order_account.in_mint),
+            owner_out_token_ata: get_associated_token_address(
+                &order_account.owner,
+                &order_account.out_mint,
+            ),
+            keeper_out_token_ata: get_associated_token_address(
+                &keeper.pubkey(),
+                &order_account.out_mint,
+            ),
+            keeper: keeper.pubkey(),
+            token_program: spl_token::id(),
+            in_token_program: spl_token::id(),
+            out_token_program: spl_token::id(),
+        })
+        .args(gfx_swap::instruction::ExecuteDcaSlice {})
+        .signer(keeper)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "ExecuteDcaSlice: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn cancel_dca_order_impl(program: &Program, order: Pubkey, owner: &Keypair) {
+    let order_account: DcaOrder = program.account(order)?;
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::CancelDcaOrder {
+            order,
+            escrow: get_associated_token_address(&order, &order_account.in_mint),
+            in_mint: order_account.in_mint,
+            owner_in_token_ata: get_associated_token_address(
+                &owner.pubkey(),
+                &order_account.in_mint,
+            ),
+            owner: owner.pubkey(),
+            token_program: spl_token::id(),
+        })
+        .args(gfx_swap::instruction::CancelDcaOrder {})
+        .signer(owner)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "CancelDcaOrder: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn withdraw_fee_impl(program: &Program, pool: Pubkey, lp_mint: Pubkey, authority: &Keypair) {
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::WithdrawFee {
+            pool: pool,
+
+            lp_token_mint: lp_mint,
+            fee_vault: get_associated_token_address(&pool, &lp_mint),
+            recipient_ata: get_associated_token_address(&authority.pubkey(), &lp_mint),
+            recipient: authority.pubkey(),
+            authority: authority.pubkey(),
+
+            token_program: spl_token::id(),
+        })
+        .accounts(approvals(&[authority]))
+        .args(gfx_swap::instruction::WithdrawFee {})
+        .signer(authority)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "WithdrawFee: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn delist_pool_impl(program: &Program, pool: Pubkey, registry_page: Pubkey, admin: &Keypair) {
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::DelistPool {
+            pool,
+            registry_page,
+            admin: admin.pubkey(),
+        })
+        .accounts(approvals(&[admin]))
+        .args(gfx_swap::instruction::DelistPool {})
+        .signer(admin)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "DelistPool: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn propose_admin_impl(program: &Program, pool: Pubkey, admin: &Keypair, new_admin: Pubkey) {
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::ProposeAdmin {
+            pool,
+            admin: admin.pubkey(),
+        })
+        .accounts(approvals(&[admin]))
+        .args(gfx_swap::instruction::ProposeAdmin { new_admin })
+        .signer(admin)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "ProposeAdmin: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn accept_admin_impl(program: &Program, pool: Pubkey, new_admin: &Keypair) {
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::AcceptAdmin {
+            pool,
+            new_admin: new_admin.pubkey(),
+        })
+        .accounts(approvals(&[new_admin]))
+        .args(gfx_swap::instruction::AcceptAdmin {})
+        .signer(new_admin)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "AcceptAdmin: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn cancel_admin_proposal_impl(program: &Program, pool: Pubkey, admin: &Keypair) {
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::CancelAdminProposal {
+            pool,
+            admin: admin.pubkey(),
+        })
+        .accounts(approvals(&[admin]))
+        .args(gfx_swap::instruction::CancelAdminProposal {})
+        .signer(admin)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "CancelAdminProposal: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn config_pool_impl(program: &Program, pool: Pubkey, authority: &Keypair, config: PoolConfig) {
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::ConfigPool {
+            pool,
+            authority: authority.pubkey(),
+        })
+        .accounts(approvals(&[authority]))
+        .args(gfx_swap::instruction::ConfigPool { config })
+        .signer(authority)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "ConfigPool: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn set_roles_impl(program: &Program, pool: Pubkey, admin: &Keypair, roles: PoolRoles) {
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::SetRoles {
+            pool,
+            admin: admin.pubkey(),
+        })
+        .accounts(approvals(&[admin]))
+        .args(gfx_swap::instruction::SetRoles { roles })
+        .signer(admin)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "SetRoles: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn create_admin_multisig_impl(
+    program: &Program,
+    payer: &Keypair,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+) -> Pubkey {
+    let seed = Keypair::new().pubkey().to_bytes();
+    let (multisig, bump) = AdminMultisig::get_address_with_bump(&gfx_swap::ID, &seed);
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::CreateAdminMultisig {
+            multisig,
+            payer: payer.pubkey(),
+            rent: sysvar::rent::id(),
+            system_program: system_program::id(),
+        })
+        .args(gfx_swap::instruction::CreateAdminMultisig {
+            seed,
+            bump,
+            signers,
+            threshold,
+        })
+        .signer(payer)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "CreateAdminMultisig: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+
+    multisig
+}
+
+#[throws(Error)]
+pub fn update_admin_multisig_impl(
+    program: &Program,
+    multisig: Pubkey,
+    approvers: &[&Keypair],
+    signers: Vec<Pubkey>,
+    threshold: u8,
+) {
+    let mut request = program
+        .request()
+        .accounts(gfx_swap::accounts::UpdateAdminMultisig { multisig })
+        .accounts(approvals(approvers))
+        .args(gfx_swap::instruction::UpdateAdminMultisig { signers, threshold });
+    for approver in approvers {
+        request = request.signer(*approver);
+    }
+    let tx = request.send().map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "UpdateAdminMultisig: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+// Accept the admin of a pool on behalf of a multisig
+#[throws(Error)]
+pub fn accept_admin_multisig_impl(
+    program: &Program,
+    pool: Pubkey,
+    multisig: Pubkey,
+    approvers: &[&Keypair],
+) {
+    let mut request = program
+        .request()
+        .accounts(gfx_swap::accounts::AcceptAdmin {
+            pool,
+            new_admin: multisig,
+        })
+        .accounts(approvals(approvers))
+        .args(gfx_swap::instruction::AcceptAdmin {});
+    for approver in approvers {
+        request = request.signer(*approver);
+    }
+    let tx = request.send().map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "AcceptAdmin: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+// Apply a pool config on behalf of a multisig
+#[throws(Error)]
+pub fn config_pool_multisig_impl(
+    program: &Program,
+    pool: Pubkey,
+    multisig: Pubkey,
+    approvers: &[&Keypair],
+    config: PoolConfig,
+) {
+    let mut request = program
+        .request()
+        .accounts(gfx_swap::accounts::ConfigPool {
+            pool,
+            authority: multisig,
+        })
+        .accounts(approvals(approvers))
+        .args(gfx_swap::instruction::ConfigPool { config });
+    for approver in approvers {
+        request = request.signer(*approver);
+    }
+    let tx = request.send().map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "ConfigPool: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn queue_config_impl(
+    program: &Program,
+    pool: Pubkey,
+    authority: &Keypair,
+    config: PoolConfig,
+) -> Pubkey {
+    let (queued_config, bump) =
+        QueuedConfig::get_address_with_bump(&gfx_swap::ID, &pool.to_bytes());
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::QueueConfig {
+            pool,
+            queued_config,
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+            authority: authority.pubkey(),
+            payer: program.payer(),
+            clock: sysvar::clock::id(),
+            rent: sysvar::rent::id(),
+            system_program: system_program::id(),
+        })
+        .accounts(approvals(&[authority]))
+        .args(gfx_swap::instruction::QueueConfig { config, bump })
+        .signer(authority)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "QueueConfig: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+
+    queued_config
+}
+
+// Passes `authority` without its signature, like anyone could
+#[throws(Error)]
+pub fn queue_config_unsigned_impl(
+    program: &Program,
+    pool: Pubkey,
+    authority: Pubkey,
+    config: PoolConfig,
+) {
+    let (queued_config, bump) =
+        QueuedConfig::get_address_with_bump(&gfx_swap::ID, &pool.to_bytes());
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::QueueConfig {
+            pool,
+            queued_config,
+            global_config: GlobalConfig::get_address(&gfx_swap::ID, &[]),
+            authority,
+            payer: program.payer(),
+            clock: sysvar::clock::id(),
+            rent: sysvar::rent::id(),
+            system_program: system_program::id(),
+        })
+        .args(gfx_swap::instruction::QueueConfig { config, bump })
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "QueueConfig: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn execute_config_impl(program: &Program, pool: Pubkey) {
+    let queued_config = QueuedConfig::get_address(&gfx_swap::ID, &pool.to_bytes());
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::ExecuteConfig {
+            pool,
+            queued_config,
+            payer: program.payer(),
+            clock: sysvar::clock::id(),
+        })
+        .args(gfx_swap::instruction::ExecuteConfig {})
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "ExecuteConfig: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn cancel_config_impl(program: &Program, pool: Pubkey, authority: &Keypair) {
+    let queued_config = QueuedConfig::get_address(&gfx_swap::ID, &pool.to_bytes());
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::CancelConfig {
+            pool,
+            queued_config,
+            payer: program.payer(),
+            authority: authority.pubkey(),
+        })
+        .accounts(approvals(&[authority]))
+        .args(gfx_swap::instruction::CancelConfig {})
+        .signer(authority)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "CancelConfig: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn add_to_allowlist_impl(program: &Program, pool: Pubkey, admin: &Keypair, wallet: Pubkey) {
+    let (allowlist_entry, bump) = AllowlistEntry::address_with_bump(&gfx_swap::ID, &pool, &wallet);
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::AddToAllowlist {
+            pool,
+            allowlist_entry,
+            wallet,
+            admin: admin.pubkey(),
+            payer: program.payer(),
+            rent: sysvar::rent::id(),
+            system_program: system_program::id(),
+        })
+        .accounts(approvals(&[admin]))
+        .args(gfx_swap::instruction::AddToAllowlist { bump })
+        .signer(admin)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "AddToAllowlist: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn remove_from_allowlist_impl(
+    program: &Program,
+    pool: Pubkey,
+    admin: &Keypair,
+    wallet: Pubkey,
+) {
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::RemoveFromAllowlist {
+            pool,
+            allowlist_entry: AllowlistEntry::address(&gfx_swap::ID, &pool, &wallet),
+            payer: program.payer(),
+            admin: admin.pubkey(),
+        })
+        .accounts(approvals(&[admin]))
+        .args(gfx_swap::instruction::RemoveFromAllowlist {})
+        .signer(admin)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "RemoveFromAllowlist: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn create_farm_impl(
+    program: &Program,
+    pool: Pubkey,
+    reward_mint: Pubkey,
+    admin: &Keypair,
+    emission_rate: u64,
+) -> Pubkey {
+    let pool_account: Pool = program.account(pool)?;
+    let (farm, bump) = Farm::address_with_bump(&gfx_swap::ID, &pool);
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::CreateFarm {
+            pool,
+            farm,
+            reward_mint,
+            reward_vault: get_associated_token_address(&farm, &reward_mint),
+            lp_token_mint: pool_account.mint,
+            lp_vault: get_associated_token_address(&farm, &pool_account.mint),
+            admin: admin.pubkey(),
+            payer: program.payer(),
+            associated_token_program: spl_associated_token_account::id(),
+            token_program: spl_token::id(),
+            rent: sysvar::rent::id(),
+            system_program: system_program::id(),
+        })
+        .accounts(approvals(&[admin]))
+        .args(gfx_swap::instruction::CreateFarm {
+            bump,
+            emission_rate,
+        })
+        .signer(admin)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "CreateFarm: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+
+    farm
+}
+
+// The admin funds the farm from its own reward token account
+#[throws(Error)]
+pub fn fund_farm_impl(program: &Program, pool: Pubkey, admin: &Keypair, amount: f64) {
+    let farm = Farm::address(&gfx_swap::ID, &pool);
+    let farm_account: Farm = program.account(farm)?;
+    let decimals = program.account::<Mint>(farm_account.reward_mint)?.decimals;
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::FundFarm {
+            pool,
+            farm,
+            reward_vault: farm_account.reward_vault,
+            funder_reward_ata: get_associated_token_address(
+                &admin.pubkey(),
+                &farm_account.reward_mint,
+            ),
+            funder: admin.pubkey(),
+            admin: admin.pubkey(),
+            token_program: spl_token::id(),
+        })
+        .accounts(approvals(&[admin]))
+        .args(gfx_swap::instruction::FundFarm {
+            amount: decimals.apply(amount),
+        })
+        .signer(admin)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "FundFarm: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn set_emission_rate_impl(
+    program: &Program,
+    pool: Pubkey,
+    admin: &Keypair,
+    emission_rate: u64,
+) {
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::SetEmissionRate {
+            pool,
+            farm: Farm::address(&gfx_swap::ID, &pool),
+            admin: admin.pubkey(),
+        })
+        .accounts(approvals(&[admin]))
+        .args(gfx_swap::instruction::SetEmissionRate { emission_rate })
+        .signer(admin)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "SetEmissionRate: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn create_farm_stake_impl(program: &Program, farm: Pubkey, owner: &Keypair) -> Pubkey {
+    let (stake, bump) = FarmStake::address_with_bump(&gfx_swap::ID, &farm, &owner.pubkey());
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::CreateFarmStake {
+            farm,
+            stake,
+            owner: owner.pubkey(),
+            rent: sysvar::rent::id(),
+            system_program: system_program::id(),
+        })
+        .args(gfx_swap::instruction::CreateFarmStake { bump })
+        .signer(owner)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "CreateFarmStake: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+
+    stake
+}
+
+fn stake_lp_accounts(
+    program: &Program,
+    farm: Pubkey,
+    owner: &Keypair,
+) -> Result<gfx_swap::accounts::StakeLp, Error> {
+    let farm_account: Farm = program.account(farm)?;
+    let pool_account: Pool = program.account(farm_account.pool)?;
+
+    Ok(gfx_swap::accounts::StakeLp {
+        farm,
+        stake: FarmStake::address(&gfx_swap::ID, &farm, &owner.pubkey()),
+        lp_vault: farm_account.lp_vault,
+        owner_lp_ata: get_associated_token_address(&owner.pubkey(), &pool_account.mint),
+        owner: owner.pubkey(),
+        token_program: spl_token::id(),
+    })
+}
+
+#[throws(Error)]
+pub fn stake_lp_impl(program: &Program, farm: Pubkey, owner: &Keypair, amount: u64) {
+    let tx = program
+        .request()
+        .accounts(stake_lp_accounts(program, farm, owner)?)
+        .args(gfx_swap::instruction::StakeLp { amount })
+        .signer(owner)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "StakeLp: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn unstake_lp_impl(program: &Program, farm: Pubkey, owner: &Keypair, amount: u64) {
+    let tx = program
+        .request()
+        .accounts(stake_lp_accounts(program, farm, owner)?)
+        .args(gfx_swap::instruction::UnstakeLp { amount })
+        .signer(owner)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "UnstakeLp: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+// The owner receives the rewards in its associated token account, which has to exist
+#[throws(Error)]
+pub fn claim_rewards_impl(program: &Program, farm: Pubkey, owner: &Keypair) {
+    let farm_account: Farm = program.account(farm)?;
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::ClaimRewards {
+            farm,
+            stake: FarmStake::address(&gfx_swap::ID, &farm, &owner.pubkey()),
+            reward_vault: farm_account.reward_vault,
+            owner_reward_ata: get_associated_token_address(
+                &owner.pubkey(),
+                &farm_account.reward_mint,
+            ),
+            owner: owner.pubkey(),
+            token_program: spl_token::id(),
+        })
+        .args(gfx_swap::instruction::ClaimRewards {})
+        .signer(owner)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "ClaimRewards: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+#[throws(Error)]
+pub fn lock_lp_impl(
+    program: &Program,
+    pool: Pubkey,
+    owner: &Keypair,
+    id: u64,
+    amount: u64,
+    cliff_duration: i64,
+    vesting_duration: i64,
+) -> Pubkey {
+    let pool_account: Pool = program.account(pool)?;
+    let (position, bump) =
+        LockedPosition::address_with_bump(&gfx_swap::ID, &pool, &owner.pubkey(), id);
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::LockLp {
+            pool,
+            position,
+            lp_token_mint: pool_account.mint,
+            escrow: get_associated_token_address(&position, &pool_account.mint),
+            owner_lp_ata: get_associated_token_address(&owner.pubkey(), &pool_account.mint),
+            owner: owner.pubkey(),
+            associated_token_program: spl_associated_token_account::id(),
+            token_program: spl_token::id(),
+            rent: sysvar::rent::id(),
+            system_program: system_program::id(),
+        })
+        .args(gfx_swap::instruction::LockLp {
+            id,
+            bump,
+            amount,
+            cliff_duration,
+            vesting_duration,
+        })
+        .signer(owner)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "LockLp: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+
+    position
+}
+
+#[throws(Error)]
+pub fn unlock_lp_impl(program: &Program, position: Pubkey, owner: &Keypair) {
+    let position_account: LockedPosition = program.account(position)?;
+
+    let tx = program
+        .request()
+        .accounts(gfx_swap::accounts::UnlockLp {
+            position,
+            escrow: get_associated_token_address(&position, &position_account.lp_mint),
+            lp_token_mint: position_account.lp_mint,
+            owner_lp_ata: get_associated_token_address(&owner.pubkey(), &position_account.lp_mint),
+            owner: owner.pubkey(),
+            token_program: spl_token::id(),
+        })
+        .args(gfx_swap::instruction::UnlockLp {})
+        .signer(owner)
+        .send()
+        .map_err(|e| e.canonicalize::<ErrorCode>())?;
+
+    println!(
+        "UnlockLp: https://explorer.solana.com/tx/{}?cluster=devnet",
+        tx
+    );
+}
+
+// Lists every `LockedPosition` of the pool, the way anyone can verify the liquidity locked in it
+#[throws(Error)]
+pub fn lock_summary_impl(program: &Program, pool: Pubkey) -> LockSummary {
+    let rpc_client = program.rpc();
+    let size = 8 + LockedPosition::default().try_to_vec()?.len();
+
+    let accounts = rpc_client.get_program_accounts_with_config(
+        &gfx_swap::ID,
+        RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::DataSize(size as u64),
+                // the pool is the first field, after the discriminator
+                RpcFilterType::Memcmp(Memcmp {
+                    offset: 8,
+                    bytes: MemcmpEncodedBytes::Base58(pool.to_string()),
+                    encoding: None,
+                }),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )?;
+    let positions = accounts
+        .into_iter()
+        .map(|(_, account)| LockedPosition::try_deserialize(&mut &account.data[..]))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let now = rpc_client.get_block_time(rpc_client.get_slot()?)?;
+    LockSummary::new(&pool, &positions, now)?
+}
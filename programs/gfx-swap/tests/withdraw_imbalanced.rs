@@ -0,0 +1,101 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anchor_spl::token::{Mint, TokenAccount};
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, LPMint, Pool};
+use serial_test::serial;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Keypair, signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn withdraw_imbalanced() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    mint_to(token_a, admin, user.pubkey(), 1000)?;
+    mint_to(token_b, admin, user.pubkey(), 1000)?;
+
+    // used for bootstrap
+    mint_to(token_a, admin, admin.pubkey(), 1)?;
+    mint_to(token_b, admin, admin.pubkey(), 1)?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        true,
+    )?;
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 100.)?;
+
+    let balance = |owner: Pubkey, token: Pubkey| -> Result<u64, Error> {
+        let ata: TokenAccount = program.account(get_associated_token_address(&owner, &token))?;
+        Ok(ata.amount)
+    };
+    let lp_supply = || -> Result<u64, Error> { Ok(program.account::<Mint>(lp_mint)?.supply) };
+
+    // burning fewer LP tokens than the withdrawal is worth
+    assert!(utils::withdraw_imbalanced_impl(
+        &program, pool, lp_mint, token_a, token_b, user, 10., 0., 1,
+    )
+    .is_err());
+
+    let (user_a, user_b) = (
+        balance(user.pubkey(), token_a)?,
+        balance(user.pubkey(), token_b)?,
+    );
+    let (reserve_a, reserve_b) = (balance(pool, token_a)?, balance(pool, token_b)?);
+    let supply = lp_supply()?;
+
+    utils::withdraw_imbalanced_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        user,
+        10.,
+        0.,
+        u64::MAX,
+    )?;
+
+    // the user gets exactly the asked amounts
+    let withdrawn_a = balance(user.pubkey(), token_a)? - user_a;
+    assert_eq!(balance(user.pubkey(), token_b)?, user_b);
+    assert_eq!(reserve_a - balance(pool, token_a)?, withdrawn_a);
+    assert_eq!(balance(pool, token_b)?, reserve_b);
+
+    // and pays the imbalance fee, burning more than its share of the reserves
+    let burnt = supply - lp_supply()?;
+    assert!((burnt as u128) * (reserve_a as u128) > (withdrawn_a as u128) * (supply as u128));
+}
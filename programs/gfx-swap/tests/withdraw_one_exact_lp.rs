@@ -0,0 +1,106 @@
+mod utils;
+
+use anchor_client::{Client, Cluster};
+use anchor_spl::token::{Mint, TokenAccount};
+use anyhow::Error;
+use fehler::throws;
+use gfx_solana_utils::{admin_wallet, create_token, mint_to, user_wallet, Duplicate};
+use gfx_swap::{DerivedAccountIdentifier, LPMint, Pool};
+use serial_test::serial;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Keypair, signature::Signer,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+#[throws(Error)]
+#[serial]
+#[test]
+fn withdraw_one_exact_lp() {
+    let _ = env_logger::try_init();
+
+    let admin = admin_wallet(1.)?;
+    let user = user_wallet(1.)?;
+
+    let client = Client::new_with_options(
+        Cluster::Devnet,
+        admin.clone(),
+        CommitmentConfig::processed(),
+    );
+    let program = client.program(gfx_swap::ID);
+
+    let token_a = create_token(&admin)?;
+    let token_b = create_token(&admin)?;
+
+    mint_to(token_a, admin, user.pubkey(), 1000)?;
+    mint_to(token_b, admin, user.pubkey(), 1000)?;
+
+    // used for bootstrap
+    mint_to(token_a, admin, admin.pubkey(), 1)?;
+    mint_to(token_b, admin, admin.pubkey(), 1)?;
+
+    // seed for creating the pool
+    let seed = Keypair::new().pubkey();
+
+    let (pool, pool_bump) = Pool::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+    let (lp_mint, lp_bump) = LPMint::get_address_with_bump(&gfx_swap::ID, &seed.to_bytes());
+
+    utils::create_pool_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        &admin,
+        seed.to_bytes(),
+        pool_bump,
+        lp_bump,
+        true,
+    )?;
+    utils::deposit2_impl(&program, pool, lp_mint, token_a, token_b, user, 100.)?;
+
+    let balance = |owner: Pubkey, token: Pubkey| -> Result<u64, Error> {
+        let ata: TokenAccount = program.account(get_associated_token_address(&owner, &token))?;
+        Ok(ata.amount)
+    };
+
+    // asking for more than the pool tokens are worth
+    assert!(utils::withdraw_one_exact_lp_impl(
+        &program,
+        pool,
+        lp_mint,
+        token_a,
+        token_b,
+        token_a,
+        user,
+        10.,
+        u64::MAX,
+    )
+    .is_err());
+
+    let user_lp = balance(user.pubkey(), lp_mint)?;
+    let (user_a, user_b) = (
+        balance(user.pubkey(), token_a)?,
+        balance(user.pubkey(), token_b)?,
+    );
+    let (reserve_a, reserve_b) = (balance(pool, token_a)?, balance(pool, token_b)?);
+    let supply = program.account::<Mint>(lp_mint)?.supply;
+
+    utils::withdraw_one_exact_lp_impl(
+        &program, pool, lp_mint, token_a, token_b, token_a, user, 10., 1,
+    )?;
+
+    // exactly the given LP tokens are taken, all of the output is token A
+    let lp_decimals = program.account::<Mint>(lp_mint)?.decimals;
+    let lp_amount = 10 * 10u64.pow(lp_decimals as u32);
+    assert_eq!(user_lp - balance(user.pubkey(), lp_mint)?, lp_amount);
+    let withdrawn_a = balance(user.pubkey(), token_a)? - user_a;
+    assert_eq!(reserve_a - balance(pool, token_a)?, withdrawn_a);
+    assert_eq!(balance(user.pubkey(), token_b)?, user_b);
+    assert_eq!(balance(pool, token_b)?, reserve_b);
+
+    // it is more than the A side of a balanced withdrawal, but less than both sides together
+    assert!((withdrawn_a as u128) * (supply as u128) > (lp_amount as u128) * (reserve_a as u128));
+    assert!(
+        (withdrawn_a as u128) * (supply as u128) < 2 * (lp_amount as u128) * (reserve_a as u128)
+    );
+}
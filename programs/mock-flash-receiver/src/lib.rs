@@ -0,0 +1,124 @@
+// A flash loan receiver for the tests of gfx-swap. It implements `on_flash_loan`, see `gfx_swap::OnFlashLoan`.
+// The first byte of `data` picks what the receiver does with the loan, it repays from the borrower's accounts.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke};
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use fehler::{throw, throws};
+
+pub use program_id::*;
+
+#[cfg(not(feature = "ci"))]
+mod program_id {
+    use anchor_lang::prelude::*;
+    declare_id!("9YS1wmmPqRSznzEH64GLtFjThQ8YCbuAJ1hdaoNuBmpQ");
+}
+
+#[cfg(feature = "ci")]
+include!(concat!(env!("OUT_DIR"), "/program_id.rs"));
+
+// repay the loan and the fees
+pub const REPAY: u8 = 0;
+// repay the loan but not the fees
+pub const REPAY_PRINCIPAL: u8 = 1;
+// call the instruction in `data[1..]` before repaying, the first remaining account is its program
+// and the others are its accounts
+pub const REENTER: u8 = 2;
+
+#[program]
+pub mod mock_flash_receiver {
+    use super::*;
+
+    #[throws(ProgramError)]
+    pub fn on_flash_loan(
+        ctx: Context<OnFlashLoan>,
+        amount_1: u64,
+        amount_2: u64,
+        fee_1: u64,
+        fee_2: u64,
+        data: Vec<u8>,
+    ) {
+        let mode = *data.first().ok_or(ProgramError::InvalidInstructionData)?;
+
+        let (fee_1, fee_2) = match mode {
+            REPAY | REENTER => (fee_1, fee_2),
+            REPAY_PRINCIPAL => (0, 0),
+            _ => throw!(ProgramError::InvalidInstructionData),
+        };
+
+        if mode == REENTER {
+            let (program, accounts) = ctx
+                .remaining_accounts
+                .split_first()
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let ix = Instruction {
+                program_id: program.key(),
+                accounts: accounts
+                    .iter()
+                    .map(|acc| AccountMeta {
+                        pubkey: acc.key(),
+                        is_signer: acc.is_signer,
+                        is_writable: acc.is_writable,
+                    })
+                    .collect(),
+                data: data[1..].to_vec(),
+            };
+            invoke(&ix, ctx.remaining_accounts)?;
+        }
+
+        let accounts = &ctx.accounts;
+        accounts.repay(
+            &accounts.borrower_token_1,
+            &accounts.token_vault_1,
+            &accounts.token_program_1,
+            amount_1 + fee_1,
+        )?;
+        accounts.repay(
+            &accounts.borrower_token_2,
+            &accounts.token_vault_2,
+            &accounts.token_program_2,
+            amount_2 + fee_2,
+        )?;
+    }
+}
+
+#[derive(Accounts)]
+pub struct OnFlashLoan<'info> {
+    pub borrower: Signer<'info>,
+    #[account(mut)]
+    pub borrower_token_1: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub borrower_token_2: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub token_vault_1: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub token_vault_2: Account<'info, TokenAccount>,
+    // the mock only repays mints of the legacy token program
+    pub token_mint_1: UncheckedAccount<'info>,
+    pub token_mint_2: UncheckedAccount<'info>,
+    pub token_program_1: Program<'info, Token>,
+    pub token_program_2: Program<'info, Token>,
+}
+
+impl<'info> OnFlashLoan<'info> {
+    #[throws(ProgramError)]
+    fn repay(
+        &self,
+        from: &Account<'info, TokenAccount>,
+        to: &Account<'info, TokenAccount>,
+        token_program: &Program<'info, Token>,
+        amount: u64,
+    ) {
+        if amount == 0 {
+            return;
+        }
+
+        let cpi_accounts = Transfer {
+            from: from.to_account_info(),
+            to: to.to_account_info(),
+            authority: self.borrower.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+    }
+}
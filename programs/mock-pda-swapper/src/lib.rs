@@ -0,0 +1,91 @@
+// A program that swaps on gfx-swap on behalf of its PDA, for the tests of gfx-swap.
+// The PDA owns the token accounts and signs the CPI with its seeds, for gfx-swap it is the user wallet.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use fehler::throws;
+
+pub use program_id::*;
+
+#[cfg(not(feature = "ci"))]
+mod program_id {
+    use anchor_lang::prelude::*;
+    declare_id!("7efvgusAhfpfcyCHnAxkt9RQK9iVqXgT1JCfnGskVKxX");
+}
+
+#[cfg(feature = "ci")]
+include!(concat!(env!("OUT_DIR"), "/program_id.rs"));
+
+pub const VAULT_SEED: &[u8] = b"vault";
+
+pub fn vault_address_with_bump() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_SEED], &ID)
+}
+
+#[program]
+pub mod mock_pda_swapper {
+    use super::*;
+
+    #[throws(ProgramError)]
+    pub fn swap_as_vault(
+        ctx: Context<SwapAsVault>,
+        vault_bump: u8,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) {
+        let accounts = &ctx.accounts;
+
+        let cpi_accounts = gfx_swap::cpi::accounts::Swap {
+            pool: accounts.pool.to_account_info(),
+            global_config: accounts.global_config.to_account_info(),
+            in_token_vault: accounts.in_token_vault.to_account_info(),
+            out_token_vault: accounts.out_token_vault.to_account_info(),
+            lp_token_mint: accounts.lp_token_mint.to_account_info(),
+            fee_vault: accounts.fee_vault.to_account_info(),
+            in_token_ata_user: accounts.in_token_ata_vault.to_account_info(),
+            out_token_ata_user: accounts.out_token_ata_vault.to_account_info(),
+            user_wallet: accounts.vault.to_account_info(),
+            allowlist_entry: accounts.allowlist_entry.to_account_info(),
+            system_program: accounts.system_program.to_account_info(),
+            token_program: accounts.token_program.to_account_info(),
+        };
+        let seeds: &[&[u8]] = &[VAULT_SEED, &[vault_bump]];
+        let signer = &[seeds];
+        let cpi_ctx = CpiContext::new_with_signer(
+            accounts.gfx_swap_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        gfx_swap::cpi::swap(cpi_ctx, amount_in, minimum_amount_out)?;
+    }
+}
+
+// The accounts of `gfx_swap::Swap` with the vault PDA as the user wallet, gfx-swap checks them.
+#[derive(Accounts)]
+#[instruction(vault_bump: u8)]
+pub struct SwapAsVault<'info> {
+    #[account(mut, seeds = [VAULT_SEED], bump = vault_bump)]
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool: UncheckedAccount<'info>,
+    pub global_config: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub in_token_vault: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub out_token_vault: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub lp_token_mint: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub fee_vault: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub in_token_ata_vault: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub out_token_ata_vault: UncheckedAccount<'info>,
+    pub allowlist_entry: UncheckedAccount<'info>,
+
+    #[account(constraint = gfx_swap_program.key() == gfx_swap::ID)]
+    pub gfx_swap_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
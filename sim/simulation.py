@@ -149,6 +149,31 @@ class Curve:
         self.x[j] = (y + fee) * 10 ** 18 // self.p[j]
         return dy - fee
 
+    def add_liquidity(self, amounts):
+        _fee = self.fee * self.n // (4 * (self.n - 1))
+
+        old_balances = self.x
+        new_balances = self.x[:]
+        D0 = self.D()
+        for i in range(self.n):
+            new_balances[i] += amounts[i]
+        self.x = new_balances
+        D1 = self.D()
+        self.x = old_balances
+        fees = [0] * self.n
+        for i in range(self.n):
+            ideal_balance = D1 * old_balances[i] // D0
+            difference = abs(ideal_balance - new_balances[i])
+            fees[i] = _fee * difference // 10 ** 10
+            new_balances[i] -= fees[i]
+        self.x = new_balances
+        D2 = self.D()
+        self.x = old_balances
+
+        mint_amount = (D2 - D0) * self.tokens // D0
+
+        return mint_amount
+
     def remove_liquidity_imbalance(self, amounts):
         _fee = self.fee * self.n // (4 * (self.n - 1))
 